@@ -0,0 +1,89 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""
+Admin Router - Runtime controls used by the Tauri shell.
+
+Endpoints:
+- GET /admin/loglevel: Current log level
+- PUT /admin/loglevel: Change the log level until the next restart
+- POST /admin/backup: Consistent copy of the database, for the shell's backups
+"""
+
+import sqlite3
+import uuid
+from contextlib import closing
+
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel
+import structlog
+
+from app.core import log_level
+from app.core.config import settings
+
+router = APIRouter()
+logger = structlog.get_logger(__name__)
+
+
+class LogLevelBody(BaseModel):
+    """Log level, e.g. "debug"."""
+    level: str
+
+
+@router.get("/admin/loglevel", response_model=LogLevelBody)
+async def get_log_level():
+    """Return the log level in effect."""
+    return LogLevelBody(level=log_level.current())
+
+
+@router.put("/admin/loglevel", response_model=LogLevelBody)
+async def set_log_level(body: LogLevelBody):
+    """Change the log level; LOG_LEVEL applies again after a restart."""
+    try:
+        level = log_level.apply(body.level)
+    except ValueError as e:
+        raise HTTPException(status_code=422, detail=str(e))
+    logger.info("Log level changed", level=level)
+    return LogLevelBody(level=level)
+
+
+class BackupBody(BaseModel):
+    """Where the database copy was written, and its size."""
+    path: str
+    bytes: int
+
+
+@router.post("/admin/backup", response_model=BackupBody)
+def backup_database():
+    """Copy the database into TEMP_DIR with SQLite's backup API.
+
+    The copy is consistent even while requests write to the database. The caller
+    deletes it once done.
+    """
+    source_path = settings.DATA_SUBDIR / "invoices.db"
+    if not source_path.exists():
+        raise HTTPException(status_code=404, detail="No database yet")
+    settings.TEMP_DIR.mkdir(parents=True, exist_ok=True)
+    destination = settings.TEMP_DIR / f"backup-{uuid.uuid4().hex}.db"
+    try:
+        with closing(sqlite3.connect(source_path)) as source, \
+                closing(sqlite3.connect(destination)) as target:
+            source.backup(target)
+    except sqlite3.Error as e:
+        destination.unlink(missing_ok=True)
+        logger.error("Database backup failed", error=str(e))
+        raise HTTPException(status_code=500, detail=f"Database backup failed: {e}")
+    size = destination.stat().st_size
+    logger.info("Database backed up", path=str(destination), bytes=size)
+    return BackupBody(path=str(destination), bytes=size)
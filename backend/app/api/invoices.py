@@ -19,26 +19,32 @@ Endpoints:
 - POST /analyze: Step 1 - Analyze document quality
 - POST /process: Step 2 - Process with chosen pipeline
 - GET /jobs/{id}/status: Check job status
+- GET /jobs/{id}/events: Job status as server-sent events
+- GET /invoices/search: Search invoices by vendor, amount and date
 - POST /cleanup: Manual cleanup of expired jobs
 - GET /cleanup/stats: Temp directory statistics
 - CRUD operations for invoices and other documents
 """
 
+import asyncio
+import json
 import os
 import shutil
 import structlog
-from datetime import datetime
-from typing import Any, Dict, List, Literal
+from datetime import date, datetime
+from typing import Any, Dict, List, Literal, Optional
 
-from fastapi import APIRouter, Depends, File, HTTPException, UploadFile
-from fastapi.responses import FileResponse
+from fastapi import APIRouter, Depends, File, HTTPException, Query, UploadFile
+from fastapi.responses import FileResponse, StreamingResponse
 from fastapi.concurrency import run_in_threadpool
 from pydantic import BaseModel, Field
+from sqlalchemy import func, or_
 from sqlalchemy.orm import Session
 
 from app.core.config import settings
 from app.db.base import SessionLocal, get_db
 from app.models.invoice import Invoice, InvoiceLine, OtherDocument
+from app.services import active_jobs
 from app.services.analysis_service import AnalysisService
 from app.services.processing_service import ProcessingService
 from app.services.cleanup_service import CleanupService
@@ -105,6 +111,12 @@ class InvoiceResponse(BaseModel):
         return cls(**data)
 
 
+class InvoiceSearchResponse(BaseModel):
+    """One page of search results and how many invoices match in all"""
+    items: List[InvoiceResponse]
+    total: int
+
+
 class OtherDocumentResponse(BaseModel):
     id: int
     provider: str | None
@@ -238,12 +250,27 @@ def _is_allowed_file(filename: str) -> bool:
     return ext in ALLOWED_EXTENSIONS
 
 
+# Invoice dates are stored as extracted, in the document's own format
+INVOICE_DATE_FORMATS = ('%Y-%m-%d', '%d/%m/%Y', '%d.%m.%Y', '%d-%m-%Y', '%Y/%m/%d')
+
+
+def _parse_invoice_date(value: str | None) -> date | None:
+    """The calendar date of a stored invoice date, if it is in a known format"""
+    for fmt in INVOICE_DATE_FORMATS:
+        try:
+            return datetime.strptime((value or '').strip(), fmt).date()
+        except ValueError:
+            continue
+    return None
+
+
 def _analyze_document_in_thread(file_path: str, original_filename: str) -> Dict[str, Any]:
     """Thread-safe document analysis with database persistence"""
     db = SessionLocal()
     try:
         analysis_service = AnalysisService()
-        job = analysis_service.analyze_document(file_path, original_filename, db)
+        with active_jobs.track("analyze", filename=original_filename):
+            job = analysis_service.analyze_document(file_path, original_filename, db)
 
         # Check Claude availability
         claude_available, claude_configured = analysis_service.check_claude_availability(db)
@@ -263,9 +290,10 @@ def _process_job_in_thread(
     db = SessionLocal()
     try:
         processing_service = ProcessingService()
-        return processing_service.process_job(
-            job_id, pipeline, save_to_db, db, user_preference
-        )
+        with active_jobs.track("process", job_id=job_id):
+            return processing_service.process_job(
+                job_id, pipeline, save_to_db, db, user_preference
+            )
     finally:
         db.close()
 
@@ -397,6 +425,50 @@ async def get_job_status(job_id: str, db: Session = Depends(get_db)):
     return JobStatusResponse(**result)
 
 
+JOB_EVENTS_POLL_SECONDS = 1.0
+# A comment line this often lets clients notice a dead connection
+JOB_EVENTS_KEEPALIVE_SECONDS = 15.0
+TERMINAL_JOB_STATUSES = {"completed", "failed", "expired"}
+
+
+@router.get("/jobs/{job_id}/events")
+async def job_events(job_id: str):
+    """
+    Stream the job's status as server-sent events.
+
+    Sends the status once, then again whenever it changes, and ends once
+    the job is completed, failed, expired or not found.
+    """
+    processing_service = ProcessingService()
+
+    async def stream():
+        last = None
+        idle = 0.0
+        while True:
+            db = SessionLocal()
+            try:
+                result = processing_service.get_job_status(job_id, db)
+            finally:
+                db.close()
+            if result != last:
+                yield f"data: {json.dumps(result)}\n\n"
+                last = result
+                idle = 0.0
+            elif idle >= JOB_EVENTS_KEEPALIVE_SECONDS:
+                yield ": keepalive\n\n"
+                idle = 0.0
+            if not result.get("found") or result.get("status") in TERMINAL_JOB_STATUSES:
+                return
+            await asyncio.sleep(JOB_EVENTS_POLL_SECONDS)
+            idle += JOB_EVENTS_POLL_SECONDS
+
+    return StreamingResponse(
+        stream(),
+        media_type="text/event-stream",
+        headers={"Cache-Control": "no-cache"},
+    )
+
+
 @router.get("/jobs/{job_id}/image")
 async def get_job_image(job_id: str, page: int = 0, db: Session = Depends(get_db)):
     """
@@ -504,12 +576,74 @@ async def get_temp_dir_stats():
 # =========================================================================
 
 @router.get("/invoices", response_model=List[InvoiceResponse])
-def get_invoices(skip: int = 0, limit: int = 100, db: Session = Depends(get_db)):
-    """Get list of invoices"""
-    invoices = db.query(Invoice).offset(skip).limit(limit).all()
+def get_invoices(
+    skip: int = 0,
+    limit: int = 100,
+    q: Optional[str] = None,
+    db: Session = Depends(get_db)
+):
+    """Get list of invoices, optionally only those whose provider, number or file name contains q"""
+    query = db.query(Invoice)
+    if q:
+        pattern = f"%{q}%"
+        query = query.filter(or_(
+            Invoice.provider.ilike(pattern),
+            Invoice.invoice_number.ilike(pattern),
+            Invoice.original_filename.ilike(pattern),
+        ))
+    # Stable order so pages don't overlap
+    invoices = query.order_by(Invoice.id).offset(skip).limit(limit).all()
     return [InvoiceResponse.from_orm_with_document_check(inv) for inv in invoices]
 
 
+@router.get("/invoices/search", response_model=InvoiceSearchResponse)
+def search_invoices(
+    vendor: Optional[str] = None,
+    min_amount: Optional[float] = Query(None, ge=0),
+    max_amount: Optional[float] = Query(None, ge=0),
+    date_from: Optional[date] = None,
+    date_to: Optional[date] = None,
+    skip: int = Query(0, ge=0),
+    limit: int = Query(100, ge=1, le=500),
+    db: Session = Depends(get_db)
+):
+    """
+    Search invoices by provider, total amount and invoice date.
+
+    Amounts are compared with the total including VAT, or without VAT when that is
+    all the invoice has. Invoices whose date can't be parsed never match a date range.
+    """
+    query = db.query(Invoice)
+    if vendor:
+        query = query.filter(Invoice.provider.ilike(f"%{vendor}%"))
+    amount = func.coalesce(Invoice.total_with_vat, Invoice.total_without_vat)
+    if min_amount is not None:
+        query = query.filter(amount >= min_amount)
+    if max_amount is not None:
+        query = query.filter(amount <= max_amount)
+    query = query.order_by(Invoice.id)
+
+    if date_from is None and date_to is None:
+        total = query.count()
+        invoices = query.offset(skip).limit(limit).all()
+    else:
+        # Stored dates are free text, so the range is checked here rather than in SQL
+        def in_range(invoice: Invoice) -> bool:
+            invoice_date = _parse_invoice_date(invoice.date)
+            return invoice_date is not None \
+                and (date_from is None or invoice_date >= date_from) \
+                and (date_to is None or invoice_date <= date_to)
+
+        matching = [inv for inv in query.all() if in_range(inv)]
+        total = len(matching)
+        invoices = matching[skip:skip + limit]
+
+    return InvoiceSearchResponse(
+        items=[InvoiceResponse.from_orm_with_document_check(inv) for inv in invoices],
+        total=total,
+    )
+
+
 @router.get("/invoices/{invoice_id}", response_model=InvoiceResponse)
 def get_invoice(invoice_id: int, db: Session = Depends(get_db)):
     """Get specific invoice by ID"""
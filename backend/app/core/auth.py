@@ -0,0 +1,92 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+
+"""
+Launch token authentication.
+
+The Tauri shell generates a fresh secret for every backend it spawns and passes it
+in INVOICATOR_LAUNCH_TOKEN. When set, every request must carry it as
+`Authorization: Bearer <token>`, or as a `token` query parameter for URLs the
+webview loads directly (document and page images). Without it (development),
+requests are not authenticated.
+"""
+
+import hmac
+import logging
+import os
+import re
+from typing import Optional
+
+from fastapi import Request
+from fastapi.responses import JSONResponse
+
+LAUNCH_TOKEN_ENV = "INVOICATOR_LAUNCH_TOKEN"
+TOKEN_QUERY_PARAM = "token"
+
+# Root /health stays open so the shell can recognise a backend on a busy port;
+# it only echoes the token to callers that already know it.
+PUBLIC_PATHS = {"/health"}
+
+_TOKEN_IN_QUERY = re.compile(rf"([?&]{TOKEN_QUERY_PARAM}=)[^&\s]*")
+
+
+def launch_token() -> Optional[str]:
+    """The token this backend was started with, if any."""
+    return os.environ.get(LAUNCH_TOKEN_ENV) or None
+
+
+def request_token(request: Request) -> Optional[str]:
+    """The token presented by a request, from the header or the query string."""
+    header = request.headers.get("authorization", "")
+    scheme, _, value = header.partition(" ")
+    if scheme.lower() == "bearer" and value:
+        return value.strip()
+    return request.query_params.get(TOKEN_QUERY_PARAM)
+
+
+def is_authorized(request: Request) -> bool:
+    """Whether the request carries the launch token, or none is required."""
+    expected = launch_token()
+    if expected is None:
+        return True
+    presented = request_token(request)
+    return presented is not None and hmac.compare_digest(presented, expected)
+
+
+async def require_launch_token(request: Request, call_next):
+    """HTTP middleware rejecting requests without the launch token."""
+    if (
+        request.method == "OPTIONS"
+        or request.url.path in PUBLIC_PATHS
+        or is_authorized(request)
+    ):
+        return await call_next(request)
+    return JSONResponse(
+        status_code=401,
+        content={"detail": "Missing or invalid launch token"},
+        headers={"WWW-Authenticate": "Bearer"},
+    )
+
+
+class RedactTokenFilter(logging.Filter):
+    """Scrub the `token` query parameter from uvicorn access log lines."""
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        if isinstance(record.args, tuple):
+            record.args = tuple(
+                _TOKEN_IN_QUERY.sub(r"\1<redacted>", arg) if isinstance(arg, str) else arg
+                for arg in record.args
+            )
+        return True
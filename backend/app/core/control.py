@@ -0,0 +1,111 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""
+Control messages from the Tauri shell, read from stdin.
+
+One JSON object per line with a string "cmd", e.g. {"cmd": "flush"}. They are
+handled on a thread of their own, so they get through while every HTTP worker is
+busy with OCR. Commands:
+
+- reload-config: re-read the encryption key metadata and API keys, rotating the
+  key if it is due, as at startup
+- flush: free cached memory and flush the log streams
+- set-log-level: apply "level", as PUT /api/v1/admin/loglevel does
+"""
+
+import gc
+import json
+import sys
+import threading
+from typing import Callable, Dict, TextIO
+
+import structlog
+
+from app.core import log_level
+
+logger = structlog.get_logger(__name__)
+
+
+def reload_config(message: dict) -> None:
+    from app.db.base import SessionLocal
+    from app.services.api_key_service import ApiKeyService
+
+    service = ApiKeyService()
+    db = SessionLocal()
+    try:
+        rotated = service.check_and_perform_key_rotation(db)
+        configured = service.get_all_status(db).get("anthropic", {}).get("configured")
+    finally:
+        db.close()
+    logger.info("Configuration reloaded", key_rotated=rotated, anthropic_configured=configured)
+
+
+def flush(message: dict) -> None:
+    collected = gc.collect()
+    # Only if a model already loaded it: importing torch here would take seconds.
+    torch = sys.modules.get("torch")
+    if torch is not None and torch.cuda.is_available():
+        torch.cuda.empty_cache()
+    sys.stdout.flush()
+    sys.stderr.flush()
+    logger.info("Caches flushed", objects_collected=collected)
+
+
+def set_log_level(message: dict) -> None:
+    level = log_level.apply(str(message.get("level", "")))
+    logger.info("Log level changed", level=level)
+
+
+COMMANDS: Dict[str, Callable[[dict], None]] = {
+    "reload-config": reload_config,
+    "flush": flush,
+    "set-log-level": set_log_level,
+}
+
+
+def dispatch(line: str) -> bool:
+    """Run the command on `line`. Returns whether it was recognised and succeeded."""
+    line = line.strip()
+    if not line:
+        return False
+    try:
+        message = json.loads(line)
+    except ValueError as e:
+        logger.warning("Ignoring malformed control message", error=str(e))
+        return False
+    cmd = message.get("cmd") if isinstance(message, dict) else None
+    handler = COMMANDS.get(cmd) if isinstance(cmd, str) else None
+    if handler is None:
+        logger.warning("Ignoring unknown control command", cmd=cmd)
+        return False
+    try:
+        handler(message)
+    except Exception as e:
+        logger.error("Control command failed", cmd=cmd, error=str(e))
+        return False
+    return True
+
+
+def start_reader(stream: TextIO) -> threading.Thread:
+    """Dispatch each line of `stream` until it ends, on a daemon thread."""
+
+    def read():
+        for line in stream:
+            dispatch(line)
+        logger.debug("Control channel closed")
+
+    thread = threading.Thread(target=read, name="control-reader", daemon=True)
+    thread.start()
+    return thread
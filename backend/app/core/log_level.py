@@ -0,0 +1,76 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""
+Log verbosity of the backend, set from LOG_LEVEL at launch and changeable while running.
+
+The Tauri shell passes its own level in LOG_LEVEL and forwards later changes to
+PUT /api/v1/admin/loglevel, so debug output can be turned on for a support session
+without a restart.
+"""
+
+import logging
+import os
+
+import structlog
+
+# "trace" is the shell's most verbose level; Python has nothing below debug.
+LEVELS = {
+    "error": logging.ERROR,
+    "warn": logging.WARNING,
+    "warning": logging.WARNING,
+    "info": logging.INFO,
+    "debug": logging.DEBUG,
+    "trace": logging.DEBUG,
+}
+DEFAULT = "info"
+
+_current = DEFAULT
+
+
+def current() -> str:
+    """Name of the level in effect."""
+    return _current
+
+
+def apply(name: str) -> str:
+    """Filter structlog and the standard loggers (uvicorn's included) at `name`.
+
+    Raises ValueError for an unknown level. Returns the normalized name.
+    """
+    global _current
+    name = name.strip().lower()
+    # uvicorn only knows the long spelling.
+    name = "warning" if name == "warn" else name
+    if name not in LEVELS:
+        raise ValueError(f"unknown log level {name!r}, expected one of {', '.join(LEVELS)}")
+    level = LEVELS[name]
+    # structlog.configure only replaces what it is given, so the processors stay.
+    structlog.configure(wrapper_class=structlog.make_filtering_bound_logger(level))
+    logging.getLogger().setLevel(level)
+    for logger_name in ("uvicorn", "uvicorn.error", "uvicorn.access"):
+        logging.getLogger(logger_name).setLevel(level)
+    _current = name
+    return name
+
+
+def apply_from_env() -> str:
+    """Apply LOG_LEVEL, falling back to info when it is unset or unknown."""
+    name = os.environ.get("LOG_LEVEL") or DEFAULT
+    try:
+        return apply(name)
+    except ValueError:
+        apply(DEFAULT)
+        structlog.get_logger(__name__).warning("Ignoring unknown LOG_LEVEL", value=name)
+        return DEFAULT
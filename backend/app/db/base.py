@@ -12,7 +12,7 @@
 # See the License for the specific language governing permissions and
 # limitations under the License.
 
-from sqlalchemy import create_engine
+from sqlalchemy import create_engine, inspect
 from sqlalchemy.ext.declarative import declarative_base
 from sqlalchemy.orm import sessionmaker
 from app.core.config import settings
@@ -42,6 +42,29 @@ def init_db(reset: bool = False):
     Base.metadata.create_all(bind=engine)
 
 
+def missing_schema() -> list[str]:
+    """Tables ("table") and columns ("table.column") the models have and the database
+    lacks; empty once the schema is current.
+
+    create_all adds missing tables but never columns, so a database from an older
+    version that skipped a migration shows up here.
+    """
+    from app.models.invoice import Invoice, InvoiceLine, OtherDocument
+    from app.models.analysis_job import AnalysisJob
+    from app.models.api_key import ApiKey
+    inspector = inspect(engine)
+    missing = []
+    for table in Base.metadata.sorted_tables:
+        if not inspector.has_table(table.name):
+            missing.append(table.name)
+            continue
+        present = {column["name"] for column in inspector.get_columns(table.name)}
+        missing.extend(
+            f"{table.name}.{column.name}" for column in table.columns if column.name not in present
+        )
+    return missing
+
+
 def clear_db():
     """Clear all data from all tables"""
     from app.models.invoice import Invoice, InvoiceLine, OtherDocument
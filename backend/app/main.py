@@ -19,16 +19,21 @@ Main application module that configures and runs the FastAPI server.
 """
 
 from contextlib import asynccontextmanager
-from fastapi import FastAPI
+from fastapi import FastAPI, HTTPException, Request
 from fastapi.middleware.cors import CORSMiddleware
+from fastapi.middleware.gzip import GZipMiddleware
+from fastapi.responses import JSONResponse
+from sqlalchemy import text
 import structlog
 
 from app.core.config import settings
-from app.db.base import init_db, SessionLocal
-from app.api import invoices, api_keys, health
+from app.core.auth import require_launch_token, is_authorized, launch_token
+from app.db.base import init_db, missing_schema, SessionLocal
+from app.api import invoices, api_keys, health, admin
 from app.services.model_manager import initialize_models
 from app.services.cleanup_service import CleanupService
 from app.services.api_key_service import ApiKeyService
+from app.services import active_jobs
 
 # Configure logging
 structlog.configure(
@@ -136,6 +141,20 @@ app = FastAPI(
     lifespan=lifespan
 )
 
+class CompressResponses(GZipMiddleware):
+    """GZip for clients that accept it, except server-sent events, which the
+    compressor would hold back until its buffer fills."""
+
+    async def __call__(self, scope, receive, send):
+        if scope["type"] == "http" and scope["path"].endswith("/events"):
+            await self.app(scope, receive, send)
+            return
+        await super().__call__(scope, receive, send)
+
+
+# Launch token check, registered first so CORS headers still reach rejected requests
+app.middleware("http")(require_launch_token)
+
 # CORS middleware
 app.add_middleware(
     CORSMiddleware,
@@ -145,10 +164,14 @@ app.add_middleware(
     allow_headers=["*"],
 )
 
+# Large invoice lists compress well; small answers aren't worth it
+app.add_middleware(CompressResponses, minimum_size=1024)
+
 # Include routers
 app.include_router(health.router, prefix="/api/v1", tags=["health"])
 app.include_router(invoices.router, prefix="/api/v1", tags=["invoices"])
 app.include_router(api_keys.router, prefix="/api/v1", tags=["api-keys"])
+app.include_router(admin.router, prefix="/api/v1", tags=["admin"])
 
 
 @app.get("/")
@@ -163,6 +186,63 @@ async def root():
 
 # Keep /health at root level for simple health checks
 @app.get("/health")
-async def health_check():
-    """Simple health check endpoint at root level."""
-    return {"status": "healthy"}
+async def health_check(request: Request):
+    """Simple health check endpoint at root level.
+
+    Echoes the launch token to callers that sent it, so the Tauri shell can tell
+    this backend apart from an unrelated server on the same port.
+    """
+    token = launch_token() if is_authorized(request) else None
+    return {"status": "healthy", "launch_token": token}
+
+
+@app.get("/ready")
+def readiness():
+    """Deep readiness check, polled by the Tauri shell once /health answers.
+
+    `db` is "ok" once a query through a session succeeds, `migrations` is "done"
+    once the schema has every table and column the models expect. 503 until both.
+    """
+    db = SessionLocal()
+    try:
+        db.execute(text("SELECT 1"))
+        db_status = "ok"
+    except Exception as e:
+        logger.warning("Readiness check: database unavailable", error=str(e))
+        db_status = "unavailable"
+    finally:
+        db.close()
+
+    body = {"db": db_status, "migrations": "unknown"}
+    if db_status == "ok":
+        missing = missing_schema()
+        body["migrations"] = "pending" if missing else "done"
+        if missing:
+            body["missing"] = missing
+    ready = body["db"] == "ok" and body["migrations"] == "done"
+    return JSONResponse(status_code=200 if ready else 503, content=body)
+
+
+@app.get("/jobs/active")
+async def jobs_active():
+    """Analyze and process calls still running, which quitting now would cut off.
+
+    Polled by the Tauri shell before it stops the backend.
+    """
+    return {"jobs": active_jobs.running()}
+
+
+@app.post("/shutdown")
+async def shutdown(request: Request):
+    """Stop the server, used by the Tauri shell when the app quits.
+
+    uvicorn stops accepting connections, lets in-flight requests finish and runs
+    the lifespan shutdown before the process exits. Like every path but /health,
+    this needs the launch token. 503 when not started through run_server.py.
+    """
+    server = getattr(request.app.state, "uvicorn_server", None)
+    if server is None:
+        raise HTTPException(status_code=503, detail="Server not started by run_server.py")
+    logger.info("Shutdown requested")
+    server.should_exit = True
+    return {"status": "shutting_down"}
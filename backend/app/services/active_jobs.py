@@ -0,0 +1,58 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""
+Registry of the analyze and process calls running right now, for GET /jobs/active.
+
+The Tauri shell asks before quitting so a batch in the middle of OCR isn't killed
+without warning. Kept in memory rather than read from analysis_jobs: a row left
+"processing" by a crash is not running any more.
+"""
+
+import itertools
+import threading
+from contextlib import contextmanager
+from datetime import datetime
+from typing import Any, Dict, Iterator, List, Optional
+
+_lock = threading.Lock()
+_keys = itertools.count()
+_running: Dict[int, Dict[str, Any]] = {}
+
+
+@contextmanager
+def track(
+    kind: str, job_id: Optional[str] = None, filename: Optional[str] = None
+) -> Iterator[None]:
+    """Register the work done inside the block as a running job of `kind`."""
+    key = next(_keys)
+    entry = {
+        "kind": kind,
+        "job_id": job_id,
+        "filename": filename,
+        "started_at": datetime.utcnow().isoformat(),
+    }
+    with _lock:
+        _running[key] = entry
+    try:
+        yield
+    finally:
+        with _lock:
+            del _running[key]
+
+
+def running() -> List[Dict[str, Any]]:
+    """The jobs running now, oldest first."""
+    with _lock:
+        return [dict(entry) for _, entry in sorted(_running.items())]
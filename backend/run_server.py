@@ -19,10 +19,16 @@ Entry point for the Invoice Processor backend server.
 
 This script is the main entry point for PyInstaller packaging.
 It can be run directly in development or bundled as an executable.
+Stdin carries control messages from the desktop shell, one JSON object per
+line; see app/core/control.py.
 
 Environment variables:
     DATA_DIR: Base directory for all application data (required in production)
     PORT: Server port (default: 8000)
+    INVOICATOR_LAUNCH_TOKEN: Secret every request must present (set by the desktop shell)
+    UDS_PATH: Also listen on this Unix domain socket (macOS/Linux), used by the desktop shell
+    SSL_CERTFILE, SSL_KEYFILE: Serve HTTPS with this certificate and key (set by the
+        desktop shell when loopback TLS is on)
     MODEL_CACHE_DIR: Override model cache location
     DEBUG: Enable debug mode (default: false)
 
@@ -34,9 +40,12 @@ Usage:
     DATA_DIR=/path/to/data python run_server.py
 """
 
+import logging
+import os
 import sys
 import shutil
 import signal
+import socket
 import structlog
 
 # Configure logging before anything else
@@ -48,6 +57,10 @@ structlog.configure(
     ]
 )
 
+from app.core import log_level
+
+LOG_LEVEL = log_level.apply_from_env()
+
 logger = structlog.get_logger("run_server")
 
 
@@ -118,6 +131,7 @@ def main():
 
     # Import the FastAPI app
     from app.main import app
+    from app.core.auth import RedactTokenFilter
 
     # Setup signal handlers for graceful shutdown
     def signal_handler(signum, frame):
@@ -130,13 +144,63 @@ def main():
     # Run the server
     logger.info("Starting uvicorn server", host=settings.HOST, port=settings.PORT)
 
-    uvicorn.run(
+    ssl_certfile = os.environ.get("SSL_CERTFILE") or None
+    ssl_keyfile = os.environ.get("SSL_KEYFILE") or None
+    if ssl_certfile:
+        logger.info("Serving HTTPS", certfile=ssl_certfile)
+
+    config = uvicorn.Config(
         app,
         host=settings.HOST,
         port=settings.PORT,
-        log_level="debug" if settings.DEBUG else "info",
-        access_log=settings.DEBUG
+        log_level="debug" if settings.DEBUG else LOG_LEVEL,
+        access_log=settings.DEBUG,
+        # Outlives the shell's 90s idle pool, so its health checks reuse one connection
+        timeout_keep_alive=120,
+        # Inside the shell's 5s wait after POST /shutdown, so an open event stream
+        # can't hold the exit up
+        timeout_graceful_shutdown=4,
+        ssl_certfile=ssl_certfile,
+        ssl_keyfile=ssl_keyfile,
     )
+    # The shell writes control messages to stdin; see app/core/control.py
+    from app.core import control
+    if sys.stdin is not None:
+        control.start_reader(sys.stdin)
+
+    # Image and document URLs carry the launch token in their query string
+    logging.getLogger("uvicorn.access").addFilter(RedactTokenFilter())
+    sockets = []
+    uds_path = os.environ.get("UDS_PATH")
+    if uds_path and hasattr(socket, "AF_UNIX"):
+        # Bound before TCP: the shell takes an open port without a socket file
+        # to mean this backend has no socket support.
+        sockets.append(bind_unix_socket(uds_path))
+    sockets.append(config.bind_socket())
+
+    server = uvicorn.Server(config)
+    # POST /shutdown sets should_exit, the same flag uvicorn's own signal handlers set
+    app.state.uvicorn_server = server
+    try:
+        server.run(sockets=sockets)
+    finally:
+        if uds_path and os.path.exists(uds_path):
+            os.unlink(uds_path)
+
+
+def bind_unix_socket(path):
+    """Listen on a Unix domain socket only the current user can connect to."""
+    if os.path.exists(path):
+        os.unlink(path)
+    sock = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    old_umask = os.umask(0o177)
+    try:
+        sock.bind(path)
+    finally:
+        os.umask(old_umask)
+    sock.listen(2048)
+    logger.info("Listening on Unix socket", path=path)
+    return sock
 
 
 if __name__ == "__main__":
@@ -0,0 +1,76 @@
+# Copyright 2026 Floriane TUERNAL SABOTINOV
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+"""
+Round trip of the stdin control channel: lines written the way the shell's
+`control::ControlChannel` writes them reach their handlers.
+
+Run from backend/: python -m unittest discover tests
+"""
+
+import json
+import os
+import unittest
+from unittest import mock
+
+from app.core import control, log_level
+
+
+class ControlChannelTest(unittest.TestCase):
+    def test_messages_reach_their_handlers_until_the_pipe_closes(self):
+        received = []
+        commands = {
+            "reload-config": lambda message: received.append(message),
+            "flush": lambda message: received.append(message),
+        }
+        read_fd, write_fd = os.pipe()
+        with mock.patch.dict(control.COMMANDS, commands, clear=True), \
+                os.fdopen(read_fd) as stdin:
+            reader = control.start_reader(stdin)
+            with os.fdopen(write_fd, "w") as stdout:
+                # serde_json output: compact, one message per line
+                for message in [
+                    {"cmd": "reload-config"},
+                    "not json",
+                    {"cmd": "unknown"},
+                    {"no": "cmd"},
+                    {"cmd": "flush", "reason": "low memory"},
+                ]:
+                    if not isinstance(message, str):
+                        message = json.dumps(message, separators=(",", ":"))
+                    stdout.write(message + "\n")
+            reader.join(timeout=5)
+
+        self.assertFalse(reader.is_alive(), "reader should stop at end of input")
+        self.assertEqual(
+            received,
+            [{"cmd": "reload-config"}, {"cmd": "flush", "reason": "low memory"}],
+        )
+
+    def test_a_failing_command_does_not_stop_the_channel(self):
+        def fail(message):
+            raise RuntimeError("database locked")
+
+        with mock.patch.dict(control.COMMANDS, {"reload-config": fail}, clear=True):
+            self.assertFalse(control.dispatch('{"cmd": "reload-config"}\n'))
+
+    def test_set_log_level(self):
+        self.assertTrue(control.dispatch('{"cmd": "set-log-level", "level": "debug"}'))
+        self.assertEqual(log_level.current(), "debug")
+        self.assertFalse(control.dispatch('{"cmd": "set-log-level", "level": "loud"}'))
+        control.dispatch('{"cmd": "set-log-level", "level": "info"}')
+
+
+if __name__ == "__main__":
+    unittest.main()
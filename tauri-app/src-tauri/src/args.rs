@@ -0,0 +1,70 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extra command-line arguments power users can pass to the backend.
+
+/// Flags the app sets itself; letting users override them would break the connection.
+const MANAGED_FLAGS: &[&str] = &["--port", "--host", "--data-dir"];
+/// Environment variables the app sets itself.
+const MANAGED_ENV: &[&str] = &["DATA_DIR", "PORT", "HOST"];
+/// Name fragments of flags and settings whose values are hidden in logs and bundles.
+pub(crate) const SECRET_HINTS: &[&str] = &["token", "secret", "password", "passwd", "key", "auth"];
+
+/// Reject arguments that would override the port, host or data dir the app manages.
+pub(crate) fn validate(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        let flag = arg.split('=').next().unwrap_or(arg);
+        if MANAGED_FLAGS.contains(&flag) {
+            return Err(format!(
+                "{flag} is managed by the app and cannot be overridden"
+            ));
+        }
+        if MANAGED_ENV
+            .iter()
+            .any(|var| arg.starts_with(&format!("{var}=")))
+        {
+            return Err(format!(
+                "{arg:?} would override an environment variable managed by the app"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_secret_flag(flag: &str) -> bool {
+    let flag = flag.to_ascii_lowercase();
+    flag.starts_with('-') && SECRET_HINTS.iter().any(|hint| flag.contains(hint))
+}
+
+/// `argv` joined for logging, with the values of secret-looking flags replaced by `***`.
+pub(crate) fn redacted(argv: &[String]) -> String {
+    let mut out = Vec::with_capacity(argv.len());
+    let mut hide_next = false;
+    for arg in argv {
+        if hide_next {
+            out.push("***".to_string());
+            hide_next = false;
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((flag, _)) if is_secret_flag(flag) => out.push(format!("{flag}=***")),
+            None if is_secret_flag(arg) => {
+                out.push(arg.clone());
+                hide_next = true;
+            }
+            _ => out.push(arg.clone()),
+        }
+    }
+    out.join(" ")
+}
@@ -0,0 +1,46 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Per-launch secret the backend requires on every request.
+//!
+//! A fresh token is generated for each spawn, handed to the backend through
+//! `AUTH_TOKEN_ENV` and sent back as `Authorization: Bearer`. It is kept in memory only:
+//! never logged, never written to the PID file.
+
+use std::sync::Mutex;
+
+/// Env var carrying the token to the backend.
+pub(crate) const AUTH_TOKEN_ENV: &str = "INVOICATOR_LAUNCH_TOKEN";
+const TOKEN_BYTES: usize = 32;
+
+/// Token of the current backend; `None` until the first spawn, and in dev mode.
+static TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+/// `len` random bytes from the OS's CSPRNG, hex encoded.
+pub(crate) fn random_hex(len: usize) -> Result<String, String> {
+    let mut bytes = vec![0u8; len];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate random bytes: {e}"))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Replace the token with a fresh one for the backend about to be spawned.
+pub(crate) fn rotate() -> Result<String, String> {
+    let token = random_hex(TOKEN_BYTES)?;
+    *TOKEN.lock().unwrap() = Some(token.clone());
+    Ok(token)
+}
+
+/// The token the current backend expects.
+pub(crate) fn token() -> Option<String> {
+    TOKEN.lock().unwrap().clone()
+}
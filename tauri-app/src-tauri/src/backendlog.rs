@@ -0,0 +1,663 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Backend output kept in `backend.log` in the app log dir, rotated by size.
+//!
+//! The monitor hands lines over without waiting, since the shell plugin holds the pipes
+//! until each event is taken. Lines queue in memory for a writer thread; when it falls
+//! behind, the oldest are dropped and a marker says how many. The writer lives as long as
+//! the app, so one file covers every backend launch.
+//!
+//! While a console panel is open, lines are also sent to the webview as `backend-log`,
+//! batched and capped so a runaway traceback loop can't flood it. The last
+//! `BACKLOG_LINES` are kept in memory as well, for a console to start from.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::loglevel::LogLevel;
+use crate::logrecord::BackendLogRecord;
+use crate::{tls, unix_millis};
+
+const FILE_NAME: &str = "backend.log";
+pub(crate) const DEFAULT_MAX_MB: u64 = 5;
+/// Files kept, the live one included: backend.log, backend.log.1 and backend.log.2.
+pub(crate) const DEFAULT_MAX_FILES: u32 = 3;
+const MAX_SIZE_LIMIT_MB: u64 = 1024;
+const MAX_FILES_LIMIT: u32 = 100;
+const MAX_AGE_LIMIT_DAYS: u64 = 3650;
+/// Lines waiting for the writer at most.
+const QUEUE_LINES: usize = 10_000;
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often forwarded lines go out as one `backend-log` event.
+const FORWARD_INTERVAL: Duration = Duration::from_millis(100);
+/// Lines per `backend-log` event at most, i.e. 500 a second; the rest are counted only.
+const FORWARD_BATCH_LINES: usize = 50;
+/// Most lines `get_backend_logs` returns.
+pub(crate) const MAX_TAIL_LINES: usize = 10_000;
+/// Read backwards from the end of a log file in steps of this many bytes.
+const TAIL_CHUNK: u64 = 64 * 1024;
+/// Records kept in memory across backend launches, for `get_log_backlog`.
+const BACKLOG_LINES: usize = 2000;
+/// Lines in `recent_output` at most.
+const RECENT_LINES: usize = 50;
+/// Bytes in `recent_output` at most, so a runaway line can't make a huge event.
+const RECENT_BYTES: usize = 16 * 1024;
+/// Start of the note `started` writes, which `recent_output` stops at.
+const STARTED_NOTE: &str = "backend started";
+
+#[derive(Default)]
+struct Queue {
+    lines: VecDeque<String>,
+    /// Dropped since the writer last took the queue.
+    dropped: u64,
+    flush_requested: u64,
+    flushed: u64,
+}
+
+/// The queue between the monitor and the writer thread, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct BackendLog {
+    queue: Mutex<Queue>,
+    /// Wakes the writer.
+    pending: Condvar,
+    /// Wakes `flush` callers.
+    written: Condvar,
+    /// Set once the writer runs; lines are ignored before and if it couldn't start.
+    running: AtomicBool,
+    forwarding: AtomicBool,
+    /// Bumped by each `set_forwarding`, so a stale forwarding task knows to stop.
+    forward_epoch: AtomicU64,
+    forward: Mutex<LogBatch>,
+    /// Least severe level forwarded; `None` forwards everything.
+    forward_min: Mutex<Option<LogLevel>>,
+    /// Label of the only window `backend-log` goes to; `None` sends it to all of them.
+    forward_to: Mutex<Option<String>>,
+    /// The latest records, oldest first.
+    backlog: Mutex<VecDeque<BackendLogRecord>>,
+    retention: Mutex<Retention>,
+}
+
+/// How much backend output is kept on disk, from the `log_max_*` settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Retention {
+    /// Size at which the live file is rotated.
+    pub max_size_mb: u64,
+    /// Files kept, the live one included.
+    pub max_files: u32,
+    /// Rotated files older than this are deleted; `None` keeps them by count only.
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            max_size_mb: DEFAULT_MAX_MB,
+            max_files: DEFAULT_MAX_FILES,
+            max_age_days: None,
+        }
+    }
+}
+
+impl Retention {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_SIZE_LIMIT_MB).contains(&self.max_size_mb) {
+            return Err(format!(
+                "Log size must be between 1 and {MAX_SIZE_LIMIT_MB} MB, got {}",
+                self.max_size_mb
+            ));
+        }
+        if !(1..=MAX_FILES_LIMIT).contains(&self.max_files) {
+            return Err(format!(
+                "Log file count must be between 1 and {MAX_FILES_LIMIT}, got {}",
+                self.max_files
+            ));
+        }
+        if let Some(days) = self
+            .max_age_days
+            .filter(|d| !(1..=MAX_AGE_LIMIT_DAYS).contains(d))
+        {
+            return Err(format!(
+                "Log age must be between 1 and {MAX_AGE_LIMIT_DAYS} days, got {days}"
+            ));
+        }
+        Ok(())
+    }
+
+    fn max_bytes(&self) -> u64 {
+        self.max_size_mb.max(1) * 1024 * 1024
+    }
+}
+
+/// Payload of the `backend-log` event.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct LogBatch {
+    pub lines: Vec<BackendLogRecord>,
+    /// Lines left out of this batch to keep the rate down; they are still in the file.
+    pub dropped: u64,
+}
+
+/// Which of the backend's streams `get_backend_logs` returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of `get_backend_logs`.
+#[derive(Debug, Serialize)]
+pub(crate) struct LogTail {
+    /// The live log file; older lines may come from its rotated copies next to it.
+    pub path: PathBuf,
+    /// Oldest first.
+    pub records: Vec<BackendLogRecord>,
+}
+
+/// Where backend output is written.
+pub(crate) fn path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map(|dir| dir.join(FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app log dir: {e}"))
+}
+
+/// Delete rotated files beyond `retention`, then start the writer thread.
+pub(crate) fn start(app: &AppHandle, retention: Retention) {
+    let path = match path(app) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("Backend output is not logged: {}", e);
+            return;
+        }
+    };
+    *app.state::<BackendLog>().retention.lock().unwrap() = retention;
+    prune(&path, retention);
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let file = fs::create_dir_all(&dir).and_then(|_| open(&path));
+    let (file, size) = match file {
+        Ok(opened) => opened,
+        Err(e) => {
+            log::warn!("Backend output is not logged: {}: {}", path.display(), e);
+            return;
+        }
+    };
+    let handle = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("backend-log".to_string())
+        .spawn(move || {
+            let log = handle.state::<BackendLog>();
+            Writer {
+                path,
+                file,
+                size,
+                retention,
+            }
+            .run(&log);
+        });
+    match spawned {
+        Ok(_) => app
+            .state::<BackendLog>()
+            .running
+            .store(true, Ordering::Release),
+        Err(e) => log::warn!("Backend output is not logged: {}", e),
+    }
+}
+
+/// Use `retention` from the next rotation on.
+pub(crate) fn set_retention(app: &AppHandle, retention: Retention) {
+    *app.state::<BackendLog>().retention.lock().unwrap() = retention;
+}
+
+/// Queue one line the backend wrote to `stream`, stamped with the time it arrived, keep
+/// it in the backlog and forward it if the console panel is open.
+pub(crate) fn line(app: &AppHandle, stream: &'static str, text: &str) {
+    let now = SystemTime::now();
+    push(app, format!("{} [{stream}] {text}\n", timestamp(now)));
+
+    let log = app.state::<BackendLog>();
+    let record = BackendLogRecord::parse(stream, text, unix_millis(now));
+    remember(&log, record.clone());
+    if log.forwarding.load(Ordering::Relaxed) {
+        if log
+            .forward_min
+            .lock()
+            .unwrap()
+            .is_some_and(|min| !record.at_least(min))
+        {
+            return;
+        }
+        let mut batch = log.forward.lock().unwrap();
+        if batch.lines.len() < FORWARD_BATCH_LINES {
+            batch.lines.push(record);
+        } else {
+            batch.dropped += 1;
+        }
+    }
+}
+
+fn remember(log: &BackendLog, record: BackendLogRecord) {
+    let mut backlog = log.backlog.lock().unwrap();
+    if backlog.len() == BACKLOG_LINES {
+        backlog.pop_front();
+    }
+    backlog.push_back(record);
+}
+
+/// The records kept in memory, oldest first. Unlike `tail`, this never touches the disk.
+pub(crate) fn backlog(app: &AppHandle) -> Vec<BackendLogRecord> {
+    app.state::<BackendLog>()
+        .backlog
+        .lock()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Send `backend-log` only to the window labelled `label`, or to every window if `None`.
+pub(crate) fn set_target(app: &AppHandle, label: Option<String>) {
+    *app.state::<BackendLog>().forward_to.lock().unwrap() = label;
+}
+
+/// Start or stop sending backend output as `backend-log` events, only records at least as
+/// severe as `min_level` if given. Off at launch.
+pub(crate) fn set_forwarding(app: &AppHandle, enabled: bool, min_level: Option<LogLevel>) {
+    let log = app.state::<BackendLog>();
+    let epoch = log.forward_epoch.fetch_add(1, Ordering::Relaxed) + 1;
+    *log.forward_min.lock().unwrap() = min_level;
+    log.forwarding.store(enabled, Ordering::Relaxed);
+    *log.forward.lock().unwrap() = LogBatch::default();
+    if !enabled {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let log = app.state::<BackendLog>();
+        while log.forward_epoch.load(Ordering::Relaxed) == epoch {
+            tokio::time::sleep(FORWARD_INTERVAL).await;
+            let batch = std::mem::take(&mut *log.forward.lock().unwrap());
+            if !batch.lines.is_empty() || batch.dropped > 0 {
+                let target = log.forward_to.lock().unwrap().clone();
+                let _ = match target {
+                    Some(label) => app.emit_to(label.as_str(), "backend-log", batch),
+                    None => app.emit("backend-log", batch),
+                };
+            }
+        }
+    });
+}
+
+/// The last lines the current backend printed, oldest first, as redacted as they were
+/// logged. At most `RECENT_LINES` and `RECENT_BYTES`; a line that would go over is cut.
+pub(crate) fn recent_output(app: &AppHandle) -> Vec<String> {
+    let log = app.state::<BackendLog>();
+    let backlog = log.backlog.lock().unwrap();
+    let mut budget = RECENT_BYTES;
+    let mut lines = Vec::new();
+    for record in backlog.iter().rev() {
+        if record.stream == "note" {
+            if record.line.starts_with(STARTED_NOTE) {
+                break;
+            }
+            continue;
+        }
+        if lines.len() == RECENT_LINES || budget == 0 {
+            break;
+        }
+        let mut line = record.line.clone();
+        if line.len() > budget {
+            let mut end = budget;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+        }
+        budget -= line.len();
+        lines.push(line);
+    }
+    lines.reverse();
+    lines
+}
+
+/// Mark the start of the backend with `pid` in the log.
+pub(crate) fn started(app: &AppHandle, pid: u32) {
+    note(app, &format!("{STARTED_NOTE}, pid {pid}"));
+}
+
+/// Queue a line of our own, e.g. that a backend started.
+pub(crate) fn note(app: &AppHandle, text: &str) {
+    let now = SystemTime::now();
+    push(app, format!("{} --- {text} ---\n", timestamp(now)));
+    remember(
+        &app.state::<BackendLog>(),
+        BackendLogRecord::note(text, unix_millis(now)),
+    );
+}
+
+fn push(app: &AppHandle, line: String) {
+    let log = app.state::<BackendLog>();
+    if !log.running.load(Ordering::Acquire) {
+        return;
+    }
+    let mut queue = log.queue.lock().unwrap();
+    if queue.lines.len() == QUEUE_LINES {
+        queue.lines.pop_front();
+        queue.dropped += 1;
+    }
+    queue.lines.push_back(line);
+    log.pending.notify_one();
+}
+
+/// Wait, at most `FLUSH_TIMEOUT`, until everything queued so far is on disk.
+pub(crate) fn flush(app: &AppHandle) {
+    let log = app.state::<BackendLog>();
+    if !log.running.load(Ordering::Acquire) {
+        return;
+    }
+    let mut queue = log.queue.lock().unwrap();
+    queue.flush_requested += 1;
+    let wanted = queue.flush_requested;
+    log.pending.notify_one();
+    let (_queue, timeout) = log
+        .written
+        .wait_timeout_while(queue, FLUSH_TIMEOUT, |q| q.flushed < wanted)
+        .unwrap();
+    if timeout.timed_out() {
+        log::warn!("Timed out writing the last backend output to disk");
+    }
+}
+
+struct Writer {
+    path: PathBuf,
+    file: BufWriter<File>,
+    size: u64,
+    /// As of the last batch; `set_retention` changes apply from the next one.
+    retention: Retention,
+}
+
+impl Writer {
+    fn run(mut self, log: &BackendLog) {
+        loop {
+            let (lines, dropped, flush) = {
+                let queue = log.queue.lock().unwrap();
+                let mut queue = log
+                    .pending
+                    .wait_while(queue, |q| {
+                        q.lines.is_empty() && q.dropped == 0 && q.flushed == q.flush_requested
+                    })
+                    .unwrap();
+                let lines = std::mem::take(&mut queue.lines);
+                let dropped = std::mem::take(&mut queue.dropped);
+                (lines, dropped, queue.flush_requested)
+            };
+            self.retention = *log.retention.lock().unwrap();
+            if dropped > 0 {
+                let marker = format!(
+                    "{} --- {dropped} lines dropped: the log writer fell behind ---\n",
+                    timestamp(SystemTime::now())
+                );
+                self.write(&marker);
+            }
+            for line in &lines {
+                self.write(line);
+            }
+            if let Err(e) = self.file.flush() {
+                log::debug!("Failed to flush {}: {}", self.path.display(), e);
+            }
+            let mut queue = log.queue.lock().unwrap();
+            queue.flushed = flush;
+            log.written.notify_all();
+        }
+    }
+
+    fn write(&mut self, line: &str) {
+        if self.size > 0 && self.size + line.len() as u64 > self.retention.max_bytes() {
+            self.rotate();
+        }
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+
+    /// backend.log becomes backend.log.1, .1 becomes .2 and so on; what falls outside the
+    /// retention policy goes.
+    fn rotate(&mut self) {
+        let _ = self.file.flush();
+        for n in (1..self.retention.max_files).rev() {
+            let _ = fs::rename(rotated(&self.path, n), rotated(&self.path, n + 1));
+        }
+        if let Err(e) = fs::rename(&self.path, rotated(&self.path, 1)) {
+            log::warn!("Failed to rotate {}: {}", self.path.display(), e);
+        }
+        prune(&self.path, self.retention);
+        match open(&self.path) {
+            Ok((file, size)) => {
+                self.file = file;
+                self.size = size;
+            }
+            Err(e) => log::warn!("Failed to reopen {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// The `n`th older copy of the log at `path`, e.g. backend.log.1.
+fn rotated(path: &Path, n: u32) -> PathBuf {
+    PathBuf::from(format!("{}.{n}", path.display()))
+}
+
+/// Delete rotated copies of `path` beyond `retention.max_files` or older than
+/// `retention.max_age_days`. The live file is left alone.
+fn prune(path: &Path, retention: Retention) {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(n) = file_name
+            .to_string_lossy()
+            .strip_prefix(&prefix)
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let expired = max_age.is_some_and(|max_age| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > max_age)
+        });
+        if n >= retention.max_files || expired {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                log::debug!("Failed to delete {}: {}", entry.path().display(), e);
+            }
+        }
+    }
+}
+
+/// The last `count` records of backend output, from `stream` only and at least as severe
+/// as `min_level` if given, once what is queued is on disk. Continues into the rotated
+/// files when the live one is short.
+pub(crate) fn tail(
+    app: &AppHandle,
+    count: usize,
+    stream: Option<Stream>,
+    min_level: Option<LogLevel>,
+) -> Result<LogTail, String> {
+    let path = path(app)?;
+    flush(app);
+    let wanted = stream.map(|stream| match stream {
+        Stream::Stdout => "stdout",
+        Stream::Stderr => "stderr",
+    });
+    let keep = |line: &str| {
+        parse_line(line).filter(|record| {
+            wanted.is_none_or(|wanted| record.stream == wanted)
+                && min_level.is_none_or(|min| record.at_least(min))
+        })
+    };
+
+    let max_files = app
+        .state::<BackendLog>()
+        .retention
+        .lock()
+        .unwrap()
+        .max_files;
+    let mut newest_first = Vec::new();
+    for n in 0..max_files {
+        let file = if n == 0 {
+            path.clone()
+        } else {
+            rotated(&path, n)
+        };
+        match read_backwards(&file, count - newest_first.len(), &keep, &mut newest_first) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+            Err(e) => return Err(format!("Failed to read {}: {e}", file.display())),
+        }
+        if newest_first.len() >= count {
+            break;
+        }
+    }
+    newest_first.reverse();
+    Ok(LogTail {
+        path,
+        records: newest_first,
+    })
+}
+
+/// A line of the log file as a record: "2026-01-31 14:05:09.123 [stderr] text", or a
+/// "--- text ---" marker. `None` for anything else.
+fn parse_line(line: &str) -> Option<BackendLogRecord> {
+    let mut parts = line.splitn(3, ' ');
+    let (date, time, rest) = (parts.next()?, parts.next()?, parts.next()?);
+    let ts = parse_timestamp(date, time)?;
+    for stream in ["stdout", "stderr"] {
+        if let Some(text) = rest
+            .strip_prefix('[')
+            .and_then(|r| r.strip_prefix(stream))
+            .and_then(|r| r.strip_prefix("] "))
+        {
+            return Some(BackendLogRecord::parse(stream, text, ts));
+        }
+    }
+    let note = rest.strip_prefix("--- ")?.strip_suffix(" ---")?;
+    Some(BackendLogRecord::note(note, ts))
+}
+
+/// `record` in the log file's format.
+pub(crate) fn format_line(record: &BackendLogRecord) -> String {
+    let time = timestamp(UNIX_EPOCH + Duration::from_millis(record.ts));
+    match record.stream {
+        "note" => format!("{time} --- {} ---", record.line),
+        stream => format!("{time} [{stream}] {}", record.line),
+    }
+}
+
+/// Inverse of `timestamp`: milliseconds since the Unix epoch.
+fn parse_timestamp(date: &str, time: &str) -> Option<u64> {
+    let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (clock, millis) = time.split_once('.')?;
+    let mut clock = clock.splitn(3, ':').map(str::parse::<u64>);
+    let (hour, minute, second) = (
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+        clock.next()?.ok()?,
+    );
+    let days = u64::try_from(tls::days_from_civil(i64::from(year), month, day)).ok()?;
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(secs * 1000 + millis.parse::<u64>().ok()?)
+}
+
+/// Push up to `count` lines of `path` that `keep` turns into a `T` onto `out`, newest
+/// first, reading from the end in `TAIL_CHUNK` steps so a large file is never loaded whole.
+fn read_backwards<T>(
+    path: &Path,
+    count: usize,
+    keep: &impl Fn(&str) -> Option<T>,
+    out: &mut Vec<T>,
+) -> std::io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut pos = file.metadata()?.len();
+    // Bytes from `pos` up to the start of the lines already taken.
+    let mut rest: Vec<u8> = Vec::new();
+    let mut found = 0;
+    let mut take = |bytes: &[u8], found: &mut usize| {
+        let line = String::from_utf8_lossy(bytes);
+        let line = line.trim_end_matches('\r');
+        if let Some(kept) = keep(line).filter(|_| !line.is_empty()) {
+            out.push(kept);
+            *found += 1;
+        }
+    };
+    while pos > 0 && found < count {
+        let step = TAIL_CHUNK.min(pos);
+        pos -= step;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk = vec![0; step as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&rest);
+        rest = chunk;
+        while let Some(newline) = rest.iter().rposition(|&b| b == b'\n') {
+            if found == count {
+                return Ok(());
+            }
+            take(&rest[newline + 1..], &mut found);
+            rest.truncate(newline);
+        }
+    }
+    if pos == 0 && found < count {
+        take(&rest, &mut found);
+    }
+    Ok(())
+}
+
+/// `path` opened for appending, and its current size.
+fn open(path: &Path) -> std::io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((BufWriter::new(file), size))
+}
+
+/// "2026-01-31 14:05:09.123" in UTC.
+pub(crate) fn timestamp(time: SystemTime) -> String {
+    let millis = unix_millis(time);
+    let secs = millis / 1000;
+    let (year, month, day) = tls::civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}.{:03}",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        millis % 1000
+    )
+}
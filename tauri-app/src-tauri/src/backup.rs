@@ -0,0 +1,272 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `backup_data`: the backend's data dir in one zip.
+//!
+//! The backend copies its database with SQLite's backup API (`/admin/backup`), so requests
+//! can keep writing meanwhile, and that copy goes into the zip instead of the live file and
+//! its journals. The other files are read as they are. The backend's `temp` folder, the
+//! `backups` folder and the shell's own files are left out. `manifest.json` comes first and
+//! records the app version, for a restore to check.
+
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::proxy::TimeoutClass;
+use crate::supervisor::Supervisor;
+use crate::{config, datadir, endpoint, support, unix_millis};
+
+const SNAPSHOT_PATH: &str = "/api/v1/admin/backup";
+/// The database, relative to `DATA_DIR`, with `/` separators as in the zip.
+pub(crate) const DATABASE_FILE: &str = "data/invoices.db";
+/// Files SQLite keeps next to the database; the snapshot has their contents.
+const DATABASE_SIDECARS: &[&str] = &["-wal", "-shm", "-journal"];
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+/// Bumped when the layout of the zip changes.
+pub(crate) const FORMAT: u32 = 1;
+/// The backend's scratch folder in `DATA_DIR`.
+pub(crate) const BACKEND_TEMP: &str = "temp";
+/// Where the app keeps its own backups in `DATA_DIR`, such as those `prepare_for_update`
+/// makes; never in a backup itself, nor replaced by a restore.
+pub(crate) const BACKUPS_DIR: &str = "backups";
+/// Zip entries under this prefix are the shell's files, kept for reference; a restore
+/// leaves them out.
+pub(crate) const SHELL_PREFIX: &str = "shell/";
+const PROGRESS_EVENT: &str = "backup-progress";
+/// Least time between two progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Manifest {
+    pub format: u32,
+    /// Version of the app that wrote the backup.
+    pub app_version: String,
+    pub created_ms: u64,
+    /// Files and bytes of data when the backup started, the manifest not included.
+    pub files: usize,
+    pub bytes: u64,
+    /// Top-level entries of `DATA_DIR` the backup holds, for one of only part of the data;
+    /// a restore replaces those and keeps the rest. `None`: all of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces: Option<Vec<String>>,
+}
+
+/// What `backup_data` wrote.
+#[derive(Debug, Serialize)]
+pub(crate) struct DataBackup {
+    pub path: PathBuf,
+    /// Files in the zip besides the manifest.
+    pub files: usize,
+    /// Their size before compression.
+    pub data_bytes: u64,
+    /// Size of the zip.
+    pub bytes: u64,
+}
+
+/// Payload of the `backup-progress` event.
+#[derive(Clone, Debug, Serialize)]
+struct BackupProgress {
+    written_bytes: u64,
+    total_bytes: u64,
+    files_done: usize,
+    files_total: usize,
+}
+
+/// The backend's answer from `/admin/backup`.
+#[derive(Deserialize)]
+struct Snapshot {
+    path: PathBuf,
+}
+
+fn skip(name: &OsStr) -> bool {
+    name == BACKEND_TEMP || name == BACKUPS_DIR || datadir::is_shell_entry(name)
+}
+
+/// `relative` with `/` separators, as zip entries are named.
+fn zip_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_database(name: &str) -> bool {
+    name == DATABASE_FILE
+        || DATABASE_SIDECARS
+            .iter()
+            .any(|suffix| name.strip_suffix(suffix) == Some(DATABASE_FILE))
+}
+
+/// Back up the data dir to `destination`, or into it under a dated name when it is a
+/// folder. Emits `backup-progress` while the zip is written.
+pub(crate) async fn backup(app: &AppHandle, destination: &Path) -> Result<DataBackup, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    let now = SystemTime::now();
+    let path = if destination.is_dir() {
+        destination.join(support::default_backup_name(now))
+    } else {
+        destination.to_path_buf()
+    };
+    // Keeps the data dir from being moved or restored under the backup.
+    let supervisor = app.state::<Supervisor>();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let data_dir = datadir::path(app)?;
+    let snapshot = snapshot(app, &data_dir).await?;
+
+    let handle = app.clone();
+    let version = app.package_info().version.to_string();
+    let written = {
+        let (path, snapshot) = (path.clone(), snapshot.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            write_backup(&handle, &data_dir, &snapshot, &path, now, version)
+        })
+        .await
+        .map_err(|e| format!("Backup failed: {e}"))
+    };
+    if let Err(e) = fs::remove_file(&snapshot) {
+        log::warn!("Failed to remove {}: {}", snapshot.display(), e);
+    }
+    let backup = written??;
+    log::info!(
+        "Backed up {} files ({} bytes) to {} ({} bytes)",
+        backup.files,
+        backup.data_bytes,
+        backup.path.display(),
+        backup.bytes
+    );
+    Ok(backup)
+}
+
+/// Have the backend copy its database, and return where the copy is. The caller deletes it.
+pub(crate) async fn snapshot(app: &AppHandle, data_dir: &Path) -> Result<PathBuf, String> {
+    let timeout = config::request_timeout(app, TimeoutClass::LongRunning);
+    let resp = endpoint::transport(app)
+        .post(SNAPSHOT_PATH, timeout)
+        .await
+        .map_err(|e| format!("The backend could not copy its database: {e}"))?;
+    if !resp.is_success() {
+        return Err(format!(
+            "The backend could not copy its database: HTTP {} {}",
+            resp.status,
+            resp.text()
+        ));
+    }
+    let snapshot = resp.json::<Snapshot>()?.path;
+    // Only ever read a file the backend put in its own temp folder.
+    let temp = data_dir.join(BACKEND_TEMP).canonicalize();
+    match (snapshot.canonicalize(), temp) {
+        (Ok(file), Ok(temp)) if file.starts_with(&temp) => Ok(file),
+        _ => Err(format!(
+            "The backend's database copy is not in its temp folder: {}",
+            snapshot.display()
+        )),
+    }
+}
+
+fn write_backup(
+    app: &AppHandle,
+    data_dir: &Path,
+    snapshot: &Path,
+    path: &Path,
+    now: SystemTime,
+    app_version: String,
+) -> Result<DataBackup, String> {
+    let tree = datadir::scan(data_dir, skip)?;
+    let database_bytes = fs::metadata(snapshot)
+        .map_err(|e| format!("Failed to read {}: {e}", snapshot.display()))?
+        .len();
+    // The zip being written may itself be inside the data dir.
+    let temp = support::temp_path(path)?;
+    let own = |file: &Path| file == path || file == temp;
+    let files: Vec<(String, PathBuf, u64)> = tree
+        .files
+        .iter()
+        .map(|(relative, size)| (zip_name(relative), data_dir.join(relative), *size))
+        .filter(|(name, full, _)| !is_database(name) && !own(full))
+        .collect();
+    let manifest = Manifest {
+        format: FORMAT,
+        app_version,
+        created_ms: unix_millis(now),
+        files: files.len() + 1,
+        bytes: database_bytes + files.iter().map(|(_, _, size)| size).sum::<u64>(),
+        replaces: None,
+    };
+
+    let mut progress = BackupProgress {
+        written_bytes: 0,
+        total_bytes: manifest.bytes,
+        files_done: 0,
+        files_total: manifest.files,
+    };
+    let mut reported = Instant::now();
+    let _ = app.emit(PROGRESS_EVENT, progress.clone());
+    let mut done = |progress: &mut BackupProgress, size: u64| {
+        progress.written_bytes += size;
+        progress.files_done += 1;
+        if progress.files_done == progress.files_total || reported.elapsed() >= PROGRESS_INTERVAL {
+            reported = Instant::now();
+            let _ = app.emit(PROGRESS_EVENT, progress.clone());
+        }
+    };
+
+    let mut written = 1;
+    let mut data_bytes = database_bytes;
+    let bytes = support::write_atomically(path, now, true, |zip| {
+        zip.entry(MANIFEST_FILE, |out| {
+            serde_json::to_writer_pretty(&mut *out, &manifest).map_err(io::Error::other)
+        })?;
+        zip.entry(DATABASE_FILE, |out| {
+            io::copy(&mut File::open(snapshot)?, out).map(|_| ())
+        })?;
+        done(&mut progress, database_bytes);
+        for (name, full, size) in &files {
+            // Only what was there when the backup started, even if it grows meanwhile.
+            let file = match File::open(full) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    log::warn!("Not backing up {}: deleted meanwhile", full.display());
+                    done(&mut progress, *size);
+                    continue;
+                }
+                Err(e) => return Err(io::Error::new(e.kind(), format!("{}: {e}", full.display()))),
+            };
+            zip.entry(name, |out| io::copy(&mut file.take(*size), out).map(|_| ()))?;
+            written += 1;
+            data_bytes += size;
+            done(&mut progress, *size);
+        }
+        Ok(())
+    })?;
+    Ok(DataBackup {
+        path: path.to_path_buf(),
+        files: written,
+        data_bytes,
+        bytes,
+    })
+}
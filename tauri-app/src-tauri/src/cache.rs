@@ -0,0 +1,421 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Cache of GET responses from read-heavy backend routes, for `backend_request`.
+//!
+//! Each route in `ROUTES` has its own time to live and names the paths whose mutations
+//! make it stale. The cache is bounded by entry count and total size, and evicts the least
+//! recently used entry first.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::transport::Response;
+
+const MAX_ENTRIES: usize = 256;
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+/// Larger responses are not cached, so one of them can't flush everything else.
+const MAX_ENTRY_BYTES: usize = MAX_BYTES / 8;
+
+/// A cacheable route; `*` in the pattern stands for one path segment. The first route in
+/// `ROUTES` that matches a path is the one used.
+struct Route {
+    pattern: &'static str,
+    ttl: Duration,
+    /// Successful non-GET calls to these paths, or below them, drop the route's entries.
+    invalidated_by: &'static [&'static str],
+}
+
+const ROUTES: &[Route] = &[
+    Route {
+        pattern: "/api/v1/api-keys/console-url",
+        ttl: Duration::from_secs(3600),
+        invalidated_by: &[],
+    },
+    Route {
+        pattern: "/api/v1/api-keys/status",
+        ttl: Duration::from_secs(30),
+        invalidated_by: &["/api/v1/api-keys"],
+    },
+    // Ahead of "/api/v1/invoices/*", which would match it too.
+    Route {
+        pattern: "/api/v1/invoices/search",
+        ttl: Duration::from_secs(10),
+        invalidated_by: &["/api/v1/invoices", "/api/v1/process"],
+    },
+    Route {
+        pattern: "/api/v1/invoices/*",
+        ttl: Duration::from_secs(30),
+        invalidated_by: &["/api/v1/invoices", "/api/v1/process"],
+    },
+    Route {
+        pattern: "/api/v1/other-documents",
+        ttl: Duration::from_secs(30),
+        invalidated_by: &["/api/v1/other-documents", "/api/v1/process"],
+    },
+    Route {
+        pattern: "/api/v1/other-documents/*",
+        ttl: Duration::from_secs(30),
+        invalidated_by: &["/api/v1/other-documents", "/api/v1/process"],
+    },
+    Route {
+        pattern: "/api/v1/cleanup/stats",
+        ttl: Duration::from_secs(10),
+        invalidated_by: &[
+            "/api/v1/analyze",
+            "/api/v1/cleanup",
+            "/api/v1/cleanup-force",
+            "/api/v1/jobs",
+            "/api/v1/process",
+        ],
+    },
+];
+
+struct Entry {
+    response: Response,
+    size: usize,
+    expires: Instant,
+    /// Value of `Cache::clock` when last served or stored.
+    last_used: u64,
+    invalidated_by: &'static [&'static str],
+}
+
+#[derive(Default)]
+struct Cache {
+    /// Keyed by path and query.
+    entries: HashMap<String, Entry>,
+    bytes: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl Cache {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.size;
+        }
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&str, &Entry) -> bool) -> usize {
+        let before = self.entries.len();
+        let mut freed = 0;
+        self.entries.retain(|key, entry| {
+            let kept = keep(key, entry);
+            if !kept {
+                freed += entry.size;
+            }
+            kept
+        });
+        self.bytes -= freed;
+        before - self.entries.len()
+    }
+
+    fn store(&mut self, path_and_query: &str, response: &Response) {
+        let Some(route) = route(path_and_query) else {
+            return;
+        };
+        let size = response.body.len()
+            + response
+                .headers
+                .iter()
+                .map(|(name, value)| name.len() + value.len())
+                .sum::<usize>();
+        if !response.is_success() || size > MAX_ENTRY_BYTES {
+            return;
+        }
+        self.remove(path_and_query);
+        self.clock += 1;
+        let entry = Entry {
+            response: response.clone(),
+            size,
+            expires: Instant::now() + route.ttl,
+            last_used: self.clock,
+            invalidated_by: route.invalidated_by,
+        };
+        self.entries.insert(path_and_query.to_string(), entry);
+        self.bytes += size;
+        while self.entries.len() > MAX_ENTRIES || self.bytes > MAX_BYTES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            let Some(oldest) = oldest else { break };
+            self.remove(&oldest);
+            self.evictions += 1;
+        }
+    }
+
+    fn invalidate_after(&mut self, path: &str) -> usize {
+        self.retain(|_, entry| {
+            !entry
+                .invalidated_by
+                .iter()
+                .any(|prefix| under(path, prefix))
+        })
+    }
+}
+
+/// Cached responses, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct ResponseCache(Mutex<Cache>);
+
+/// Payload of `get_cache_stats`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_entries: usize,
+    pub max_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits over lookups of cacheable routes, 0 before the first lookup.
+    pub hit_ratio: f64,
+    pub evictions: u64,
+}
+
+fn cache(app: &AppHandle) -> std::sync::MutexGuard<'_, Cache> {
+    app.state::<ResponseCache>().inner().0.lock().unwrap()
+}
+
+fn route(path_and_query: &str) -> Option<&'static Route> {
+    let path = path_and_query.split('?').next().unwrap_or_default();
+    ROUTES.iter().find(|route| {
+        let mut pattern = route.pattern.split('/');
+        let mut segments = path.split('/');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (None, None) => return true,
+                (Some("*"), Some(segment)) if !segment.is_empty() => {}
+                (Some(expected), Some(segment)) if expected == segment => {}
+                _ => return false,
+            }
+        }
+    })
+}
+
+/// Whether `path` is `prefix` or below it.
+pub(crate) fn under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with(['/', '?']))
+}
+
+/// A fresh cached response to `GET path_and_query`. Lookups of cacheable routes count as
+/// hits or misses.
+pub(crate) fn lookup(app: &AppHandle, path_and_query: &str) -> Option<Response> {
+    route(path_and_query)?;
+    let mut cache = cache(app);
+    cache.clock += 1;
+    let clock = cache.clock;
+    let fresh = match cache.entries.get_mut(path_and_query) {
+        Some(entry) if entry.expires > Instant::now() => {
+            entry.last_used = clock;
+            Some(entry.response.clone())
+        }
+        Some(_) => {
+            cache.remove(path_and_query);
+            None
+        }
+        None => None,
+    };
+    if fresh.is_some() {
+        cache.hits += 1;
+    } else {
+        cache.misses += 1;
+    }
+    fresh
+}
+
+/// Keep a successful answer to `GET path_and_query` if its route is cacheable.
+pub(crate) fn store(app: &AppHandle, path_and_query: &str, response: &Response) {
+    cache(app).store(path_and_query, response);
+}
+
+/// Drop the entries a successful non-GET call to `path` made stale.
+pub(crate) fn invalidate_after(app: &AppHandle, path: &str) {
+    let dropped = cache(app).invalidate_after(path);
+    if dropped > 0 {
+        log::debug!("{} dropped {} cached response(s)", path, dropped);
+    }
+}
+
+/// Drop the entries for `prefix` and the paths below it; "/" drops everything. Returns how
+/// many were dropped.
+pub(crate) fn invalidate(app: &AppHandle, prefix: &str) -> usize {
+    cache(app).retain(|key, _| !under(key, prefix))
+}
+
+pub(crate) fn stats(app: &AppHandle) -> CacheStats {
+    let cache = cache(app);
+    let lookups = cache.hits + cache.misses;
+    CacheStats {
+        entries: cache.entries.len(),
+        bytes: cache.bytes,
+        max_entries: MAX_ENTRIES,
+        max_bytes: MAX_BYTES,
+        hits: cache.hits,
+        misses: cache.misses,
+        hit_ratio: if lookups == 0 {
+            0.0
+        } else {
+            cache.hits as f64 / lookups as f64
+        },
+        evictions: cache.evictions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(body: &str) -> Response {
+        Response {
+            status: 200,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+            wire_bytes: body.len(),
+        }
+    }
+
+    fn pattern(path: &str) -> Option<&'static str> {
+        route(path).map(|route| route.pattern)
+    }
+
+    #[test]
+    fn first_matching_route_wins() {
+        assert_eq!(
+            pattern("/api/v1/invoices/search?q=acme&limit=20"),
+            Some("/api/v1/invoices/search")
+        );
+        assert_eq!(route("/api/v1/invoices/search").unwrap().ttl.as_secs(), 10);
+        assert_eq!(pattern("/api/v1/invoices/42"), Some("/api/v1/invoices/*"));
+        assert_eq!(route("/api/v1/invoices/42").unwrap().ttl.as_secs(), 30);
+        assert_eq!(
+            pattern("/api/v1/api-keys/console-url"),
+            Some("/api/v1/api-keys/console-url")
+        );
+        assert_eq!(
+            route("/api/v1/api-keys/console-url").unwrap().ttl.as_secs(),
+            3600
+        );
+        assert_eq!(
+            pattern("/api/v1/other-documents"),
+            Some("/api/v1/other-documents")
+        );
+        assert_eq!(
+            pattern("/api/v1/other-documents/7?x=1"),
+            Some("/api/v1/other-documents/*")
+        );
+        assert_eq!(route("/api/v1/cleanup/stats").unwrap().ttl.as_secs(), 10);
+    }
+
+    #[test]
+    fn wildcards_match_one_non_empty_segment() {
+        assert_eq!(pattern("/api/v1/invoices"), None);
+        assert_eq!(pattern("/api/v1/invoices/"), None);
+        assert_eq!(pattern("/api/v1/invoices/42/document"), None);
+        assert_eq!(pattern("/api/v1/invoices2/42"), None);
+        assert_eq!(pattern("/api/v1/jobs/1/status"), None);
+    }
+
+    #[test]
+    fn under_respects_segment_boundaries() {
+        assert!(under("/api/v1/invoices", "/api/v1/invoices"));
+        assert!(under("/api/v1/invoices/42", "/api/v1/invoices/"));
+        assert!(under("/api/v1/invoices?limit=5", "/api/v1/invoices"));
+        assert!(!under("/api/v1/invoices2", "/api/v1/invoices"));
+        assert!(!under("/api/v1/invoice", "/api/v1/invoices"));
+        assert!(under("/api/v1/anything", "/"));
+    }
+
+    #[test]
+    fn mutations_drop_exactly_the_dependent_entries() {
+        let paths = [
+            "/api/v1/invoices/42",
+            "/api/v1/invoices/search?q=acme",
+            "/api/v1/other-documents",
+            "/api/v1/other-documents/7",
+            "/api/v1/api-keys/status",
+            "/api/v1/api-keys/console-url",
+            "/api/v1/cleanup/stats",
+        ];
+        let filled = || {
+            let mut cache = Cache::default();
+            for path in paths {
+                cache.store(path, &ok(path));
+            }
+            assert_eq!(cache.entries.len(), paths.len());
+            cache
+        };
+        let remaining = |cache: &Cache| {
+            let mut keys: Vec<_> = cache.entries.keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let without = |dropped: &[&str]| {
+            let mut keys: Vec<_> = paths
+                .iter()
+                .filter(|path| !dropped.contains(path))
+                .map(|path| path.to_string())
+                .collect();
+            keys.sort();
+            keys
+        };
+
+        let mut cache = filled();
+        assert_eq!(cache.invalidate_after("/api/v1/invoices/42"), 2);
+        assert_eq!(
+            remaining(&cache),
+            without(&["/api/v1/invoices/42", "/api/v1/invoices/search?q=acme"])
+        );
+
+        let mut cache = filled();
+        assert_eq!(cache.invalidate_after("/api/v1/process"), 5);
+        assert_eq!(
+            remaining(&cache),
+            without(&[
+                "/api/v1/invoices/42",
+                "/api/v1/invoices/search?q=acme",
+                "/api/v1/other-documents",
+                "/api/v1/other-documents/7",
+                "/api/v1/cleanup/stats",
+            ])
+        );
+
+        let mut cache = filled();
+        assert_eq!(cache.invalidate_after("/api/v1/api-keys/openai"), 1);
+        assert_eq!(remaining(&cache), without(&["/api/v1/api-keys/status"]));
+
+        let mut cache = filled();
+        assert_eq!(cache.invalidate_after("/api/v1/invoices2"), 0);
+        assert_eq!(cache.invalidate_after("/api/v1/jobs/1"), 1);
+        assert_eq!(remaining(&cache), without(&["/api/v1/cleanup/stats"]));
+        assert_eq!(
+            cache.bytes,
+            cache
+                .entries
+                .values()
+                .map(|entry| entry.size)
+                .sum::<usize>()
+        );
+    }
+}
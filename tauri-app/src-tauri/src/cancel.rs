@@ -0,0 +1,99 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Cancel long-running backend calls by an id the caller picks.
+//!
+//! A Tauri command resolves once, with its result, so the webview chooses the id up front
+//! and passes it to both the call and `cancel_backend_request`.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+/// Calls in progress by id, each with the signal that cancels it. Managed by Tauri.
+#[derive(Default)]
+pub(crate) struct Cancellations(Mutex<HashMap<String, Arc<Notify>>>);
+
+/// Removes the call from `Cancellations` however it ends.
+pub(crate) struct Registration<'a> {
+    calls: &'a Cancellations,
+    id: String,
+    signal: Arc<Notify>,
+}
+
+impl Registration<'_> {
+    /// Resolves once the call is cancelled.
+    pub(crate) async fn cancelled(&self) {
+        self.signal.notified().await
+    }
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.calls.0.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl Cancellations {
+    /// Make the call `id` cancellable until the returned registration is dropped.
+    pub(crate) fn register(&self, id: &str) -> Result<Registration<'_>, String> {
+        let mut calls = self.0.lock().unwrap();
+        if calls.contains_key(id) {
+            return Err(format!("Request id {id} is already in use"));
+        }
+        let signal = Arc::new(Notify::new());
+        calls.insert(id.to_string(), signal.clone());
+        Ok(Registration {
+            calls: self,
+            id: id.to_string(),
+            signal,
+        })
+    }
+
+    /// Cancel the call `id`. `false` if no such call is in progress.
+    pub(crate) fn cancel(&self, id: &str) -> bool {
+        match self.0.lock().unwrap().get(id) {
+            Some(signal) => {
+                // Stores a permit, so a call that hasn't started waiting yet still sees it.
+                signal.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Run `future`, or drop it (aborting the request inside) if `id` is cancelled first.
+///
+/// `Ok(None)` means cancelled; calls without an id can't be.
+pub(crate) async fn run<F: Future>(
+    app: &AppHandle,
+    id: Option<&str>,
+    future: F,
+) -> Result<Option<F::Output>, String> {
+    let Some(id) = id else {
+        return Ok(Some(future.await));
+    };
+    let calls = app.state::<Cancellations>();
+    let registration = calls.register(id)?;
+    tokio::select! {
+        output = future => Ok(Some(output)),
+        _ = registration.cancelled() => {
+            log::info!("Request {} cancelled", id);
+            Ok(None)
+        }
+    }
+}
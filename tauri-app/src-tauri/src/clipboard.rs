@@ -0,0 +1,91 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Put text on the system clipboard through the platform's own copy tool.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy `text` to the clipboard. Blocks until the copy tool exits.
+pub(crate) fn write_text(text: &str) -> Result<(), String> {
+    let mut failures = Vec::new();
+    for (program, args) in tools() {
+        match pipe_to(program, args, &encode(text)) {
+            Ok(()) => return Ok(()),
+            Err(e) => failures.push(format!("{program}: {e}")),
+        }
+    }
+    Err(format!(
+        "Failed to copy to the clipboard ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Copy tools to try in order, with their arguments.
+#[cfg(target_os = "macos")]
+fn tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbcopy", &[])]
+}
+
+#[cfg(windows)]
+fn tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("clip", &[])]
+}
+
+/// Wayland first: under XWayland `xclip` works too, but only for X clients.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+}
+
+/// `clip` reads the console code page unless the input is UTF-16 with a BOM.
+#[cfg(windows)]
+fn encode(text: &str) -> Vec<u8> {
+    std::iter::once(0xFEFF)
+        .chain(text.encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn encode(text: &str) -> Vec<u8> {
+    text.as_bytes().to_vec()
+}
+
+fn pipe_to(program: &str, args: &[&str], input: &[u8]) -> Result<(), String> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| e.to_string())?;
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("exited with {status}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,205 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cap how many calls the shell has in flight to the backend at once.
+//!
+//! A single uvicorn worker falls over when hundreds of uploads arrive together, so excess
+//! calls wait here for a permit instead.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::settings::Settings;
+
+pub(crate) const DEFAULT_UPLOADS: u32 = 4;
+pub(crate) const DEFAULT_REQUESTS: u32 = 16;
+/// Highest limit `set_request_concurrency` accepts.
+pub(crate) const MAX_LIMIT: u32 = 64;
+
+/// Which limit a call counts against.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Lane {
+    /// File uploads to `/analyze`, each of which starts OCR work.
+    Uploads,
+    /// Proxied JSON requests and downloads.
+    Requests,
+}
+
+/// One semaphore plus the numbers behind `get_request_queue_stats`.
+struct Limiter {
+    semaphore: Arc<Semaphore>,
+    limit: Mutex<u32>,
+    active: AtomicU32,
+    waiting: AtomicU32,
+}
+
+/// Limits for both lanes, managed by Tauri.
+pub(crate) struct RequestLimits {
+    uploads: Limiter,
+    requests: Limiter,
+}
+
+/// Counts of one lane.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct LaneStats {
+    pub limit: u32,
+    pub active: u32,
+    /// Calls queued for a permit.
+    pub waiting: u32,
+}
+
+/// Payload of `get_request_queue_stats`, also sent with every heartbeat.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct QueueStats {
+    pub uploads: LaneStats,
+    pub requests: LaneStats,
+}
+
+/// Held while a call talks to the backend; frees its slot on drop.
+pub(crate) struct Permit<'a> {
+    _permit: SemaphorePermit<'a>,
+    active: &'a AtomicU32,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Takes a call off the waiting count, also when it is dropped while queued.
+struct Queued<'a>(&'a AtomicU32);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Limiter {
+    fn new(limit: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit as usize)),
+            limit: Mutex::new(limit),
+            active: AtomicU32::new(0),
+            waiting: AtomicU32::new(0),
+        }
+    }
+
+    async fn acquire(&self) -> Permit<'_> {
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        let queued = Queued(&self.waiting);
+        let permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("request semaphore is never closed");
+        drop(queued);
+        self.active.fetch_add(1, Ordering::Relaxed);
+        Permit {
+            _permit: permit,
+            active: &self.active,
+        }
+    }
+
+    fn set_limit(&self, limit: u32) {
+        let mut current = self.limit.lock().unwrap();
+        if limit > *current {
+            self.semaphore.add_permits((limit - *current) as usize);
+        } else if limit < *current {
+            let excess = (*current - limit) as usize;
+            let remaining = excess - self.semaphore.forget_permits(excess);
+            if remaining > 0 {
+                // The rest are held by calls in flight and retired as those finish.
+                let semaphore = self.semaphore.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(remaining as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+        *current = limit;
+    }
+
+    fn stats(&self) -> LaneStats {
+        LaneStats {
+            limit: *self.limit.lock().unwrap(),
+            active: self.active.load(Ordering::Relaxed),
+            waiting: self.waiting.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl RequestLimits {
+    pub(crate) fn new(settings: &Settings) -> Self {
+        Self {
+            uploads: Limiter::new(clamp(settings.max_concurrent_uploads)),
+            requests: Limiter::new(clamp(settings.max_concurrent_requests)),
+        }
+    }
+
+    fn lane(&self, lane: Lane) -> &Limiter {
+        match lane {
+            Lane::Uploads => &self.uploads,
+            Lane::Requests => &self.requests,
+        }
+    }
+
+    /// Change a lane's limit, pulled into range. Calls already queued get a raised limit
+    /// right away; a lowered one takes effect as calls in flight finish.
+    pub(crate) fn set_limit(&self, lane: Lane, limit: u32) {
+        self.lane(lane).set_limit(clamp(limit));
+        log::info!("{:?} concurrency limit set to {}", lane, clamp(limit));
+    }
+
+    pub(crate) fn stats(&self) -> QueueStats {
+        QueueStats {
+            uploads: self.uploads.stats(),
+            requests: self.requests.stats(),
+        }
+    }
+}
+
+/// Reject a limit `set_limit` would have to clamp.
+pub(crate) fn validate(limit: u32) -> Result<(), String> {
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(format!(
+            "Concurrency limit must be between 1 and {MAX_LIMIT}, got {limit}"
+        ));
+    }
+    Ok(())
+}
+
+/// A settings file may hold anything; out-of-range limits are pulled back into range.
+fn clamp(limit: u32) -> u32 {
+    limit.clamp(1, MAX_LIMIT)
+}
+
+/// Wait for a free slot in `lane`. Dropping the future leaves the queue.
+pub(crate) async fn acquire(app: &AppHandle, lane: Lane) -> Permit<'_> {
+    app.state::<RequestLimits>()
+        .inner()
+        .lane(lane)
+        .acquire()
+        .await
+}
+
+pub(crate) fn stats(app: &AppHandle) -> QueueStats {
+    app.state::<RequestLimits>().stats()
+}
@@ -0,0 +1,330 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tuning values resolved once at startup from settings and env overrides.
+
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::endpoint;
+use crate::proxy::TimeoutClass;
+use crate::settings::Settings;
+use crate::DEFAULT_BACKEND_PORT;
+
+/// Overrides the readiness timeout, in seconds.
+const READINESS_TIMEOUT_ENV: &str = "INVOICATOR_READY_TIMEOUT_SECS";
+/// Overrides the longest readiness poll interval, in milliseconds.
+const READINESS_POLL_ENV: &str = "INVOICATOR_READY_POLL_MS";
+/// Overrides the timeout of each readiness `/health` request, in milliseconds.
+const READINESS_HTTP_TIMEOUT_ENV: &str = "INVOICATOR_READY_HTTP_TIMEOUT_MS";
+/// Attach to the backend at this URL instead of spawning the bundled one.
+const BACKEND_URL_ENV: &str = "INVOICE_BACKEND_URL";
+/// Alternative to `BACKEND_URL_ENV`: host and (optional, default 8000) port over plain http.
+const BACKEND_HOST_ENV: &str = "INVOICE_BACKEND_HOST";
+const BACKEND_PORT_ENV: &str = "INVOICE_BACKEND_PORT";
+
+/// How `wait_for_backend_ready` polls a freshly spawned backend.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ReadinessConfig {
+    /// Give up after this long. First-run model extraction on old machines can take well
+    /// over a minute.
+    pub max_wait: Duration,
+    /// Delay after the first poll; it doubles after each poll up to `max_poll_interval`,
+    /// so a fast startup is noticed quickly without hammering a slow one.
+    pub initial_poll_interval: Duration,
+    pub max_poll_interval: Duration,
+    /// Timeout of each `/health` request.
+    pub http_timeout: Duration,
+    /// Timeout of the TCP connect that gates HTTP polling until the port is open. Kept
+    /// short: a refused connect on Windows can otherwise take a second or more.
+    pub tcp_probe_timeout: Duration,
+}
+
+impl ReadinessConfig {
+    pub(crate) const DEFAULT: Self = Self {
+        max_wait: Duration::from_secs(120),
+        initial_poll_interval: Duration::from_millis(150),
+        max_poll_interval: Duration::from_secs(2),
+        http_timeout: Duration::from_secs(2),
+        tcp_probe_timeout: Duration::from_millis(100),
+    };
+
+    /// The delay after a poll that waited `previous`: doubled, capped, with up to ±10%
+    /// jitter so restarts of several instances don't poll in lockstep.
+    pub(crate) fn next_poll_interval(&self, previous: Duration) -> Duration {
+        jitter(previous.saturating_mul(2).min(self.max_poll_interval))
+    }
+
+    /// Roughly how many polls fit in `max_wait`, for progress reporting.
+    pub(crate) fn expected_polls(&self) -> u32 {
+        let mut elapsed = Duration::ZERO;
+        let mut interval = self.initial_poll_interval.max(Duration::from_millis(1));
+        let mut polls = 1;
+        while elapsed + interval < self.max_wait && polls < u32::MAX {
+            elapsed += interval;
+            interval = interval
+                .saturating_mul(2)
+                .min(self.max_poll_interval)
+                .max(Duration::from_millis(1));
+            polls += 1;
+        }
+        polls
+    }
+}
+
+/// `base` give or take up to 10%, at least 1ms.
+pub(crate) fn jitter(base: Duration) -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    // -10..=10 percent, from the clock's low bits.
+    let percent = i64::from(nanos % 21) - 10;
+    let jitter = base.as_millis() as i64 * percent / 100;
+    Duration::from_millis((base.as_millis() as i64 + jitter).max(1) as u64)
+}
+
+/// The clock of one readiness wait: polls made so far, the pause before the next one and
+/// the deadline.
+pub(crate) struct ReadinessWait {
+    config: ReadinessConfig,
+    started: Instant,
+    interval: Duration,
+    attempt: u32,
+}
+
+impl ReadinessWait {
+    pub(crate) fn start(config: &ReadinessConfig) -> Self {
+        Self {
+            config: *config,
+            started: Instant::now(),
+            interval: config.initial_poll_interval,
+            attempt: 0,
+        }
+    }
+
+    /// Count a poll; returns its number, from 1.
+    pub(crate) fn next_attempt(&mut self) -> u32 {
+        self.attempt += 1;
+        self.attempt
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// The time elapsed, or the timeout error once `max_wait` is up. `process` and
+    /// `last_response` describe the backend and its last answer for that error.
+    pub(crate) fn check_deadline(
+        &self,
+        process: &str,
+        last_response: &str,
+    ) -> Result<Duration, String> {
+        let elapsed = self.elapsed();
+        if elapsed < self.config.max_wait {
+            return Ok(elapsed);
+        }
+        Err(format!(
+            "Backend did not become ready within {}s ({}, last response: {}; {} attempts, {:.1}s elapsed)",
+            self.config.max_wait.as_secs(),
+            process,
+            last_response,
+            self.attempt,
+            elapsed.as_secs_f32()
+        ))
+    }
+
+    /// Sleep until the next poll. The deadline is time-based: never sleep past it.
+    pub(crate) async fn pause(&mut self) {
+        let left = self.config.max_wait.saturating_sub(self.elapsed());
+        tokio::time::sleep(self.interval.min(left)).await;
+        self.interval = self.config.next_poll_interval(self.interval);
+    }
+}
+
+/// Client-side limits of backend calls, by `TimeoutClass`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TimeoutConfig {
+    pub fast: Duration,
+    pub normal: Duration,
+    /// Only a backstop for a hung backend: cutting a call off leaves the backend working
+    /// on it, so these are meant to be cancelled instead.
+    pub long_running: Duration,
+    /// Bounds the whole exchange; event streams reconnect when it runs out.
+    pub streaming: Duration,
+}
+
+impl TimeoutConfig {
+    pub(crate) const DEFAULT: Self = Self {
+        fast: Duration::from_secs(2),
+        normal: Duration::from_secs(30),
+        long_running: Duration::from_secs(60 * 60),
+        streaming: Duration::from_secs(60 * 60),
+    };
+
+    pub(crate) fn get(&self, class: TimeoutClass) -> Duration {
+        match class {
+            TimeoutClass::Fast => self.fast,
+            TimeoutClass::Normal => self.normal,
+            TimeoutClass::LongRunning => self.long_running,
+            TimeoutClass::Streaming => self.streaming,
+        }
+    }
+
+    fn load(settings: &Settings) -> Self {
+        let mut timeouts = Self::DEFAULT;
+        for (class, secs) in &settings.request_timeout_secs {
+            let slot = match class {
+                TimeoutClass::Fast => &mut timeouts.fast,
+                TimeoutClass::Normal => &mut timeouts.normal,
+                TimeoutClass::LongRunning => &mut timeouts.long_running,
+                TimeoutClass::Streaming => &mut timeouts.streaming,
+            };
+            match secs {
+                0 => log::warn!("Ignoring {} request timeout of 0s", class.name()),
+                secs => *slot = Duration::from_secs(*secs),
+            }
+        }
+        timeouts
+    }
+}
+
+/// Configuration managed by Tauri; env vars win over settings, settings over defaults.
+#[derive(Clone, Debug)]
+pub(crate) struct AppConfig {
+    pub readiness: ReadinessConfig,
+    pub timeouts: TimeoutConfig,
+    /// Base URL of a backend the app attaches to rather than spawns.
+    pub external_backend: Option<String>,
+}
+
+impl AppConfig {
+    pub(crate) fn load(settings: &Settings) -> Self {
+        let default = ReadinessConfig::DEFAULT;
+        let readiness = ReadinessConfig {
+            max_wait: env_override(READINESS_TIMEOUT_ENV, Duration::from_secs)
+                .or(settings.readiness_timeout_secs.map(Duration::from_secs))
+                .unwrap_or(default.max_wait),
+            initial_poll_interval: default.initial_poll_interval,
+            max_poll_interval: env_override(READINESS_POLL_ENV, Duration::from_millis)
+                .or(settings
+                    .readiness_poll_interval_ms
+                    .map(Duration::from_millis))
+                .unwrap_or(default.max_poll_interval),
+            http_timeout: env_override(READINESS_HTTP_TIMEOUT_ENV, Duration::from_millis)
+                .or(settings
+                    .readiness_http_timeout_ms
+                    .map(Duration::from_millis))
+                .unwrap_or(default.http_timeout),
+            tcp_probe_timeout: default.tcp_probe_timeout,
+        };
+        let readiness = ReadinessConfig {
+            initial_poll_interval: readiness
+                .initial_poll_interval
+                .min(readiness.max_poll_interval),
+            ..readiness
+        };
+        Self {
+            readiness,
+            timeouts: TimeoutConfig::load(settings),
+            external_backend: external_backend(settings),
+        }
+    }
+}
+
+/// The configured limit for calls of `class`.
+pub(crate) fn request_timeout(app: &AppHandle, class: TimeoutClass) -> Duration {
+    app.state::<AppConfig>().timeouts.get(class)
+}
+
+/// The external backend URL from the env, else from settings. Invalid URLs are ignored.
+fn external_backend(settings: &Settings) -> Option<String> {
+    let url = env_value(BACKEND_URL_ENV)
+        .or_else(|| {
+            let host = env_value(BACKEND_HOST_ENV)?;
+            let port = env_value(BACKEND_PORT_ENV).unwrap_or(DEFAULT_BACKEND_PORT.to_string());
+            Some(format!("http://{}:{}", host.trim(), port.trim()))
+        })
+        .or_else(|| settings.backend_url.clone())?;
+    match endpoint::normalize(&url) {
+        Ok(url) => {
+            log::info!("Using external backend at {}", url);
+            Some(url)
+        }
+        Err(e) => {
+            log::warn!("Ignoring external backend: {}", e);
+            None
+        }
+    }
+}
+
+/// The env var `name`, unless unset or blank.
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// A positive integer from the env var `name`, converted with `unit`.
+fn env_override(name: &str, unit: fn(u64) -> Duration) -> Option<Duration> {
+    let value = std::env::var(name).ok()?;
+    match value.parse::<u64>() {
+        Ok(n) if n > 0 => Some(unit(n)),
+        _ => {
+            log::warn!("Ignoring invalid {}={:?}", name, value);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend that never answers runs into the deadline; the last pause is cut short
+    /// instead of overshooting it by a whole poll interval.
+    #[test]
+    fn readiness_wait_times_out() {
+        let config = ReadinessConfig {
+            max_wait: Duration::from_millis(30),
+            initial_poll_interval: Duration::from_millis(5),
+            max_poll_interval: Duration::from_millis(20),
+            ..ReadinessConfig::DEFAULT
+        };
+        let (error, wait) = tauri::async_runtime::block_on(async {
+            let mut wait = ReadinessWait::start(&config);
+            loop {
+                wait.next_attempt();
+                match wait.check_deadline("process still running", "port not open") {
+                    Ok(_) => wait.pause().await,
+                    Err(e) => break (e, wait),
+                }
+            }
+        });
+
+        assert!(
+            error.starts_with(
+                "Backend did not become ready within 0s (process still running, \
+                 last response: port not open; "
+            ),
+            "{error}"
+        );
+        assert!(wait.attempt > 1, "polled only once");
+        let elapsed = wait.elapsed();
+        assert!(elapsed >= config.max_wait);
+        assert!(
+            elapsed < config.max_wait + Duration::from_secs(1),
+            "{elapsed:?}"
+        );
+    }
+}
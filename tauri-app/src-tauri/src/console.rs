@@ -0,0 +1,79 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Troubleshooting console that shows the backend's output on Windows.
+//!
+//! The backend itself is always spawned with `CREATE_NO_WINDOW` (set by the shell plugin),
+//! so instead the app opens a console of its own and echoes the captured output into it.
+
+use std::fs::File;
+use std::io::Write;
+use std::sync::Mutex;
+
+use windows_sys::Win32::System::Console::{AllocConsole, FreeConsole, GetConsoleWindow};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    DeleteMenu, GetSystemMenu, MF_BYCOMMAND, SC_CLOSE,
+};
+
+/// Handle to the console's output while it is open.
+static CONSOLE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Open the console window, if it isn't already.
+pub(crate) fn show() -> Result<(), String> {
+    let mut console = CONSOLE.lock().unwrap();
+    if console.is_some() {
+        return Ok(());
+    }
+    // SAFETY: AllocConsole has no preconditions; it fails if we already have a console.
+    if unsafe { AllocConsole() } == 0 {
+        return Err(format!(
+            "Failed to open backend console: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    // Closing a console window terminates every process attached to it, the app included,
+    // so take away its close button; `hide` is the way to get rid of it.
+    // SAFETY: plain calls on the console window we just allocated; null results are skipped.
+    unsafe {
+        let window = GetConsoleWindow();
+        if !window.is_null() {
+            let menu = GetSystemMenu(window, 0);
+            if !menu.is_null() {
+                DeleteMenu(menu, SC_CLOSE, MF_BYCOMMAND);
+            }
+        }
+    }
+    let out = std::fs::OpenOptions::new()
+        .write(true)
+        .open("CONOUT$")
+        .map_err(|e| format!("Failed to open backend console output: {e}"))?;
+    *console = Some(out);
+    Ok(())
+}
+
+/// Close the console window, if it is open.
+pub(crate) fn hide() {
+    let mut console = CONSOLE.lock().unwrap();
+    if console.take().is_some() {
+        // SAFETY: we allocated the console in `show` and release it exactly once.
+        unsafe { FreeConsole() };
+    }
+}
+
+/// Write one line of backend output to the console when it is open.
+pub(crate) fn echo(line: &str) {
+    if let Some(out) = CONSOLE.lock().unwrap().as_mut() {
+        let _ = writeln!(out, "{line}");
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Newline-delimited JSON control messages written to the backend's stdin.
+//!
+//! Works while the HTTP server is saturated, e.g. to make the backend reload its settings
+//! or flush caches in the middle of an OCR batch. The backend's `app/core/control.py` lists
+//! the commands it understands.
+
+use std::time::Duration;
+
+use tauri_plugin_shell::process::CommandChild;
+use tokio::sync::{mpsc, oneshot};
+
+/// Messages queued but not yet written before `send` reports the channel as full.
+const QUEUE_DEPTH: usize = 16;
+/// How long `send` waits for a write to complete before giving up on the backend.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+type Request = (Vec<u8>, oneshot::Sender<Result<(), String>>);
+
+/// Queue into the writer thread that owns the backend's stdin.
+#[derive(Clone)]
+pub(crate) struct ControlChannel(mpsc::Sender<Request>);
+
+/// Move `child` onto a dedicated writer thread, since a write blocks for as long as the
+/// backend leaves its stdin unread. The thread, and the child handle with it, goes away
+/// once the `ControlChannel` is dropped.
+pub(crate) fn spawn_writer(mut child: CommandChild) -> ControlChannel {
+    let (tx, mut rx) = mpsc::channel::<Request>(QUEUE_DEPTH);
+    std::thread::spawn(move || {
+        while let Some((line, reply)) = rx.blocking_recv() {
+            let result = child
+                .write(&line)
+                .map_err(|e| format!("Backend control pipe is closed: {e}"));
+            let closed = result.is_err();
+            let _ = reply.send(result);
+            if closed {
+                break;
+            }
+        }
+    });
+    ControlChannel(tx)
+}
+
+impl ControlChannel {
+    /// Write `message` as one line and wait for the write to complete.
+    pub(crate) async fn send(&self, message: &serde_json::Value) -> Result<(), String> {
+        if !message.get("cmd").is_some_and(serde_json::Value::is_string) {
+            return Err("Control message must be an object with a string \"cmd\" field".into());
+        }
+        let mut line = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.0.try_send((line, reply_tx)).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => {
+                "Backend is not reading its control channel".to_string()
+            }
+            mpsc::error::TrySendError::Closed(_) => "Backend control pipe is closed".to_string(),
+        })?;
+        match tokio::time::timeout(WRITE_TIMEOUT, reply_rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Backend control pipe is closed".to_string()),
+            Err(_) => Err("Backend is not reading its control channel".to_string()),
+        }
+    }
+}
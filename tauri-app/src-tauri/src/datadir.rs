@@ -0,0 +1,391 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Where the backend keeps invoice data (`DATA_DIR`), and moving it elsewhere.
+//!
+//! The default is the app data dir. Another location is recorded in `data_dir.json`
+//! there, which stays put along with the shell's own files: settings, pid file, TLS
+//! files, socket, temp dir. Moving copies everything else while the backend is stopped,
+//! switches the pointer, then deletes the old copy; until the pointer is switched a
+//! failure removes what was copied and leaves the old location in use.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::supervisor::{self, Startup, Supervisor};
+use crate::{datalock, diskspace, endpoint, pidfile, restore, settings, startupmetrics, telemetry};
+use crate::{tempdir, tls, transport};
+
+pub(crate) const POINTER_FILE_NAME: &str = "data_dir.json";
+/// Left in the app data dir by a move, and ignored when checking it is empty.
+const SHELL_ENTRIES: &[&str] = &[
+    POINTER_FILE_NAME,
+    settings::SETTINGS_FILE_NAME,
+    pidfile::PID_FILE_NAME,
+    startupmetrics::FILE_NAME,
+    telemetry::QUEUE_FILE,
+    tls::CERT_FILE_NAME,
+    tls::KEY_FILE_NAME,
+    transport::SOCKET_FILE_NAME,
+    tempdir::TEMP_DIR_NAME,
+    restore::MARKER_FILE,
+    datalock::LOCK_FILE_NAME,
+];
+/// Room left on the target disk on top of the data itself.
+const FREE_SPACE_MARGIN: u64 = 100 * 1024 * 1024;
+const PROGRESS_EVENT: &str = "data-dir-migration-progress";
+/// Least time between two progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Deserialize)]
+struct Pointer {
+    path: PathBuf,
+}
+
+/// Payload of `get_data_dir` and `set_data_dir`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct DataDir {
+    pub path: PathBuf,
+    /// The app data dir, not a location the user chose.
+    pub is_default: bool,
+}
+
+/// Payload of the `data-dir-migration-progress` and `legacy-migration-progress` events.
+#[derive(Clone, Debug, Serialize)]
+struct MigrationProgress {
+    copied_bytes: u64,
+    total_bytes: u64,
+    files_done: usize,
+    files_total: usize,
+}
+
+fn default_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// The data dir the backend is given, and whether it is the default one. An unreadable
+/// pointer file counts as none.
+pub(crate) fn current(app: &AppHandle) -> Result<DataDir, String> {
+    let default = default_dir(app)?;
+    let pointer = fs::read_to_string(default.join(POINTER_FILE_NAME))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Pointer>(&json).ok());
+    Ok(match pointer {
+        Some(Pointer { path }) if path != default => DataDir {
+            path,
+            is_default: false,
+        },
+        _ => DataDir {
+            path: default,
+            is_default: true,
+        },
+    })
+}
+
+/// The effective data dir's path.
+pub(crate) fn path(app: &AppHandle) -> Result<PathBuf, String> {
+    current(app).map(|dir| dir.path)
+}
+
+/// Move the backend's data to `target` and restart the backend there. The caller must not
+/// hold the lifecycle lock.
+pub(crate) async fn migrate(app: &AppHandle, target: PathBuf) -> Result<DataDir, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    let default = default_dir(app)?;
+    let old = path(app)?;
+    if !crate::active_backend_jobs(&endpoint::transport(app))
+        .await
+        .is_empty()
+    {
+        return Err("Wait for the running jobs to finish before moving the data".into());
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let plan = {
+        let (old, target, default) = (old.clone(), target.clone(), default.clone());
+        tauri::async_runtime::spawn_blocking(move || plan(&old, &target, &default))
+            .await
+            .map_err(|e| format!("Checking the new data dir failed: {e}"))??
+    };
+    let target = plan.target.clone();
+
+    log::info!(
+        "Moving backend data from {} to {} ({} files, {} bytes)",
+        old.display(),
+        target.display(),
+        plan.tree.files.len(),
+        plan.tree.total_bytes
+    );
+    supervisor::stop_current(app).await;
+    let handle = app.clone();
+    let from = old.clone();
+    let copied = tauri::async_runtime::spawn_blocking(move || {
+        let switched = copy_tree(&handle, PROGRESS_EVENT, &from, &plan.target, &plan.tree)
+            .and_then(|()| {
+                write_pointer(&default, &plan)
+                    .map_err(|e| format!("Failed to save the new data dir: {e}"))
+            });
+        if switched.is_err() {
+            remove_copies(&plan.target, &plan.tree);
+        }
+        switched.map(|()| plan)
+    })
+    .await
+    .map_err(|e| format!("Moving the data failed: {e}"))?;
+
+    let result = match copied {
+        Ok(plan) => {
+            remove_copies(&old, &plan.tree);
+            log::info!("Backend data is now in {}", target.display());
+            current(app)
+        }
+        Err(e) => {
+            log::error!(
+                "Moving backend data failed, staying in {}: {}",
+                old.display(),
+                e
+            );
+            Err(e)
+        }
+    };
+    match supervisor::launch_and_wait(app, &mut stop).await {
+        Ok(Startup::Ready) => {}
+        Ok(_) => log::warn!("Backend did not become ready after the data dir change"),
+        Err(e) => log::error!("Backend failed to start after the data dir change: {}", e),
+    }
+    result
+}
+
+/// What a move copies, and where to.
+struct Plan {
+    /// The new data dir, resolved.
+    target: PathBuf,
+    /// `target` is the app data dir, so the pointer goes away.
+    to_default: bool,
+    tree: Tree,
+}
+
+/// Files under a directory, relative to it, for copying them elsewhere.
+pub(crate) struct Tree {
+    /// Directories first, each before what it holds.
+    dirs: Vec<PathBuf>,
+    pub files: Vec<(PathBuf, u64)>,
+    /// Top-level entries, for the cleanup.
+    top: Vec<PathBuf>,
+    pub total_bytes: u64,
+}
+
+/// Check `target` can take the contents of `old` and list them.
+fn plan(old: &Path, target: &Path, default: &Path) -> Result<Plan, String> {
+    if !target.is_absolute() {
+        return Err(format!("Not an absolute path: {}", target.display()));
+    }
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+    let resolve = |dir: &Path| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let target = target
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {e}", target.display()))?;
+    let (old_resolved, to_default) = (resolve(old), target == resolve(default));
+    if target == old_resolved {
+        return Err("The data is already there".into());
+    }
+    if target.starts_with(&old_resolved) || old_resolved.starts_with(&target) {
+        return Err("The new data dir can't be inside the current one, or hold it".into());
+    }
+    let occupied = fs::read_dir(&target)
+        .map_err(|e| format!("Failed to read {}: {e}", target.display()))?
+        .flatten()
+        .any(|entry| !to_default || !is_shell_entry(&entry.file_name()));
+    if occupied {
+        return Err(format!("{} is not empty", target.display()));
+    }
+    let probe = target.join(".write-test");
+    fs::write(&probe, b"")
+        .and_then(|()| fs::remove_file(&probe))
+        .map_err(|e| format!("{} is not writable: {e}", target.display()))?;
+
+    // The shell's own files stay in the app data dir wherever the data goes.
+    let tree = scan(old, |name| old == default && is_shell_entry(name))?;
+    let plan = Plan {
+        target,
+        to_default,
+        tree,
+    };
+    check_free_space(&plan.target, plan.tree.total_bytes)?;
+    Ok(plan)
+}
+
+/// Fail unless `dir`'s disk has room for `bytes` and some more.
+pub(crate) fn check_free_space(dir: &Path, bytes: u64) -> Result<(), String> {
+    let free = diskspace::free_space(dir)?;
+    if free < bytes + FREE_SPACE_MARGIN {
+        return Err(format!(
+            "Not enough free space in {}: {} MB needed, {} MB free",
+            dir.display(),
+            (bytes + FREE_SPACE_MARGIN) / (1024 * 1024),
+            free / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the effective data dir holds anything besides the shell's own files.
+pub(crate) fn holds_data(app: &AppHandle) -> bool {
+    let Ok(dir) = path(app) else {
+        return false;
+    };
+    fs::read_dir(dir).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|entry| !is_shell_entry(&entry.file_name()))
+    })
+}
+
+/// List what is under `root`, leaving out top-level entries `skip` picks. A missing
+/// `root` is an empty tree.
+pub(crate) fn scan(root: &Path, skip: impl Fn(&OsStr) -> bool) -> Result<Tree, String> {
+    let mut tree = Tree {
+        dirs: Vec::new(),
+        files: Vec::new(),
+        top: Vec::new(),
+        total_bytes: 0,
+    };
+    if !root.exists() {
+        return Ok(tree);
+    }
+    let unreadable = |e: io::Error| format!("Failed to read {}: {e}", root.display());
+    for entry in fs::read_dir(root).map_err(unreadable)? {
+        let entry = entry.map_err(unreadable)?;
+        if skip(&entry.file_name()) {
+            continue;
+        }
+        let relative = PathBuf::from(entry.file_name());
+        tree.top.push(relative.clone());
+        list(root, &relative, &mut tree).map_err(unreadable)?;
+    }
+    Ok(tree)
+}
+
+pub(crate) fn is_shell_entry(name: &OsStr) -> bool {
+    SHELL_ENTRIES.iter().any(|entry| name == *entry)
+}
+
+/// Add `relative` under `root` to `tree`, with what it holds if it is a directory.
+fn list(root: &Path, relative: &Path, tree: &mut Tree) -> io::Result<()> {
+    let meta = fs::symlink_metadata(root.join(relative))?;
+    if meta.is_dir() {
+        tree.dirs.push(relative.to_path_buf());
+        for entry in fs::read_dir(root.join(relative))? {
+            list(root, &relative.join(entry?.file_name()), tree)?;
+        }
+    } else if meta.is_file() {
+        tree.total_bytes += meta.len();
+        tree.files.push((relative.to_path_buf(), meta.len()));
+    } else {
+        log::warn!(
+            "Not copying {}: not a regular file",
+            root.join(relative).display()
+        );
+    }
+    Ok(())
+}
+
+/// Copy `tree` from `from` to `to`, replacing files there, and emit `event` with a
+/// `MigrationProgress` as it goes.
+pub(crate) fn copy_tree(
+    app: &AppHandle,
+    event: &str,
+    from: &Path,
+    to: &Path,
+    tree: &Tree,
+) -> Result<(), String> {
+    for dir in &tree.dirs {
+        fs::create_dir_all(to.join(dir))
+            .map_err(|e| format!("Failed to create {}: {e}", to.join(dir).display()))?;
+    }
+    let mut progress = MigrationProgress {
+        copied_bytes: 0,
+        total_bytes: tree.total_bytes,
+        files_done: 0,
+        files_total: tree.files.len(),
+    };
+    let mut reported = Instant::now();
+    let _ = app.emit(event, progress.clone());
+    for (file, size) in &tree.files {
+        fs::copy(from.join(file), to.join(file))
+            .map_err(|e| format!("Failed to copy {}: {e}", from.join(file).display()))?;
+        progress.copied_bytes += size;
+        progress.files_done += 1;
+        let last = progress.files_done == progress.files_total;
+        if last || reported.elapsed() >= PROGRESS_INTERVAL {
+            reported = Instant::now();
+            let _ = app.emit(event, progress.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Delete the top-level entries of `tree` from `dir`, logging what can't be deleted.
+pub(crate) fn remove_copies(dir: &Path, tree: &Tree) {
+    for entry in &tree.top {
+        let path = dir.join(entry);
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            if e.kind() != io::ErrorKind::NotFound {
+                log::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Point at the plan's target, or drop the pointer when it is the default dir. Written to
+/// a temp file first, so a crash never leaves half a pointer.
+fn write_pointer(default: &Path, plan: &Plan) -> io::Result<()> {
+    let pointer = default.join(POINTER_FILE_NAME);
+    if plan.to_default {
+        return match fs::remove_file(&pointer) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    fs::create_dir_all(default)?;
+    let json = serde_json::to_string_pretty(&Pointer {
+        path: plan.target.clone(),
+    })
+    .map_err(io::Error::other)?;
+    let temp = default.join(format!(".{POINTER_FILE_NAME}.part"));
+    fs::write(&temp, json)?;
+    fs::rename(&temp, &pointer)
+}
@@ -0,0 +1,231 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Lock file in `DATA_DIR`, so two app instances never run backends on the same database.
+//!
+//! Taken before each spawn and released when the backend is stopped or the app exits. It
+//! names the app process holding it and when that process started, so a lock left by a
+//! crash is recognised even if its PID was reused, and broken. One written on another
+//! machine, for a data dir on a share, can't be checked and counts as held. This is on
+//! top of any single-instance guard, which can't see an instance on another machine or
+//! one launched from another install.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter};
+
+use crate::datadir;
+use crate::supervisor::StartupError;
+
+pub(crate) const LOCK_FILE_NAME: &str = "data_dir.lock";
+/// Allowed gap between the recorded start time and the one the OS reports.
+const START_TIME_TOLERANCE_SECS: u64 = 5;
+/// A lock file this fresh that can't be read yet is being written by its owner.
+const WRITE_GRACE: Duration = Duration::from_secs(5);
+
+/// Contents of the lock file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Lock {
+    pid: u32,
+    /// Start of the app process, in seconds since the epoch, as the OS reports it.
+    started_at: u64,
+    host: String,
+    exe: Option<PathBuf>,
+}
+
+/// Payload of the `data-dir-locked` event: the instance that holds the data dir.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct DataDirLocked {
+    pub data_dir: PathBuf,
+    /// `None` while the other instance is still writing the lock file.
+    pub pid: Option<u32>,
+    pub started_at: Option<u64>,
+    pub host: Option<String>,
+    pub exe: Option<PathBuf>,
+}
+
+fn host() -> String {
+    System::host_name().unwrap_or_default()
+}
+
+/// When the process `pid` started, if it is running.
+fn start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).map(|process| process.start_time())
+}
+
+fn ours() -> Lock {
+    let pid = std::process::id();
+    Lock {
+        pid,
+        started_at: start_time(pid).unwrap_or_default(),
+        host: host(),
+        exe: std::env::current_exe().ok(),
+    }
+}
+
+/// Whether `lock` belongs to a process that still runs, or can't be told not to.
+fn is_live(lock: &Lock) -> bool {
+    if lock.host != host() {
+        return true;
+    }
+    start_time(lock.pid)
+        .is_some_and(|started| started.abs_diff(lock.started_at) <= START_TIME_TOLERANCE_SECS)
+}
+
+enum Found {
+    Missing,
+    Ours,
+    /// Held by a live instance; `None` while it is still writing the file.
+    Held(Option<Lock>),
+    Stale(String),
+}
+
+fn inspect(path: &Path) -> Found {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Found::Missing,
+        Err(e) => return Found::Stale(format!("unreadable: {e}")),
+    };
+    let lock = match serde_json::from_str::<Lock>(&contents) {
+        Ok(lock) => lock,
+        Err(e) => {
+            let fresh = fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age < WRITE_GRACE);
+            return if fresh {
+                Found::Held(None)
+            } else {
+                Found::Stale(format!("unreadable: {e}"))
+            };
+        }
+    };
+    if lock.pid == std::process::id() && lock.host == host() {
+        Found::Ours
+    } else if is_live(&lock) {
+        Found::Held(Some(lock))
+    } else {
+        Found::Stale(format!(
+            "pid {} on {} is no longer running",
+            lock.pid, lock.host
+        ))
+    }
+}
+
+fn lock_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(datadir::path(app)?.join(LOCK_FILE_NAME))
+}
+
+/// Take the lock on the data dir, breaking a stale one. Emits `data-dir-locked` and fails
+/// if another live instance holds it.
+pub(crate) fn acquire(app: &AppHandle) -> Result<(), StartupError> {
+    let data_dir = datadir::path(app)?;
+    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data dir: {e}"))?;
+    let path = data_dir.join(LOCK_FILE_NAME);
+    // Twice at most: once more after breaking a stale lock.
+    for _ in 0..2 {
+        match inspect(&path) {
+            Found::Ours => return Ok(()),
+            Found::Held(lock) => return Err(locked(app, data_dir, lock)),
+            Found::Stale(reason) => {
+                log::warn!(
+                    "Breaking stale data dir lock {}: {}",
+                    path.display(),
+                    reason
+                );
+                if let Err(e) = fs::remove_file(&path) {
+                    if e.kind() != io::ErrorKind::NotFound {
+                        return Err(format!("Failed to remove {}: {e}", path.display()).into());
+                    }
+                }
+            }
+            Found::Missing => {}
+        }
+        match create(&path) {
+            Ok(()) => {
+                log::info!("Locked data dir {}", data_dir.display());
+                return Ok(());
+            }
+            // Another instance took it in between; look at it again.
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to lock {}: {e}", path.display()).into()),
+        }
+    }
+    Err(locked(app, data_dir, None))
+}
+
+/// Create the lock file, failing if it exists.
+fn create(path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    let json = serde_json::to_vec(&ours()).map_err(io::Error::other)?;
+    let written = file.write_all(&json).and_then(|()| file.sync_all());
+    if written.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    written
+}
+
+fn locked(app: &AppHandle, data_dir: PathBuf, lock: Option<Lock>) -> StartupError {
+    let holder = match &lock {
+        Some(lock) => format!("the app running as pid {} on {}", lock.pid, lock.host),
+        None => "another instance of the app".to_string(),
+    };
+    let message = format!(
+        "{} is in use by {holder}; close it before starting the backend here",
+        data_dir.display()
+    );
+    log::error!("{}", message);
+    let payload = DataDirLocked {
+        data_dir,
+        pid: lock.as_ref().map(|lock| lock.pid),
+        started_at: lock.as_ref().map(|lock| lock.started_at),
+        host: lock.as_ref().map(|lock| lock.host.clone()),
+        exe: lock.and_then(|lock| lock.exe),
+    };
+    let _ = app.emit("data-dir-locked", &payload);
+    StartupError::DataDirLocked {
+        pid: payload.pid,
+        message,
+    }
+}
+
+/// Whether another live instance holds the data dir, without touching the lock.
+pub(crate) fn held_elsewhere(app: &AppHandle) -> bool {
+    lock_path(app).is_ok_and(|path| matches!(inspect(&path), Found::Held(_)))
+}
+
+/// Give the lock up if this process holds it.
+pub(crate) fn release(app: &AppHandle) {
+    let Ok(path) = lock_path(app) else {
+        return;
+    };
+    if matches!(inspect(&path), Found::Ours) {
+        match fs::remove_file(&path) {
+            Ok(()) => log::info!("Unlocked data dir"),
+            Err(e) => log::warn!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+}
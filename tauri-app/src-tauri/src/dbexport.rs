@@ -0,0 +1,224 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `export_database`: the backend's SQLite file on its own, for other tools.
+//!
+//! The backend's `/admin/backup` snapshot is copied to the destination, and the copy only
+//! counts as exported once `PRAGMA integrity_check` passes on it. When it doesn't, the
+//! snapshot is checked too, to tell a corrupt database from a bad copy.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::supervisor::Supervisor;
+use crate::{backup, datadir, endpoint, support};
+
+/// Tables whose rows are counted for the summary.
+const MAIN_TABLES: &[&str] = &["invoices", "invoice_lines", "other_documents"];
+/// Problems `integrity_check` reports at most.
+const MAX_PROBLEMS: usize = 20;
+
+/// What `export_database` wrote.
+#[derive(Debug, Serialize)]
+pub(crate) struct DatabaseExport {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Rows in each of `MAIN_TABLES` the database has.
+    pub row_counts: BTreeMap<String, u64>,
+}
+
+/// Why an export failed, tagged by `kind`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum ExportError {
+    /// Nothing was exported: no backend to snapshot the database, or it refused.
+    Unavailable { message: String },
+    /// The database itself fails the integrity check, so no copy was kept.
+    SourceCorrupt {
+        message: String,
+        problems: Vec<String>,
+    },
+    /// Writing the copy failed, or the copy failed the check while the database passed.
+    CopyFailed { message: String },
+}
+
+fn unavailable(message: impl Into<String>) -> ExportError {
+    ExportError::Unavailable {
+        message: message.into(),
+    }
+}
+
+fn copy_failed(message: impl Into<String>) -> ExportError {
+    ExportError::CopyFailed {
+        message: message.into(),
+    }
+}
+
+/// Export the database to `destination`, or into it under a dated name when it is a
+/// folder.
+pub(crate) async fn export(
+    app: &AppHandle,
+    destination: &Path,
+) -> Result<DatabaseExport, ExportError> {
+    if cfg!(debug_assertions) {
+        return Err(unavailable(
+            "Dev mode: the backend's data dir is not managed by the app",
+        ));
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err(unavailable("An external backend keeps its data itself"));
+    }
+    let path = if destination.is_dir() {
+        destination.join(support::default_database_export_name(SystemTime::now()))
+    } else {
+        destination.to_path_buf()
+    };
+    let supervisor = app.state::<Supervisor>();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err(unavailable(
+            "The backend is starting or restarting, try again in a moment",
+        ));
+    };
+    let data_dir = datadir::path(app).map_err(unavailable)?;
+    let snapshot = backup::snapshot(app, &data_dir)
+        .await
+        .map_err(unavailable)?;
+
+    let exported = {
+        let snapshot = snapshot.clone();
+        tauri::async_runtime::spawn_blocking(move || write_checked(&snapshot, &path))
+            .await
+            .map_err(|e| copy_failed(format!("Exporting the database failed: {e}")))
+    };
+    if let Err(e) = fs::remove_file(&snapshot) {
+        log::warn!("Failed to remove {}: {}", snapshot.display(), e);
+    }
+    let export = exported??;
+    log::info!(
+        "Exported the database to {} ({} bytes, {:?})",
+        export.path.display(),
+        export.bytes,
+        export.row_counts
+    );
+    Ok(export)
+}
+
+/// Copy `snapshot` next to `path`, check the copy and move it into place.
+fn write_checked(snapshot: &Path, path: &Path) -> Result<DatabaseExport, ExportError> {
+    let temp = support::temp_path(path).map_err(copy_failed)?;
+    let copied = fs::copy(snapshot, &temp).and_then(|_| File::open(&temp)?.sync_all());
+    if let Err(e) = copied {
+        let _ = fs::remove_file(&temp);
+        return Err(copy_failed(format!(
+            "Failed to write {}: {e}",
+            path.display()
+        )));
+    }
+    let checked = integrity_problems(&temp).and_then(|problems| {
+        if problems.is_empty() {
+            return row_counts(&temp);
+        }
+        // The copy is bad; whether the database is too decides whose fault it is.
+        Err(match integrity_problems(snapshot) {
+            Ok(source) if source.is_empty() => {
+                copy_failed("The copy failed the integrity check, though the database passes it")
+            }
+            Ok(source) => ExportError::SourceCorrupt {
+                message: "The database is corrupt; it failed the integrity check".into(),
+                problems: source,
+            },
+            Err(e) => e,
+        })
+    });
+    let row_counts = match checked {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(copy_failed(format!(
+            "Failed to write {}: {e}",
+            path.display()
+        )));
+    }
+    Ok(DatabaseExport {
+        bytes: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+        path: path.to_path_buf(),
+        row_counts,
+    })
+}
+
+/// Open an existing database; read-write, as one in WAL mode needs its `-shm` file.
+fn open(path: &Path) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+}
+
+/// What `PRAGMA integrity_check` finds wrong with the database at `path`; empty if sound.
+fn integrity_problems(path: &Path) -> Result<Vec<String>, ExportError> {
+    let check = || -> rusqlite::Result<Vec<String>> {
+        let connection = open(path)?;
+        let mut statement =
+            connection.prepare(&format!("PRAGMA integrity_check({MAX_PROBLEMS})"))?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect()
+    };
+    match check() {
+        Ok(rows) if rows == ["ok"] => Ok(Vec::new()),
+        Ok(rows) => Ok(rows),
+        // A file SQLite can't even read as a database fails the check too.
+        Err(rusqlite::Error::SqliteFailure(e, message))
+            if e.code == rusqlite::ErrorCode::NotADatabase
+                || e.code == rusqlite::ErrorCode::DatabaseCorrupt =>
+        {
+            Ok(vec![message.unwrap_or_else(|| e.to_string())])
+        }
+        Err(e) => Err(copy_failed(format!(
+            "Failed to check {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+/// Rows in each of `MAIN_TABLES` the database at `path` has.
+fn row_counts(path: &Path) -> Result<BTreeMap<String, u64>, ExportError> {
+    let count = || -> rusqlite::Result<BTreeMap<String, u64>> {
+        let connection = open(path)?;
+        let mut counts = BTreeMap::new();
+        for table in MAIN_TABLES {
+            let exists: bool = connection.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+                [table],
+                |row| row.get(0),
+            )?;
+            if exists {
+                let rows: u64 = connection.query_row(
+                    &format!("SELECT COUNT(*) FROM \"{table}\""),
+                    [],
+                    |row| row.get(0),
+                )?;
+                counts.insert(table.to_string(), rows);
+            }
+        }
+        Ok(counts)
+    };
+    count().map_err(|e| copy_failed(format!("Failed to count rows in {}: {e}", path.display())))
+}
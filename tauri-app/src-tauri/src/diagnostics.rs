@@ -0,0 +1,195 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Environment report for the Diagnostics panel and support bundles.
+//!
+//! Each probe that can fail is reported on its own, so one missing piece (no webview
+//! version, an unreadable disk) never costs the rest of the report.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use sysinfo::System;
+use tauri::{AppHandle, Manager};
+
+use crate::health::HealthLatency;
+use crate::startupmetrics::{self, StartupRun};
+use crate::supervisor::{BackendStatus, Supervisor};
+use crate::{auth, diskspace, endpoint, shelllog, support, unix_millis};
+
+/// A probe's result, or `{"unavailable": reason}`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum Probe<T> {
+    Available(T),
+    Unavailable { unavailable: String },
+}
+
+impl<T, E: std::fmt::Display> From<Result<T, E>> for Probe<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Available(value),
+            Err(e) => Self::Unavailable {
+                unavailable: e.to_string(),
+            },
+        }
+    }
+}
+
+/// The backend executable as found on disk.
+#[derive(Debug, Serialize)]
+pub(crate) struct ExeInfo {
+    pub path: PathBuf,
+    pub exists: bool,
+    pub bytes: Option<u64>,
+    pub modified_ms: Option<u64>,
+}
+
+/// Payload of `collect_diagnostics`.
+#[derive(Debug, Serialize)]
+pub(crate) struct Diagnostics {
+    pub app_version: String,
+    pub tauri_version: &'static str,
+    pub webview_version: Probe<String>,
+    pub os: &'static str,
+    pub os_version: Probe<String>,
+    pub arch: &'static str,
+    pub resource_dir: Probe<PathBuf>,
+    pub app_data_dir: Probe<PathBuf>,
+    pub app_log_dir: Probe<PathBuf>,
+    pub shell_log: Probe<PathBuf>,
+    pub backend_exe: Probe<ExeInfo>,
+    /// Where requests go: the managed backend or a configured external one.
+    pub backend_url: String,
+    pub backend_host: Probe<String>,
+    pub backend_port: Probe<u16>,
+    pub status: BackendStatus,
+    pub restart_count: u32,
+    pub last_health_latency_ms: Probe<f64>,
+    /// Free space on the volume holding `app_data_dir`.
+    pub free_disk_bytes: Probe<u64>,
+    /// Phase timings of the latest backend launch.
+    pub last_startup: Probe<StartupRun>,
+}
+
+/// Gather the report. `backend_exe` is where the executable should be, whether or not it
+/// exists. Blocks while free space is measured.
+pub(crate) fn collect(app: &AppHandle, backend_exe: Result<PathBuf, String>) -> Diagnostics {
+    let paths = app.path();
+    let app_data_dir = paths.app_data_dir();
+    let free_disk_bytes = match &app_data_dir {
+        Ok(dir) => diskspace::free_space(dir).into(),
+        Err(e) => Probe::Unavailable {
+            unavailable: format!("No app data dir: {e}"),
+        },
+    };
+    let backend_url = endpoint::base_url(app);
+    let addr = endpoint::socket_addr(&backend_url)
+        .ok_or_else(|| format!("No TCP address in {backend_url}"));
+    let supervisor = app.state::<Supervisor>();
+    let last_latency = app
+        .state::<HealthLatency>()
+        .history()
+        .samples
+        .last()
+        .map(|sample| sample.latency_ms)
+        .ok_or("No health check yet");
+
+    Diagnostics {
+        app_version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION,
+        webview_version: tauri::webview_version().into(),
+        os: std::env::consts::OS,
+        os_version: System::long_os_version().ok_or("Unknown").into(),
+        arch: std::env::consts::ARCH,
+        resource_dir: paths.resource_dir().into(),
+        app_data_dir: app_data_dir.into(),
+        app_log_dir: paths.app_log_dir().into(),
+        shell_log: shelllog::file().into(),
+        backend_exe: backend_exe.map(|path| exe_info(&path)).into(),
+        backend_host: addr.clone().map(|(host, _)| host).into(),
+        backend_port: addr.map(|(_, port)| port).into(),
+        backend_url,
+        status: supervisor.status(),
+        restart_count: supervisor.stats().count,
+        last_health_latency_ms: last_latency.into(),
+        free_disk_bytes,
+        last_startup: startupmetrics::latest(app)
+            .ok_or("No backend launch recorded yet")
+            .into(),
+    }
+}
+
+/// `report` as a Markdown table to paste into a GitHub issue. The home directory becomes
+/// `~`, and the auth token and anything else that looks like a credential is masked.
+pub(crate) fn markdown(report: &Diagnostics, home: Option<&Path>) -> String {
+    let mut value = serde_json::to_value(report).unwrap_or_default();
+    support::redact_secret_fields(&mut value);
+    let token = auth::token();
+    let home = home.map(|home| home.to_string_lossy().into_owned());
+    let mut rows = Vec::new();
+    flatten("", &value, &mut rows);
+
+    let mut out = String::from("<details>\n<summary>Invoicator diagnostics</summary>\n\n");
+    out.push_str("| Item | Value |\n| --- | --- |\n");
+    for (name, cell) in rows {
+        let mut cell = if name == "backend_url" {
+            support::without_credentials(&cell)
+        } else {
+            cell
+        };
+        if let Some(token) = token.as_deref().filter(|t| !t.is_empty()) {
+            cell = cell.replace(token, "***");
+        }
+        if let Some(home) = home.as_deref().filter(|h| !h.is_empty()) {
+            cell = cell.replace(home, "~");
+        }
+        let cell = cell.replace('|', "\\|").replace(['\r', '\n'], " ");
+        out.push_str(&format!("| {name} | {cell} |\n"));
+    }
+    out.push_str("\n</details>\n");
+    out
+}
+
+/// One `(dotted.name, text)` row per leaf of `value`; an unavailable probe is one row.
+fn flatten(name: &str, value: &Value, rows: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            if let (1, Some(Value::String(reason))) = (object.len(), object.get("unavailable")) {
+                rows.push((name.to_string(), format!("_unavailable: {reason}_")));
+                return;
+            }
+            for (key, field) in object {
+                let key = match name {
+                    "" => key.clone(),
+                    _ => format!("{name}.{key}"),
+                };
+                flatten(&key, field, rows);
+            }
+        }
+        Value::Null => rows.push((name.to_string(), "_none_".to_string())),
+        Value::String(text) => rows.push((name.to_string(), text.clone())),
+        other => rows.push((name.to_string(), other.to_string())),
+    }
+}
+
+fn exe_info(path: &Path) -> ExeInfo {
+    let metadata = std::fs::metadata(path).ok();
+    ExeInfo {
+        path: path.to_path_buf(),
+        exists: metadata.is_some(),
+        bytes: metadata.as_ref().map(|m| m.len()),
+        modified_ms: metadata.and_then(|m| m.modified().ok()).map(unix_millis),
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Free space on the volume holding `DATA_DIR`.
+//!
+//! SQLite can corrupt the database when the disk fills mid-write, so uploads are refused
+//! below `min_free_space_mb` and a watch task emits `low-disk-space` when space drops under
+//! `low_disk_space_warn_mb`. A network share that doesn't answer in `PROBE_TIMEOUT` is
+//! reported as unknown, and unknown space never refuses anything.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::supervisor::Supervisor;
+use crate::{datadir, settings};
+
+pub(crate) const DEFAULT_FLOOR_MB: u64 = 500;
+pub(crate) const DEFAULT_WARN_MB: u64 = 2048;
+const MB: u64 = 1024 * 1024;
+/// How long a probe may take before the space counts as unknown.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often the watch task checks the data dir.
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Set while a probe that timed out is still blocked on its volume, so later ones don't
+/// pile up behind it.
+static STUCK: AtomicBool = AtomicBool::new(false);
+
+/// When free space is too low, from the `min_free_space_mb` and `low_disk_space_warn_mb`
+/// settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Thresholds {
+    /// Uploads are refused below this; 0 never refuses.
+    pub floor_mb: u64,
+    /// `low-disk-space` is emitted below this.
+    pub warn_mb: u64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            floor_mb: DEFAULT_FLOOR_MB,
+            warn_mb: DEFAULT_WARN_MB,
+        }
+    }
+}
+
+impl Thresholds {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.warn_mb < self.floor_mb {
+            return Err(format!(
+                "Warning threshold ({} MB) must not be below the upload floor ({} MB)",
+                self.warn_mb, self.floor_mb
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Payload of `get_disk_space` and the `low-disk-space` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct DiskSpace {
+    /// The data dir measured.
+    pub path: PathBuf,
+    /// `None` when the volume couldn't be measured in time, e.g. an unreachable share.
+    pub free_bytes: Option<u64>,
+    pub floor_bytes: u64,
+    pub warn_bytes: u64,
+}
+
+impl DiskSpace {
+    /// Known to be below the upload floor.
+    pub(crate) fn below_floor(&self) -> bool {
+        self.free_bytes.is_some_and(|free| free < self.floor_bytes)
+    }
+
+    fn below_warning(&self) -> bool {
+        self.free_bytes.is_some_and(|free| free < self.warn_bytes)
+    }
+}
+
+/// Bytes available to us on the volume holding `dir`. Blocks, possibly for long on a
+/// network share.
+pub(crate) fn free_space(dir: &Path) -> Result<u64, String> {
+    // The data dir may not exist yet on first launch; its parent will.
+    let dir = dir
+        .ancestors()
+        .find(|dir| dir.exists())
+        .ok_or_else(|| format!("{} has no existing parent", dir.display()))?;
+    imp::free_space(dir).map_err(|e| format!("Free space of {} unknown: {e}", dir.display()))
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    // The `statvfs` field types differ between Linux and macOS.
+    #[allow(clippy::unnecessary_cast)]
+    pub(super) fn free_space(dir: &Path) -> io::Result<u64> {
+        let path = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: `path` is NUL-terminated and `stat` is a valid out-pointer.
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Blocks free to unprivileged users, in fragment-size units.
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    pub(super) fn free_space(dir: &Path) -> io::Result<u64> {
+        let path: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut available = 0u64;
+        // SAFETY: `path` is NUL-terminated and the unused totals may be null. Works for
+        // UNC paths as well as drive letters.
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                path.as_ptr(),
+                &mut available,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(available)
+    }
+}
+
+/// Measure the data dir's volume against the thresholds in settings, giving up after
+/// `PROBE_TIMEOUT`.
+pub(crate) async fn probe(app: &AppHandle) -> Result<DiskSpace, String> {
+    let path = datadir::path(app)?;
+    let thresholds = settings::current(app).disk_space_thresholds();
+    Ok(DiskSpace {
+        free_bytes: free_bytes(path.clone()).await,
+        path,
+        floor_bytes: thresholds.floor_mb.saturating_mul(MB),
+        warn_bytes: thresholds.warn_mb.saturating_mul(MB),
+    })
+}
+
+async fn free_bytes(dir: PathBuf) -> Option<u64> {
+    if STUCK.load(Ordering::SeqCst) {
+        return None;
+    }
+    let done = Arc::new(AtomicBool::new(false));
+    let finished = done.clone();
+    let shown = dir.display().to_string();
+    let probe = tauri::async_runtime::spawn_blocking(move || {
+        let free = free_space(&dir);
+        finished.store(true, Ordering::SeqCst);
+        STUCK.store(false, Ordering::SeqCst);
+        free
+    });
+    match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+        Ok(Ok(Ok(bytes))) => Some(bytes),
+        Ok(Ok(Err(e))) => {
+            log::debug!("{}", e);
+            None
+        }
+        Ok(Err(e)) => {
+            log::warn!("Free space probe of {} failed: {}", shown, e);
+            None
+        }
+        Err(_) => {
+            STUCK.store(true, Ordering::SeqCst);
+            // It may have finished between the timeout and the store above.
+            if done.load(Ordering::SeqCst) {
+                STUCK.store(false, Ordering::SeqCst);
+            }
+            log::warn!(
+                "Free space probe of {} took over {:?}; treating it as unknown",
+                shown,
+                PROBE_TIMEOUT
+            );
+            None
+        }
+    }
+}
+
+/// Log and announce `space` if it is under the warning threshold.
+pub(crate) fn warn_if_low(app: &AppHandle, space: &DiskSpace) -> bool {
+    if !space.below_warning() {
+        return false;
+    }
+    log::warn!(
+        "Low disk space for {}: {} MB free, warning below {} MB",
+        space.path.display(),
+        space.free_bytes.unwrap_or_default() / MB,
+        space.warn_bytes / MB
+    );
+    let _ = app.emit("low-disk-space", space);
+    true
+}
+
+/// Check the data dir every `WATCH_INTERVAL` and emit `low-disk-space` each time it drops
+/// under the warning threshold; not again until it has recovered.
+pub(crate) fn start_watch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let exited = app.state::<Supervisor>().exited();
+        tokio::pin!(exited);
+        let mut warned = false;
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+                _ = &mut exited => return,
+            }
+            let Ok(space) = probe(&app).await else {
+                continue;
+            };
+            // Unknown space keeps the last state rather than re-arming the warning.
+            if space.free_bytes.is_none() {
+                continue;
+            }
+            if !space.below_warning() {
+                warned = false;
+            } else if !warned {
+                warned = warn_if_low(&app, &space);
+            }
+        }
+    });
+}
@@ -0,0 +1,205 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `download_to_file`: stream a backend response straight to a file on disk.
+//!
+//! The body goes to a temp file next to the destination, which is renamed into place
+//! only once the download is complete, so an interrupted one never leaves a partial file
+//! at the final path.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWriteExt;
+
+use crate::concurrency::{self, Lane};
+use crate::proxy::{self, ProxyError, TimeoutClass};
+use crate::transport::Request;
+use crate::{auth, cancel, config, endpoint};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+/// Merged PDFs are built on request, so the first byte can take a while.
+const DOWNLOAD_TIMEOUT_CLASS: TimeoutClass = TimeoutClass::Streaming;
+/// How much of an error response to show.
+const MAX_ERROR_BODY_BYTES: usize = 4096;
+
+/// Payload of the `download-progress` event.
+#[derive(Clone, Debug, Serialize)]
+struct DownloadProgress {
+    path: String,
+    destination: PathBuf,
+    received_bytes: u64,
+    /// `None` when the backend sends no `Content-Length`.
+    total_bytes: Option<u64>,
+}
+
+/// What `download_to_file` wrote.
+#[derive(Debug, Serialize)]
+pub(crate) struct Downloaded {
+    pub bytes: u64,
+    /// Hex SHA-256 of the file's contents.
+    pub sha256: String,
+}
+
+/// Deletes the temp file unless the download was committed.
+struct TempFile {
+    path: PathBuf,
+    committed: bool,
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// GET `path` (with `query`) from the backend and write the body to `destination`.
+///
+/// With a `request_id`, `cancel_backend_request` aborts the download and removes the
+/// partial file.
+pub(crate) async fn download(
+    app: &AppHandle,
+    path: &str,
+    query: &BTreeMap<String, String>,
+    destination: &Path,
+    request_id: Option<&str>,
+) -> Result<Downloaded, ProxyError> {
+    match cancel::run(
+        app,
+        request_id,
+        write_to_file(app, path, query, destination),
+    )
+    .await?
+    {
+        Some(result) => Ok(result?),
+        None => Err(ProxyError::cancelled(format!(
+            "Download of {path} was cancelled"
+        ))),
+    }
+}
+
+async fn write_to_file(
+    app: &AppHandle,
+    path: &str,
+    query: &BTreeMap<String, String>,
+    destination: &Path,
+) -> Result<Downloaded, String> {
+    proxy::validate_path(path)?;
+    let request_path = proxy::with_query(path, query)?;
+    let file_name = destination
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file path", destination.display()))?;
+    let dir = destination
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    // Same directory, so the final rename never crosses file systems.
+    let temp = TempFile {
+        path: dir.join(format!(
+            ".{}.{}.part",
+            file_name.to_string_lossy(),
+            auth::random_hex(4)?
+        )),
+        committed: false,
+    };
+
+    let transport = endpoint::transport(app);
+    let timeout = config::request_timeout(app, DOWNLOAD_TIMEOUT_CLASS);
+    let transfer = async {
+        let _permit = concurrency::acquire(app, Lane::Requests).await;
+        let mut resp = transport
+            .open(Request::new(reqwest::Method::GET, &request_path, timeout))
+            .await
+            .map_err(|e| format!("GET {path} failed: {e}"))?;
+        if !resp.is_success() {
+            let mut body = Vec::new();
+            while let Ok(Some(chunk)) = resp.chunk().await {
+                body.extend_from_slice(&chunk);
+                if body.len() >= MAX_ERROR_BODY_BYTES {
+                    break;
+                }
+            }
+            body.truncate(MAX_ERROR_BODY_BYTES);
+            return Err(format!(
+                "GET {path} returned HTTP {}: {}",
+                resp.status,
+                String::from_utf8_lossy(&body).trim()
+            ));
+        }
+
+        let mut file = tokio::fs::File::create(&temp.path)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", temp.path.display()))?;
+        let mut progress = DownloadProgress {
+            path: path.to_string(),
+            destination: destination.to_path_buf(),
+            received_bytes: 0,
+            total_bytes: resp.content_length(),
+        };
+        let mut hasher = Sha256::new();
+        let mut last_progress = Instant::now();
+        let _ = app.emit("download-progress", progress.clone());
+        while let Some(chunk) = resp
+            .chunk()
+            .await
+            .map_err(|e| format!("Connection lost while downloading {path}: {e}"))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {e}", temp.path.display()))?;
+            hasher.update(&chunk);
+            progress.received_bytes += chunk.len() as u64;
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                last_progress = Instant::now();
+                let _ = app.emit("download-progress", progress.clone());
+            }
+        }
+        file.sync_all()
+            .await
+            .map_err(|e| format!("Failed to write {}: {e}", temp.path.display()))?;
+        let _ = app.emit("download-progress", progress.clone());
+        let digest = hasher.finalize();
+        Ok(Downloaded {
+            bytes: progress.received_bytes,
+            sha256: digest.iter().map(|b| format!("{b:02x}")).collect(),
+        })
+    };
+    let downloaded = tokio::time::timeout(timeout, transfer)
+        .await
+        .map_err(|_| {
+            format!(
+                "Download of {path} timed out after {} ({} timeout)",
+                proxy::describe(timeout),
+                DOWNLOAD_TIMEOUT_CLASS.name()
+            )
+        })??;
+
+    let mut temp = temp;
+    tokio::fs::rename(&temp.path, destination)
+        .await
+        .map_err(|e| format!("Failed to move download to {}: {e}", destination.display()))?;
+    temp.committed = true;
+    log::info!(
+        "Downloaded {} to {} ({} bytes)",
+        path,
+        destination.display(),
+        downloaded.bytes
+    );
+    Ok(downloaded)
+}
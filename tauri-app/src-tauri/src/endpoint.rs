@@ -0,0 +1,80 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Where the backend listens: the bundled one we spawn, or one the user runs elsewhere.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::port;
+use crate::transport::BackendTransport;
+
+/// How the app reaches its backend, reported alongside status and health events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Connection {
+    /// Spawned and supervised by the app.
+    Managed,
+    /// Started outside the app: a configured remote backend, or the dev-mode one.
+    External,
+}
+
+/// The configured external backend URL, if the app attaches instead of spawning.
+pub(crate) fn external_url(app: &AppHandle) -> Option<String> {
+    app.state::<AppConfig>().external_backend.clone()
+}
+
+pub(crate) fn connection(app: &AppHandle) -> Connection {
+    if cfg!(debug_assertions) || external_url(app).is_some() {
+        Connection::External
+    } else {
+        Connection::Managed
+    }
+}
+
+/// Base URL of the backend over TCP, without a trailing slash, for the webview.
+pub(crate) fn base_url(app: &AppHandle) -> String {
+    external_url(app).unwrap_or_else(|| port::base_url(app))
+}
+
+/// How the shell's own requests reach the backend: the external URL, else the managed
+/// backend's socket when it has one, else its TCP port.
+pub(crate) fn transport(app: &AppHandle) -> BackendTransport {
+    if let Some(url) = external_url(app) {
+        return BackendTransport::Tcp(url);
+    }
+    #[cfg(unix)]
+    if let Some(socket) = crate::transport::current_socket(app) {
+        return BackendTransport::Unix(socket);
+    }
+    BackendTransport::Tcp(port::base_url(app))
+}
+
+/// `url` normalised to `scheme://host[:port][/path]`, or an error unless it is http(s).
+pub(crate) fn normalize(url: &str) -> Result<String, String> {
+    let parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid backend URL {url:?}: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Err(format!(
+            "Backend URL must be http(s)://host[:port], got {url:?}"
+        ));
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+/// Host and port the backend at `base` listens on, for bare TCP probes.
+pub(crate) fn socket_addr(base: &str) -> Option<(String, u16)> {
+    let url = reqwest::Url::parse(base).ok()?;
+    Some((url.host_str()?.to_string(), url.port_or_known_default()?))
+}
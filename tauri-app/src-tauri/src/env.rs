@@ -0,0 +1,84 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The environment the backend is spawned with.
+//!
+//! The backend starts from an empty environment: stray `PYTHONPATH`, `PYTHONHOME`, proxy or
+//! locale variables from the desktop session break the PyInstaller bundle.
+
+/// Inherited from the app's environment when set.
+#[cfg(unix)]
+const INHERITED: &[&str] = &["PATH", "HOME", "TMPDIR", "LANG"];
+/// `SystemRoot` is needed by Winsock and `TEMP`/`TMP` by the temp file APIs.
+#[cfg(windows)]
+const INHERITED: &[&str] = &[
+    "PATH",
+    "USERPROFILE",
+    "TEMP",
+    "TMP",
+    "LANG",
+    "SystemRoot",
+    "WINDIR",
+];
+
+/// Variables reqwest and most HTTP stacks take a proxy from.
+#[cfg(unix)]
+const PROXY_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+/// Windows env names ignore case.
+#[cfg(windows)]
+const PROXY_VARS: &[&str] = &["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"];
+
+/// Names (not values, which may hold credentials) of the proxy variables set for the
+/// app. Requests to the backend ignore them.
+pub(crate) fn proxy_vars() -> Vec<String> {
+    PROXY_VARS
+        .iter()
+        .filter(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Build the backend's environment: the allowlist, the user's extra `forward` names, then
+/// the variables the app manages, which always win.
+pub(crate) fn backend_env(forward: &[String], managed: &[(&str, String)]) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = Vec::new();
+    let names = INHERITED
+        .iter()
+        .copied()
+        .chain(forward.iter().map(String::as_str));
+    for name in names {
+        if managed.iter().any(|(managed, _)| *managed == name) {
+            continue;
+        }
+        if env.iter().any(|(existing, _)| existing == name) {
+            continue;
+        }
+        if let Ok(value) = std::env::var(name) {
+            env.push((name.to_string(), value));
+        }
+    }
+    env.extend(
+        managed
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone())),
+    );
+    env
+}
@@ -0,0 +1,65 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backend lifecycle events, with the latest one kept for listeners that attach late.
+//!
+//! `setup` can emit `backend-ready` before the webview has registered its listeners; the
+//! frontend calls `get_last_backend_event` after registering, and each page load gets
+//! the latest event replayed.
+
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Webview};
+
+use crate::unix_millis;
+
+/// A lifecycle event as it was emitted.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct LastBackendEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub at_ms: u64,
+}
+
+/// The most recent lifecycle event, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct BackendEvents(Mutex<Option<LastBackendEvent>>);
+
+impl BackendEvents {
+    pub(crate) fn last(&self) -> Option<LastBackendEvent> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Emit a backend lifecycle event (`backend-ready`, `backend-error`, `backend-crashed`, ...)
+/// to every window and remember it for late listeners.
+pub(crate) fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    let value = serde_json::to_value(&payload).unwrap_or(serde_json::Value::Null);
+    *app.state::<BackendEvents>().0.lock().unwrap() = Some(LastBackendEvent {
+        event: event.to_string(),
+        payload: value,
+        at_ms: unix_millis(SystemTime::now()),
+    });
+    let _ = app.emit(event, payload);
+}
+
+/// Send the latest lifecycle event again, to `webview` only.
+pub(crate) fn replay<R: Runtime>(webview: &Webview<R>) {
+    let Some(last) = webview.state::<BackendEvents>().last() else {
+        return;
+    };
+    let _ = webview.emit_to(webview.label(), &last.event, last.payload);
+}
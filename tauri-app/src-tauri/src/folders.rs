@@ -0,0 +1,73 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Open the app's log and data folders in the OS file manager, for support requests.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::datadir;
+
+/// Folders users are asked to send files from.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Folder {
+    Logs,
+    Data,
+}
+
+/// Why a folder could not be shown.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum OpenFolderError {
+    /// The platform has no such directory for this app.
+    Unresolved { message: String },
+    /// The folder did not exist and could not be created.
+    CreateFailed { path: PathBuf, message: String },
+    /// There is no file manager to hand the folder to, e.g. Linux without xdg-open.
+    /// `path` is still worth showing so the user can browse there themselves.
+    NoOpener { path: PathBuf, message: String },
+    /// The file manager was found but failed.
+    OpenFailed { path: PathBuf, message: String },
+}
+
+/// Resolve `folder`, create it if missing and open it in the file manager.
+pub(crate) fn open(app: &AppHandle, folder: Folder) -> Result<PathBuf, OpenFolderError> {
+    let resolved = match folder {
+        Folder::Logs => app.path().app_log_dir().map_err(|e| e.to_string()),
+        Folder::Data => datadir::path(app),
+    };
+    let path = resolved.map_err(|e| OpenFolderError::Unresolved {
+        message: format!("Failed to resolve the {folder:?} folder: {e}"),
+    })?;
+    std::fs::create_dir_all(&path).map_err(|e| OpenFolderError::CreateFailed {
+        message: format!("Failed to create {}: {e}", path.display()),
+        path: path.clone(),
+    })?;
+
+    match app.opener().open_path(path.to_string_lossy(), None::<&str>) {
+        Ok(()) => Ok(path),
+        Err(tauri_plugin_opener::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(OpenFolderError::NoOpener {
+                message: format!("No file manager to open {} with: {e}", path.display()),
+                path,
+            })
+        }
+        Err(e) => Err(OpenFolderError::OpenFailed {
+            message: format!("Failed to open {}: {e}", path.display()),
+            path,
+        }),
+    }
+}
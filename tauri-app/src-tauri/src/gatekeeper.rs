@@ -0,0 +1,148 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! macOS Gatekeeper checks for the bundled backend.
+//!
+//! A .dmg downloaded outside the App Store can leave `com.apple.quarantine` on the embedded
+//! backend. macOS then refuses to run it and the child exits the moment it is spawned.
+
+use serde::Serialize;
+
+/// What the backend executable looks like to Gatekeeper, for diagnostics.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ExeState {
+    pub quarantined: bool,
+    pub executable: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::process::Command;
+    use std::time::Duration;
+
+    use tauri_plugin_shell::process::TerminatedPayload;
+
+    use super::ExeState;
+    use crate::supervisor::StartupError;
+
+    const QUARANTINE_ATTR: &str = "com.apple.quarantine";
+    /// Exits faster than this are treated as macOS refusing to run the backend.
+    pub(crate) const INSTANT_EXIT: Duration = Duration::from_secs(3);
+
+    fn is_quarantined(path: &Path) -> bool {
+        let (Ok(path), Ok(name)) = (
+            CString::new(path.as_os_str().as_bytes()),
+            CString::new(QUARANTINE_ATTR),
+        ) else {
+            return false;
+        };
+        // SAFETY: both strings are NUL-terminated; a null buffer of size 0 only queries
+        // the attribute's size.
+        let size = unsafe {
+            libc::getxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+                0,
+                libc::XATTR_NOFOLLOW,
+            )
+        };
+        size >= 0
+    }
+
+    fn is_executable(path: &Path) -> bool {
+        std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+    }
+
+    pub(crate) fn inspect(exe: &Path) -> ExeState {
+        ExeState {
+            quarantined: is_quarantined(exe),
+            executable: is_executable(exe),
+        }
+    }
+
+    /// Clear quarantine from the whole backend directory (the PyInstaller libraries carry
+    /// it too) and restore the executable bit, logging anything we could not fix.
+    pub(crate) fn preflight(backend_dir: &Path, exe: &Path) {
+        let state = inspect(exe);
+        if state.quarantined {
+            log::warn!(
+                "Backend executable is quarantined, clearing {}",
+                QUARANTINE_ATTR
+            );
+            match Command::new("xattr")
+                .args(["-dr", QUARANTINE_ATTR])
+                .arg(backend_dir)
+                .status()
+            {
+                Ok(status) if status.success() => {}
+                Ok(status) => log::warn!("Clearing quarantine failed ({})", status),
+                Err(e) => log::warn!("Clearing quarantine failed: {}", e),
+            }
+        }
+        if !state.executable {
+            log::warn!("Backend executable lost its executable bit, restoring it");
+            let result = std::fs::metadata(exe).and_then(|m| {
+                let mut permissions = m.permissions();
+                permissions.set_mode(permissions.mode() | 0o755);
+                std::fs::set_permissions(exe, permissions)
+            });
+            if let Err(e) = result {
+                log::warn!("Failed to make the backend executable: {}", e);
+            }
+        }
+    }
+
+    /// Explain an exit right after spawn as Gatekeeper blocking the backend, if the
+    /// executable's state or the kill signal point that way.
+    pub(crate) fn explain_instant_exit(
+        exe: &Path,
+        status: &TerminatedPayload,
+    ) -> Option<StartupError> {
+        let state = inspect(exe);
+        let (code, message) = if state.quarantined {
+            (
+                "macos_quarantined",
+                "macOS blocked the bundled backend because the app is quarantined. \
+                 Move Invoicator to Applications, open it once with right-click > Open, \
+                 then restart it.",
+            )
+        } else if !state.executable {
+            (
+                "macos_not_executable",
+                "The bundled backend is not executable. Reinstall Invoicator from the .dmg.",
+            )
+        } else if status.signal == Some(libc::SIGKILL) {
+            (
+                "macos_killed_at_launch",
+                "macOS stopped the bundled backend at launch, most likely a Gatekeeper or \
+                 code signature check. Reinstall Invoicator from the .dmg.",
+            )
+        } else {
+            return None;
+        };
+        Some(StartupError::Gatekeeper {
+            code,
+            message: message.to_string(),
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) use imp::{explain_instant_exit, inspect, preflight, INSTANT_EXIT};
@@ -0,0 +1,388 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Requests to the backend's `/health` endpoint.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth;
+use crate::transport::{BackendTransport, Response, TransportError};
+use crate::unix_millis;
+
+/// Latency samples kept for `get_health_latency_history`.
+const LATENCY_HISTORY_LEN: usize = 100;
+/// Characters of an unhealthy answer's body passed on to the frontend.
+const BODY_EXCERPT_CHARS: usize = 200;
+/// How long a health result is served to the frontend before asking the backend again.
+const RESULT_TTL: Duration = Duration::from_secs(2);
+
+/// What `/health` reports. Every field but `status` is optional: the current backend only
+/// sends `status`, and older ones answer with plain text.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BackendHealth {
+    pub status: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub db: Option<String>,
+    #[serde(default)]
+    pub ocr_engine: Option<String>,
+    /// Echo of our auth token, proving the answer comes from the backend we spawned; only
+    /// sent back to requests that already carry it.
+    #[serde(default, skip_serializing)]
+    pub launch_token: Option<String>,
+}
+
+impl BackendHealth {
+    /// Read a `/health` body, JSON or plain text.
+    fn parse(body: &str) -> Self {
+        serde_json::from_str(body).unwrap_or_else(|_| Self {
+            status: body.trim().to_string(),
+            ..Self::default()
+        })
+    }
+}
+
+/// Whether a healthy answer echoed the token of the backend we spawned.
+pub(crate) fn is_our_backend(health: &BackendHealth) -> bool {
+    health.launch_token.is_some() && health.launch_token == auth::token()
+}
+
+/// Why the backend could not be reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UnreachableKind {
+    /// Refused or reset, as it is while the backend (re)starts.
+    Refused,
+    Timeout,
+    Dns,
+    Other,
+}
+
+impl UnreachableKind {
+    fn of(e: &TransportError) -> Self {
+        if e.timed_out {
+            Self::Timeout
+        } else if e.dns_failed {
+            Self::Dns
+        } else if e.connection_failed {
+            Self::Refused
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Result of one health poll, as `check_backend_health_detailed` reports it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub(crate) enum HealthCheck {
+    Healthy(BackendHealth),
+    /// The backend answered with a non-success status.
+    Unhealthy {
+        status: u16,
+        body_excerpt: String,
+    },
+    Unreachable {
+        kind: UnreachableKind,
+        message: String,
+    },
+}
+
+impl HealthCheck {
+    pub(crate) fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy(_))
+    }
+
+    /// Whether restarting the backend could help. A 4xx answer comes from a running
+    /// backend turning our request down, which a restart would not change.
+    pub(crate) fn warrants_restart(&self) -> bool {
+        match self {
+            Self::Healthy(_) => false,
+            Self::Unhealthy { status, .. } => *status >= 500,
+            Self::Unreachable { .. } => true,
+        }
+    }
+
+    pub(crate) fn into_result(self) -> Result<BackendHealth, String> {
+        match self {
+            Self::Healthy(health) => Ok(health),
+            check => Err(check.error().unwrap_or_default()),
+        }
+    }
+
+    /// What went wrong, or `None` if the backend is healthy.
+    pub(crate) fn error(&self) -> Option<String> {
+        match self {
+            Self::Healthy(_) => None,
+            Self::Unhealthy { status, .. } => {
+                Some(format!("Backend health check returned HTTP {status}"))
+            }
+            Self::Unreachable { message, .. } => Some(format!("Backend not reachable: {message}")),
+        }
+    }
+}
+
+/// One request to `/health`, keeping whatever the backend said.
+pub(crate) async fn poll(transport: &BackendTransport, timeout: Duration) -> HealthCheck {
+    match get_health(transport, timeout).await {
+        Ok(resp) if resp.is_success() => HealthCheck::Healthy(BackendHealth::parse(&resp.text())),
+        Ok(resp) => HealthCheck::Unhealthy {
+            status: resp.status,
+            body_excerpt: resp
+                .text()
+                .trim()
+                .chars()
+                .take(BODY_EXCERPT_CHARS)
+                .collect(),
+        },
+        Err(e) => HealthCheck::Unreachable {
+            kind: UnreachableKind::of(&e),
+            message: e.to_string(),
+        },
+    }
+}
+
+/// `GET /health`, timed. Only the first request after a launch pays for connecting; the
+/// ones after it reuse the pooled connection.
+async fn get_health(
+    transport: &BackendTransport,
+    timeout: Duration,
+) -> Result<Response, TransportError> {
+    let started = Instant::now();
+    let result = transport.get("/health", timeout).await;
+    log::debug!(
+        "/health over {} answered in {:.1} ms",
+        transport,
+        started.elapsed().as_secs_f64() * 1000.0
+    );
+    result
+}
+
+/// What `/ready` reports once `/health` answers.
+#[derive(Debug, Default, Deserialize)]
+struct Readiness {
+    #[serde(default)]
+    db: Option<String>,
+    #[serde(default)]
+    migrations: Option<String>,
+}
+
+/// Result of one `/ready` poll.
+pub(crate) enum ReadyPoll {
+    /// The database is up and migrations are done.
+    Ready,
+    /// Older backends have no `/ready`; `/health` is all they offer.
+    Missing,
+    /// Not ready yet: the HTTP status, if any, and what the backend said.
+    Pending(Option<u16>, String),
+}
+
+/// One request to `/ready`, which must report `{"db": "ok", "migrations": "done"}`.
+pub(crate) async fn poll_ready(transport: &BackendTransport, timeout: Duration) -> ReadyPoll {
+    let resp = match transport.get("/ready", timeout).await {
+        Ok(resp) => resp,
+        Err(e) => return ReadyPoll::Pending(None, format!("/ready unreachable: {e}")),
+    };
+    if resp.status == 404 {
+        return ReadyPoll::Missing;
+    }
+    if !resp.is_success() {
+        return ReadyPoll::Pending(
+            Some(resp.status),
+            format!("/ready returned HTTP {}", resp.status),
+        );
+    }
+    let readiness: Readiness = resp.json().unwrap_or_default();
+    let db = readiness.db.as_deref().unwrap_or("unknown");
+    let migrations = readiness.migrations.as_deref().unwrap_or("unknown");
+    if db == "ok" && migrations == "done" {
+        return ReadyPoll::Ready;
+    }
+    ReadyPoll::Pending(
+        Some(resp.status),
+        format!("/ready reports db {db}, migrations {migrations}"),
+    )
+}
+
+/// The parsed `/health` report, or an error if the backend is not healthy.
+pub(crate) async fn fetch(
+    transport: &BackendTransport,
+    timeout: Duration,
+) -> Result<BackendHealth, String> {
+    poll(transport, timeout).await.into_result()
+}
+
+/// The last health check made for the frontend, managed by Tauri.
+///
+/// The lock is held while polling, so components checking at once cost one request: the
+/// ones arriving meanwhile wait for it and take its result.
+#[derive(Default)]
+pub(crate) struct HealthCache(tokio::sync::Mutex<Option<(Instant, HealthCheck)>>);
+
+impl HealthCache {
+    /// The last result if it is younger than `RESULT_TTL`, else a new poll. `force` always
+    /// polls, for an explicit "check again".
+    pub(crate) async fn check(
+        &self,
+        transport: &BackendTransport,
+        timeout: Duration,
+        force: bool,
+    ) -> HealthCheck {
+        let mut last = self.0.lock().await;
+        if let Some((at, check)) = last.as_ref() {
+            if !force && at.elapsed() < RESULT_TTL {
+                return check.clone();
+            }
+        }
+        let check = poll(transport, timeout).await;
+        *last = Some((Instant::now(), check.clone()));
+        check
+    }
+}
+
+/// Round-trip time of one health request.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct LatencySample {
+    pub at_ms: u64,
+    pub latency_ms: f64,
+    /// Whether the backend answered healthy; failures are timed until the error.
+    pub ok: bool,
+    /// Taken while waiting for a fresh backend to become ready.
+    pub startup: bool,
+}
+
+/// Summary of the steady-state samples that succeeded.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct LatencyStats {
+    pub count: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+}
+
+/// Payload of `get_health_latency_history`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct LatencyHistory {
+    /// Oldest first.
+    pub samples: Vec<LatencySample>,
+    pub steady_state: Option<LatencyStats>,
+}
+
+/// The last `LATENCY_HISTORY_LEN` health round trips, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct HealthLatency(Mutex<VecDeque<LatencySample>>);
+
+impl HealthLatency {
+    pub(crate) fn record(&self, latency: Duration, ok: bool, startup: bool) {
+        let mut samples = self.0.lock().unwrap();
+        if samples.len() == LATENCY_HISTORY_LEN {
+            samples.pop_front();
+        }
+        samples.push_back(LatencySample {
+            at_ms: unix_millis(SystemTime::now()),
+            latency_ms: latency.as_secs_f64() * 1000.0,
+            ok,
+            startup,
+        });
+    }
+
+    pub(crate) fn history(&self) -> LatencyHistory {
+        let samples: Vec<LatencySample> = self.0.lock().unwrap().iter().cloned().collect();
+        let mut steady: Vec<f64> = samples
+            .iter()
+            .filter(|s| s.ok && !s.startup)
+            .map(|s| s.latency_ms)
+            .collect();
+        steady.sort_by(f64::total_cmp);
+        let steady_state = (!steady.is_empty()).then(|| {
+            // Nearest-rank percentile.
+            let p95_rank = (steady.len() * 95).div_ceil(100).max(1);
+            LatencyStats {
+                count: steady.len(),
+                min_ms: steady[0],
+                avg_ms: steady.iter().sum::<f64>() / steady.len() as f64,
+                p95_ms: steady[p95_rank - 1],
+            }
+        });
+        LatencyHistory {
+            samples,
+            steady_state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A backend that answers `/health` slowly on every connection, and how many it got.
+    /// `Connection: close` keeps connections and requests one to one.
+    fn counting_backend() -> (BackendTransport, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let _ = stream.read(&mut [0; 4096]);
+                    // Long enough for every caller to arrive while the first poll is out.
+                    std::thread::sleep(Duration::from_millis(200));
+                    let body = r#"{"status":"healthy"}"#;
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                });
+            }
+        });
+        (BackendTransport::Tcp(base), connections)
+    }
+
+    #[test]
+    fn concurrent_checks_share_one_request() {
+        let (transport, connections) = counting_backend();
+        let cache = Arc::new(HealthCache::default());
+        let checks: Vec<_> = (0..8)
+            .map(|_| {
+                let (cache, transport) = (cache.clone(), transport.clone());
+                tauri::async_runtime::spawn(async move {
+                    cache.check(&transport, Duration::from_secs(5), false).await
+                })
+            })
+            .collect();
+        let results = tauri::async_runtime::block_on(async {
+            let mut results = Vec::new();
+            for check in checks {
+                results.push(check.await.unwrap());
+            }
+            results
+        });
+
+        assert!(results.iter().all(HealthCheck::is_healthy), "{results:?}");
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+}
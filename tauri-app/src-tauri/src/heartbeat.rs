@@ -0,0 +1,98 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Periodic `backend-heartbeat` event for status widgets.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+use crate::supervisor::{SpawnState, Supervisor};
+use crate::transport::BackendTransport;
+use crate::{concurrency, endpoint, running_backend_pid};
+
+pub(crate) const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+/// Shortest interval `set_heartbeat_interval` accepts.
+pub(crate) const MIN_INTERVAL: Duration = Duration::from_secs(1);
+const STATS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Heartbeat interval, managed by Tauri; changes wake the heartbeat task right away.
+pub(crate) struct HeartbeatInterval(pub(crate) watch::Sender<Duration>);
+
+impl Default for HeartbeatInterval {
+    fn default() -> Self {
+        Self(watch::Sender::new(DEFAULT_INTERVAL))
+    }
+}
+
+/// Payload of the `backend-heartbeat` event.
+#[derive(Clone, Debug, Serialize)]
+struct Heartbeat {
+    uptime_secs: Option<u64>,
+    pid: Option<u32>,
+    /// Result of the health monitor's latest poll; the heartbeat sends no request of its own.
+    healthy: Option<bool>,
+    /// Jobs waiting in the backend, from `/stats` when it has one.
+    queue_depth: Option<u64>,
+    /// Calls to the backend in flight and queued in the shell.
+    request_queue: concurrency::QueueStats,
+}
+
+#[derive(Deserialize)]
+struct Stats {
+    queue_depth: Option<u64>,
+}
+
+async fn queue_depth(transport: &BackendTransport) -> Option<u64> {
+    let resp = transport.get("/stats", STATS_TIMEOUT).await.ok()?;
+    if !resp.is_success() {
+        return None;
+    }
+    resp.json::<Stats>().ok()?.queue_depth
+}
+
+/// Emit `backend-heartbeat` every interval while the backend is running and not suspended.
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval_rx = app.state::<HeartbeatInterval>().0.subscribe();
+        let supervisor = app.state::<Supervisor>();
+        let exited = supervisor.exited();
+        tokio::pin!(exited);
+
+        loop {
+            let interval = *interval_rx.borrow_and_update();
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = interval_rx.changed() => continue,
+                _ = &mut exited => return,
+            }
+            if supervisor.state() != SpawnState::Running || supervisor.is_suspended() {
+                continue;
+            }
+            let heartbeat = Heartbeat {
+                uptime_secs: supervisor
+                    .spawned_at()
+                    .and_then(|at| at.elapsed().ok())
+                    .map(|d| d.as_secs()),
+                pid: running_backend_pid(&app),
+                healthy: supervisor.last_health_ok(),
+                queue_depth: queue_depth(&endpoint::transport(&app)).await,
+                request_queue: concurrency::stats(&app),
+            };
+            let _ = app.emit("backend-heartbeat", heartbeat);
+        }
+    });
+}
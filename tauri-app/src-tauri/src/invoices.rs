@@ -0,0 +1,254 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Paged invoice list, cached in the shell so navigating back to the list is instant.
+//!
+//! Pages are kept per query until something may have changed invoices: a mutating call
+//! through `backend_request`, a finished upload or a job completing. A cached page older
+//! than `FRESH_FOR` is returned as is and refreshed in the background; `invoices-updated`
+//! brings the new page if it differs.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::concurrency::{self, Lane};
+use crate::proxy::{self, TimeoutClass};
+use crate::{config, endpoint};
+
+pub(crate) const PATH: &str = "/api/v1/invoices";
+pub(crate) const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+/// Cached pages younger than this are not refreshed.
+const FRESH_FOR: Duration = Duration::from_secs(10);
+/// Pages kept at most; the oldest goes first.
+const MAX_PAGES: usize = 64;
+
+/// One page of the list; also the cache key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub(crate) struct ListQuery {
+    /// Counted from 0.
+    pub page: u32,
+    pub page_size: u32,
+    /// Matched against provider, invoice number and file name by the backend.
+    pub filter: Option<String>,
+}
+
+impl ListQuery {
+    pub(crate) fn new(page: u32, page_size: u32, filter: Option<String>) -> Result<Self, String> {
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(format!(
+                "Page size must be between 1 and {MAX_PAGE_SIZE}, got {page_size}"
+            ));
+        }
+        let filter = filter
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty());
+        Ok(Self {
+            page,
+            page_size,
+            filter,
+        })
+    }
+}
+
+/// Whether a page came from the cache; for checking that caching works.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct CacheDebug {
+    pub hit: bool,
+    pub age_ms: u64,
+    /// A background refresh is under way; `invoices-updated` follows if the page changed.
+    pub revalidating: bool,
+}
+
+/// What `list_invoices` returns and `invoices-updated` carries.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct InvoicePage {
+    #[serde(flatten)]
+    pub query: ListQuery,
+    /// The backend's invoices, passed through untouched.
+    pub invoices: serde_json::Value,
+    /// The page is full, so there may be another one.
+    pub has_more: bool,
+    pub debug: CacheDebug,
+}
+
+impl InvoicePage {
+    fn new(query: ListQuery, invoices: serde_json::Value, debug: CacheDebug) -> Self {
+        let count = invoices.as_array().map_or(0, Vec::len);
+        Self {
+            has_more: count >= query.page_size as usize,
+            query,
+            invoices,
+            debug,
+        }
+    }
+}
+
+struct Cached {
+    invoices: serde_json::Value,
+    fetched_at: Instant,
+}
+
+#[derive(Default)]
+struct Cache {
+    pages: HashMap<ListQuery, Cached>,
+    /// Bumped by `invalidate`, so fetches started before it don't store stale pages.
+    generation: u64,
+    refreshing: HashSet<ListQuery>,
+}
+
+/// Cached invoice pages, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct InvoiceCache(Mutex<Cache>);
+
+fn cache(app: &AppHandle) -> std::sync::MutexGuard<'_, Cache> {
+    app.state::<InvoiceCache>().inner().0.lock().unwrap()
+}
+
+/// The page for `query`, from the cache if we have it.
+pub(crate) async fn list(app: &AppHandle, query: ListQuery) -> Result<InvoicePage, String> {
+    let (cached, generation) = {
+        let cache = cache(app);
+        let cached = cache
+            .pages
+            .get(&query)
+            .map(|c| (c.invoices.clone(), c.fetched_at.elapsed()));
+        (cached, cache.generation)
+    };
+    if let Some((invoices, age)) = cached {
+        let revalidating = age >= FRESH_FOR && refresh_in_background(app, &query);
+        let debug = CacheDebug {
+            hit: true,
+            age_ms: age.as_millis() as u64,
+            revalidating,
+        };
+        return Ok(InvoicePage::new(query, invoices, debug));
+    }
+
+    let invoices = fetch(app, &query).await?;
+    store(app, &query, generation, invoices.clone());
+    let debug = CacheDebug {
+        hit: false,
+        age_ms: 0,
+        revalidating: false,
+    };
+    Ok(InvoicePage::new(query, invoices, debug))
+}
+
+/// Forget every cached page; the next `list_invoices` asks the backend.
+pub(crate) fn invalidate(app: &AppHandle) {
+    let mut cache = cache(app);
+    cache.generation += 1;
+    if !cache.pages.is_empty() {
+        log::debug!("Dropping {} cached invoice page(s)", cache.pages.len());
+        cache.pages.clear();
+    }
+}
+
+/// Refetch `query` and emit `invoices-updated` if it changed. `false` if it can't start.
+fn refresh_in_background(app: &AppHandle, query: &ListQuery) -> bool {
+    let generation = {
+        let mut cache = cache(app);
+        if !cache.refreshing.insert(query.clone()) {
+            return true;
+        }
+        cache.generation
+    };
+    let app = app.clone();
+    let query = query.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = fetch(&app, &query).await;
+        cache(&app).refreshing.remove(&query);
+        match result {
+            Ok(invoices) => {
+                if store(&app, &query, generation, invoices.clone()) {
+                    let debug = CacheDebug {
+                        hit: false,
+                        age_ms: 0,
+                        revalidating: false,
+                    };
+                    let _ = app.emit("invoices-updated", InvoicePage::new(query, invoices, debug));
+                }
+            }
+            Err(e) => log::debug!("Refreshing invoice page {} failed: {}", query.page, e),
+        }
+    });
+    true
+}
+
+/// Cache `invoices` unless `invalidate` ran since `generation`. Returns whether they
+/// differ from the page cached before.
+fn store(app: &AppHandle, query: &ListQuery, generation: u64, invoices: serde_json::Value) -> bool {
+    let mut cache = cache(app);
+    if cache.generation != generation {
+        return false;
+    }
+    if cache.pages.len() >= MAX_PAGES && !cache.pages.contains_key(query) {
+        let oldest = cache
+            .pages
+            .iter()
+            .min_by_key(|(_, c)| c.fetched_at)
+            .map(|(q, _)| q.clone());
+        if let Some(oldest) = oldest {
+            cache.pages.remove(&oldest);
+        }
+    }
+    let changed = cache
+        .pages
+        .get(query)
+        .is_none_or(|c| c.invoices != invoices);
+    cache.pages.insert(
+        query.clone(),
+        Cached {
+            invoices,
+            fetched_at: Instant::now(),
+        },
+    );
+    changed
+}
+
+/// `GET /api/v1/invoices` for one page.
+async fn fetch(app: &AppHandle, query: &ListQuery) -> Result<serde_json::Value, String> {
+    let mut params = BTreeMap::new();
+    params.insert(
+        "skip".to_string(),
+        (u64::from(query.page) * u64::from(query.page_size)).to_string(),
+    );
+    params.insert("limit".to_string(), query.page_size.to_string());
+    if let Some(filter) = &query.filter {
+        params.insert("q".to_string(), filter.clone());
+    }
+    let path = proxy::with_query(PATH, &params)?;
+    let timeout = config::request_timeout(app, TimeoutClass::Normal);
+    let resp = {
+        let _permit = concurrency::acquire(app, Lane::Requests).await;
+        endpoint::transport(app)
+            .get(&path, timeout)
+            .await
+            .map_err(|e| format!("Failed to list invoices: {e}"))?
+    };
+    if !resp.is_success() {
+        return Err(format!("Listing invoices returned HTTP {}", resp.status));
+    }
+    let invoices: serde_json::Value = resp
+        .json()
+        .map_err(|e| format!("Invalid invoice list: {e}"))?;
+    if !invoices.is_array() {
+        return Err("Backend sent an invoice list that is not an array".to_string());
+    }
+    Ok(invoices)
+}
@@ -0,0 +1,128 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows Job Object that takes the backend down with the app.
+//!
+//! The job is created with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`: when our process dies for
+//! any reason (including Task Manager), the OS closes the job handle and kills the backend.
+
+use std::sync::Mutex;
+
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+    SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+};
+use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+/// Owned job handle, closed on drop.
+pub(crate) struct JobObject(HANDLE);
+
+// SAFETY: a job handle is a kernel object reference that may be used from any thread.
+unsafe impl Send for JobObject {}
+unsafe impl Sync for JobObject {}
+
+/// The job every spawned backend is assigned to, kept alive for the app's lifetime.
+pub(crate) struct BackendJob(pub(crate) Mutex<Option<JobObject>>);
+
+impl JobObject {
+    /// Create an anonymous job that kills its processes when the last handle closes.
+    pub(crate) fn kill_on_close() -> Result<Self, String> {
+        // SAFETY: null attributes and name are allowed and create an unnamed job.
+        let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if handle.is_null() {
+            return Err(format!(
+                "Failed to create job object: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let job = Self(handle);
+        job.set_limits(None)?;
+        Ok(job)
+    }
+
+    /// Keep kill-on-close and cap each process's committed memory at `memory_limit` bytes,
+    /// or lift the cap with `None`.
+    pub(crate) fn set_limits(&self, memory_limit: Option<usize>) -> Result<(), String> {
+        // SAFETY: the struct is plain old data, all-zero is a valid "no limits" value.
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        if let Some(bytes) = memory_limit {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            info.ProcessMemoryLimit = bytes;
+        }
+        // SAFETY: `info` is a valid JOBOBJECT_EXTENDED_LIMIT_INFORMATION of the given size.
+        let ok = unsafe {
+            SetInformationJobObject(
+                self.0,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+        };
+        if ok == 0 {
+            return Err(format!(
+                "Failed to configure job object: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn assign(&self, pid: u32) -> Result<(), String> {
+        // SAFETY: OpenProcess has no pointer arguments; a null result is handled below.
+        let process = unsafe { OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid) };
+        if process.is_null() {
+            return Err(format!(
+                "Failed to open backend process {pid}: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        // SAFETY: both handles are valid for the duration of the call.
+        let ok = unsafe { AssignProcessToJobObject(self.0, process) };
+        let error = std::io::Error::last_os_error();
+        // SAFETY: we opened `process` above and close it exactly once.
+        unsafe { CloseHandle(process) };
+        if ok == 0 {
+            return Err(format!("Failed to assign backend to job object: {error}"));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        // SAFETY: we own the handle and close it exactly once.
+        unsafe { CloseHandle(self.0) };
+    }
+}
+
+/// Assign the process `pid` to the app-wide backend job, creating the job on first use.
+pub(crate) fn assign_to_backend_job(job: &BackendJob, pid: u32) -> Result<(), String> {
+    let mut slot = job.0.lock().unwrap();
+    if slot.is_none() {
+        *slot = Some(JobObject::kill_on_close()?);
+    }
+    slot.as_ref().unwrap().assign(pid)
+}
+
+/// Set the per-process memory cap of the app-wide backend job, creating the job on first use.
+pub(crate) fn limit_backend_memory(job: &BackendJob, bytes: Option<usize>) -> Result<(), String> {
+    let mut slot = job.0.lock().unwrap();
+    if slot.is_none() {
+        *slot = Some(JobObject::kill_on_close()?);
+    }
+    slot.as_ref().unwrap().set_limits(bytes)
+}
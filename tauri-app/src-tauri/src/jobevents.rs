@@ -0,0 +1,225 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Forward the backend's per-job server-sent events as Tauri events.
+//!
+//! The webview's own `EventSource` against localhost breaks when the machine sleeps; here
+//! each subscription reconnects with backoff and notices a silent connection by the
+//! backend's keepalives going missing.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::proxy::TimeoutClass;
+use crate::transport::Request;
+use crate::{config, endpoint, invoices};
+
+/// The backend sends a keepalive every 15s; three missed ones mean the connection is dead.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(45);
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const TERMINAL_STATUSES: &[&str] = &["completed", "failed", "expired"];
+
+/// Running subscriptions by job id, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct JobSubscriptions(Mutex<HashMap<String, JoinHandle<()>>>);
+
+/// How a connection to the event stream ended.
+enum StreamEnd {
+    /// The job reached a final state; nothing more will come.
+    Finished,
+    /// The stream ended early or failed; worth reconnecting. `true` if any event arrived.
+    Dropped(bool),
+    /// The backend has no events endpoint or doesn't know the job.
+    Unavailable,
+}
+
+/// Start forwarding the events of `job_id` as `job-progress:{job_id}`. Subscribing twice is
+/// a no-op.
+pub(crate) fn subscribe(app: &AppHandle, job_id: &str) -> Result<(), String> {
+    if job_id.is_empty()
+        || !job_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid job id {job_id:?}"));
+    }
+    let subscriptions = app.state::<JobSubscriptions>();
+    let mut subscriptions = subscriptions.0.lock().unwrap();
+    if subscriptions.contains_key(job_id) {
+        return Ok(());
+    }
+    let task = tauri::async_runtime::spawn(forward(app.clone(), job_id.to_string()));
+    subscriptions.insert(job_id.to_string(), task);
+    Ok(())
+}
+
+/// Stop forwarding the events of `job_id`. Unknown ids are ignored.
+pub(crate) fn unsubscribe(app: &AppHandle, job_id: &str) {
+    if let Some(task) = app
+        .state::<JobSubscriptions>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(job_id)
+    {
+        task.abort();
+    }
+}
+
+/// Drop every subscription; they would outlive the backend they were connected to.
+pub(crate) fn unsubscribe_all(app: &AppHandle) {
+    let tasks: Vec<_> = app
+        .state::<JobSubscriptions>()
+        .0
+        .lock()
+        .unwrap()
+        .drain()
+        .collect();
+    if !tasks.is_empty() {
+        log::info!("Closing {} job event subscription(s)", tasks.len());
+    }
+    for (_, task) in tasks {
+        task.abort();
+    }
+}
+
+async fn forward(app: AppHandle, job_id: String) {
+    let mut delay = INITIAL_RECONNECT_DELAY;
+    loop {
+        match stream_once(&app, &job_id).await {
+            StreamEnd::Finished | StreamEnd::Unavailable => break,
+            StreamEnd::Dropped(received) => {
+                if received {
+                    delay = INITIAL_RECONNECT_DELAY;
+                }
+                log::debug!(
+                    "Job {} event stream dropped, reconnecting in {:?}",
+                    job_id,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    }
+    app.state::<JobSubscriptions>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(&job_id);
+}
+
+/// Read one connection to the event stream until it ends.
+async fn stream_once(app: &AppHandle, job_id: &str) -> StreamEnd {
+    let path = format!("/api/v1/jobs/{job_id}/events");
+    let transport = endpoint::transport(app);
+    let mut resp = match transport
+        .open(Request::new(
+            reqwest::Method::GET,
+            &path,
+            // SSE has no end of its own; running out of this only makes us reconnect.
+            config::request_timeout(app, TimeoutClass::Streaming),
+        ))
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            log::debug!("Job {} event stream unreachable: {}", job_id, e);
+            return StreamEnd::Dropped(false);
+        }
+    };
+    if resp.status == 404 {
+        log::warn!("Backend has no event stream for job {}", job_id);
+        return StreamEnd::Unavailable;
+    }
+    if !resp.is_success() {
+        log::debug!("Job {} event stream returned HTTP {}", job_id, resp.status);
+        return StreamEnd::Dropped(false);
+    }
+
+    let event_name = format!("job-progress:{job_id}");
+    let mut parser = SseParser::default();
+    let mut received = false;
+    loop {
+        let chunk = match tokio::time::timeout(IDLE_TIMEOUT, resp.chunk()).await {
+            Ok(Ok(Some(chunk))) => chunk,
+            Ok(Ok(None)) => return StreamEnd::Dropped(received),
+            Ok(Err(e)) => {
+                log::debug!("Job {} event stream failed: {}", job_id, e);
+                return StreamEnd::Dropped(received);
+            }
+            Err(_) => {
+                log::debug!("Job {} event stream went silent", job_id);
+                return StreamEnd::Dropped(received);
+            }
+        };
+        for data in parser.feed(&chunk) {
+            received = true;
+            let payload = serde_json::from_str(&data).unwrap_or(serde_json::Value::String(data));
+            let _ = app.emit(&event_name, &payload);
+            if is_final(&payload) {
+                // A completed job may have saved an invoice.
+                if payload.get("status").and_then(|s| s.as_str()) == Some("completed") {
+                    invoices::invalidate(app);
+                }
+                return StreamEnd::Finished;
+            }
+        }
+    }
+}
+
+/// Whether a job status event is the last one: the job is done or unknown.
+fn is_final(payload: &serde_json::Value) -> bool {
+    let status = payload.get("status").and_then(|s| s.as_str());
+    payload.get("found").and_then(|f| f.as_bool()) == Some(false)
+        || status.is_some_and(|s| TERMINAL_STATUSES.contains(&s))
+}
+
+/// Splits a `text/event-stream` body into the `data` of each event.
+#[derive(Default)]
+struct SseParser {
+    line: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&self.line)
+                .trim_end_matches('\r')
+                .to_string();
+            self.line.clear();
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+            // Comments (":keepalive"), `event:`, `id:` and `retry:` lines are not needed.
+        }
+        events
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Launch trampoline for the backend on Unix.
+//!
+//! The shell plugin can't run code between fork and exec, so the app spawns itself with
+//! [`EXEC_FLAG`] and the child execs the backend after moving into its own process group
+//! and, on Linux, capping its memory (see `memlimit`).
+//! exec keeps the pid, so the plugin's child is the backend and its pid is the group id.
+
+use std::ffi::{OsStr, OsString};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+
+/// First argument of a trampoline launch.
+const EXEC_FLAG: &str = "--exec-backend";
+
+/// Command line that makes the app exec `exe` with `args`, capped at `memory_limit_mb`.
+pub(crate) fn args(memory_limit_mb: Option<u64>, exe: &Path, args: &[String]) -> Vec<OsString> {
+    let limit = memory_limit_mb.unwrap_or(0).to_string();
+    [OsString::from(EXEC_FLAG), limit.into(), exe.into()]
+        .into_iter()
+        .chain(args.iter().map(OsString::from))
+        .collect()
+}
+
+/// Exec the backend if the app was started as the trampoline. Must run before anything
+/// else in `run()`; only returns if this is a normal launch.
+pub(crate) fn exec_if_requested() {
+    let mut argv = std::env::args_os().skip(1);
+    if argv.next().as_deref() != Some(OsStr::new(EXEC_FLAG)) {
+        return;
+    }
+    let limit_mb = argv
+        .next()
+        .and_then(|limit| limit.to_str()?.parse::<u64>().ok());
+    let (Some(limit_mb), Some(exe)) = (limit_mb, argv.next()) else {
+        eprintln!("{EXEC_FLAG} needs a memory limit and the backend executable");
+        std::process::exit(127);
+    };
+    // Stdio, environment and working directory are already the backend's.
+    let mut command = Command::new(&exe);
+    command.args(argv).process_group(0);
+    #[cfg(target_os = "linux")]
+    if limit_mb > 0 {
+        // SAFETY: exec replaces this process without forking, so the closure runs with
+        // the process in a normal state; setrlimit(2) is async-signal-safe regardless.
+        unsafe {
+            command.pre_exec(move || crate::memlimit::limit_self(limit_mb));
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = limit_mb;
+    let error = command.exec();
+    // Goes to the backend's stderr pipe, so it shows up in the backend log.
+    eprintln!(
+        "Failed to exec backend {}: {error}",
+        Path::new(&exe).display()
+    );
+    std::process::exit(127);
+}
@@ -0,0 +1,283 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Data of the standalone backend we shipped before the app, offered for import once.
+//!
+//! The old backend kept its `DATA_DIR` in `~/.invoice_processor` (Linux, macOS) or
+//! `%LOCALAPPDATA%\invoice_processor_old` (Windows), laid out as ours is. It is looked for
+//! before the first backend launch, while our data dir is still empty, and announced with
+//! `legacy-data-found`; `get_legacy_data` has it for a webview that wasn't listening yet.
+//! Importing copies it over the new backend's fresh files and checks the copy; the legacy
+//! folder itself is never changed.
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::datadir;
+use crate::proxy::TimeoutClass;
+use crate::supervisor::{self, Startup, Supervisor};
+use crate::{config, endpoint, invoices, settings, transport};
+
+#[cfg(not(windows))]
+const LEGACY_DIR_NAME: &str = ".invoice_processor";
+#[cfg(windows)]
+const LEGACY_DIR_NAME: &str = "invoice_processor_old";
+/// Caches and scratch files the import leaves behind.
+const SKIPPED: &[&str] = &["models", "temp", "logs"];
+const PROGRESS_EVENT: &str = "legacy-migration-progress";
+const OTHER_DOCUMENTS_PATH: &str = "/api/v1/other-documents";
+/// Rows fetched per request when counting them.
+const COUNT_PAGE: usize = 500;
+
+/// Legacy data found at launch, managed by Tauri. Cleared once imported or declined.
+#[derive(Default)]
+pub(crate) struct LegacyData(Mutex<Option<LegacyFound>>);
+
+/// Payload of the `legacy-data-found` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct LegacyFound {
+    pub path: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// What `migrate_legacy_data` did.
+#[derive(Debug, Serialize)]
+pub(crate) struct LegacyImport {
+    pub files: usize,
+    pub bytes: u64,
+    /// Rows the backend reports after starting on the imported data; `None` if it could
+    /// not be asked.
+    pub invoices: Option<usize>,
+    pub other_documents: Option<usize>,
+    /// Every file matched its original and the backend started on them.
+    pub verified: bool,
+}
+
+fn legacy_dir(app: &AppHandle) -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        app.path().local_data_dir()
+    } else {
+        app.path().home_dir()
+    };
+    base.ok().map(|dir| dir.join(LEGACY_DIR_NAME))
+}
+
+fn skip(name: &OsStr) -> bool {
+    SKIPPED.iter().any(|skipped| name == *skipped)
+}
+
+/// Look for legacy data and announce it, unless our data dir already holds data or the
+/// user declined before. Call before the first backend launch.
+#[cfg_attr(debug_assertions, allow(dead_code))]
+pub(crate) fn detect(app: &AppHandle) {
+    if settings::current(app).legacy_data_dismissed || datadir::holds_data(app) {
+        return;
+    }
+    let Some(dir) = legacy_dir(app).filter(|dir| dir.is_dir()) else {
+        return;
+    };
+    let tree = match datadir::scan(&dir, skip) {
+        Ok(tree) if !tree.files.is_empty() => tree,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Legacy data found but unreadable: {}", e);
+            return;
+        }
+    };
+    let found = LegacyFound {
+        path: dir,
+        files: tree.files.len(),
+        bytes: tree.total_bytes,
+    };
+    log::info!(
+        "Legacy data found in {} ({} files, {} bytes)",
+        found.path.display(),
+        found.files,
+        found.bytes
+    );
+    *app.state::<LegacyData>().0.lock().unwrap() = Some(found.clone());
+    let _ = app.emit("legacy-data-found", found);
+}
+
+/// The legacy data still on offer.
+pub(crate) fn offered(app: &AppHandle) -> Option<LegacyFound> {
+    app.state::<LegacyData>().0.lock().unwrap().clone()
+}
+
+/// Import the legacy data if `confirm`, else remember not to offer it again. `None` when
+/// declined.
+pub(crate) async fn migrate(
+    app: &AppHandle,
+    confirm: bool,
+) -> Result<Option<LegacyImport>, String> {
+    let Some(found) = offered(app) else {
+        return Err("No legacy data to import".into());
+    };
+    if !confirm {
+        settings::update(app, |s| s.legacy_data_dismissed = true)?;
+        *app.state::<LegacyData>().0.lock().unwrap() = None;
+        log::info!("Legacy data import declined");
+        return Ok(None);
+    }
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    // The import replaces the fresh database, so it must still be empty.
+    let transport = endpoint::transport(app);
+    let timeout = config::request_timeout(app, TimeoutClass::Fast);
+    if count(&transport, invoices::PATH, timeout)
+        .await
+        .is_ok_and(|n| n > 0)
+    {
+        return Err(
+            "Invoices were added since launch; the legacy data can't be imported any more".into(),
+        );
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let target = datadir::path(app)?;
+    let from = found.path.clone();
+    let tree = {
+        let (from, target) = (from.clone(), target.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            let tree = datadir::scan(&from, skip)?;
+            datadir::check_free_space(&target, tree.total_bytes)?;
+            Ok::<_, String>(tree)
+        })
+        .await
+        .map_err(|e| format!("Reading the legacy data failed: {e}"))??
+    };
+
+    log::info!(
+        "Importing legacy data from {} into {}",
+        from.display(),
+        target.display()
+    );
+    supervisor::stop_current(app).await;
+    let handle = app.clone();
+    let copied = tauri::async_runtime::spawn_blocking(move || {
+        // What the copy replaced was the fresh backend's, so a failed copy is just removed.
+        if let Err(e) = datadir::copy_tree(&handle, PROGRESS_EVENT, &from, &target, &tree) {
+            datadir::remove_copies(&target, &tree);
+            return Err(e);
+        }
+        let mismatched = tree
+            .files
+            .iter()
+            .filter(|(file, _)| !same_contents(&from.join(file), &target.join(file)))
+            .count();
+        Ok::<_, String>((tree, mismatched))
+    })
+    .await
+    .map_err(|e| format!("Importing the legacy data failed: {e}"))?;
+
+    let started = supervisor::launch_and_wait(app, &mut stop).await;
+    let (tree, mismatched) = copied?;
+    if mismatched > 0 {
+        log::error!(
+            "{} imported files differ from their legacy originals",
+            mismatched
+        );
+    }
+    *app.state::<LegacyData>().0.lock().unwrap() = None;
+
+    let ready = matches!(started, Ok(Startup::Ready));
+    let (invoices, other_documents) = if ready {
+        let transport = endpoint::transport(app);
+        (
+            count(&transport, invoices::PATH, timeout).await.ok(),
+            count(&transport, OTHER_DOCUMENTS_PATH, timeout).await.ok(),
+        )
+    } else {
+        (None, None)
+    };
+    let import = LegacyImport {
+        files: tree.files.len(),
+        bytes: tree.total_bytes,
+        verified: ready && mismatched == 0 && invoices.is_some(),
+        invoices,
+        other_documents,
+    };
+    log::info!(
+        "Imported legacy data: {} files, {:?} invoices, {:?} other documents, verified: {}",
+        import.files,
+        import.invoices,
+        import.other_documents,
+        import.verified
+    );
+    Ok(Some(import))
+}
+
+/// Whether `a` and `b` have the same length and SHA-256.
+fn same_contents(a: &Path, b: &Path) -> bool {
+    match (digest(a), digest(b)) {
+        (Ok((len_a, hash_a)), Ok((len_b, hash_b))) => len_a == len_b && hash_a == hash_b,
+        _ => false,
+    }
+}
+
+fn digest(path: &Path) -> io::Result<(u64, [u8; 32])> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut len = 0;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        len += n as u64;
+    }
+    Ok((len, hasher.finalize().into()))
+}
+
+/// Rows behind the list endpoint at `path`, paged through `COUNT_PAGE` at a time.
+async fn count(
+    transport: &transport::BackendTransport,
+    path: &str,
+    timeout: Duration,
+) -> Result<usize, String> {
+    let mut total = 0;
+    loop {
+        let page = format!("{path}?skip={total}&limit={COUNT_PAGE}");
+        let resp = transport
+            .get(&page, timeout)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.is_success() {
+            return Err(format!("{path} answered HTTP {}", resp.status));
+        }
+        let rows = resp
+            .json::<Vec<serde_json::Value>>()
+            .map_err(|e| e.to_string())?
+            .len();
+        total += rows;
+        if rows < COUNT_PAGE {
+            return Ok(total);
+        }
+    }
+}
@@ -12,59 +12,295 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::process::Child;
-#[cfg(not(debug_assertions))]
-use std::process::Command;
+mod args;
+mod auth;
+mod backendlog;
+mod backup;
+mod cache;
+mod cancel;
+mod clipboard;
+mod concurrency;
+mod config;
+#[cfg(windows)]
+mod console;
+mod control;
+mod datadir;
+mod datalock;
+mod dbexport;
+mod diagnostics;
+mod diskspace;
+mod download;
+mod endpoint;
+mod env;
+mod events;
+mod folders;
+mod gatekeeper;
+mod health;
+mod heartbeat;
+mod invoices;
+#[cfg(windows)]
+mod job;
+mod jobevents;
+#[cfg(unix)]
+mod launcher;
+mod legacy;
+mod loglevel;
+mod logrecord;
+mod logwindow;
+mod loopback;
+mod memlimit;
+mod monitor;
+mod panics;
+mod pidfile;
+mod port;
+mod preupdate;
+mod priority;
+mod proctree;
+mod proxy;
+mod purge;
+mod redact;
+mod reset;
+mod resources;
+mod restore;
+mod search;
+mod settings;
+mod shelllog;
+mod staging;
+mod startupmetrics;
+mod storage;
+mod supervisor;
+mod support;
+mod suspend;
+mod telemetry;
+mod tempdir;
+mod tls;
+mod transport;
+mod unzip;
+mod upload;
+mod version;
+mod wake;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use tauri::{Emitter, Manager, RunEvent};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::async_runtime::Receiver;
+use tauri::webview::PageLoadEvent;
+use tauri::{Emitter, Manager, RunEvent, WindowEvent};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_shell::process::{CommandChild, CommandEvent, TerminatedPayload};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::watch;
+
+use config::{AppConfig, ReadinessConfig, ReadinessWait};
+use endpoint::Connection;
+use health::{BackendHealth, HealthCache, HealthLatency, LatencyHistory};
+use monitor::BackendStderr;
+use priority::{BackendPriority, PriorityError};
+use proxy::TimeoutClass;
+use settings::AppSettings;
+use supervisor::{BackendReady, SpawnState, Startup, StartupError, Supervisor};
 
-const BACKEND_PORT: u16 = 8000;
+/// Port of the dev-mode backend; release builds let the OS pick one at launch.
+const DEFAULT_BACKEND_PORT: u16 = 8000;
 const BACKEND_HOST: &str = "127.0.0.1";
+/// Label of the window declared in `tauri.conf.json`.
+const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Launch attempts before giving up on transient errors (e.g. antivirus still scanning the exe).
+const SPAWN_ATTEMPTS: u32 = 3;
+const SPAWN_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How long the backend gets to exit on its own after a `/shutdown` request.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How long the backend gets to exit after SIGTERM before it is killed.
+#[cfg(unix)]
+const SIGTERM_GRACE_PERIOD: Duration = Duration::from_secs(3);
+/// How long quitting waits on `/jobs/active`; a hung backend must never keep the app open.
+const ACTIVE_JOBS_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Shortest health poll interval `set_health_monitor_interval` accepts.
+const MIN_HEALTH_MONITOR_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Holds the backend child process handle for lifecycle management.
-struct BackendProcess(Mutex<Option<Child>>);
+struct BackendProcess(Mutex<Option<BackendChild>>);
 
-/// Resolve the path to the backend executable inside bundled resources.
-#[cfg(not(debug_assertions))]
-fn backend_exe_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+/// The spawned backend: its stdin control channel plus its exit status, filled in by
+/// `monitor::forward_events` when the `Terminated` event arrives.
+struct BackendChild {
+    pid: u32,
+    control: control::ControlChannel,
+    exit: watch::Receiver<Option<TerminatedPayload>>,
+    env_names: Vec<String>,
+    memory_limit_mb: Option<u64>,
+    port: u16,
+    /// How `/shutdown` reaches this backend.
+    transport: transport::BackendTransport,
+}
+
+impl BackendChild {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Kill the process by pid; the shell plugin's handle lives on the control writer thread.
+    fn kill(&self) {
+        #[cfg(unix)]
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe {
+            libc::kill(self.pid as libc::pid_t, libc::SIGKILL);
+        }
+        #[cfg(not(unix))]
+        {
+            use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+            let pid = Pid::from_u32(self.pid);
+            let mut system = System::new();
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                true,
+                ProcessRefreshKind::nothing(),
+            );
+            if let Some(process) = system.process(pid) {
+                process.kill();
+            }
+        }
+    }
+
+    fn exit_status(&self) -> Option<TerminatedPayload> {
+        self.exit.borrow().clone()
+    }
+
+    /// Wait up to `timeout` for the process to exit. Returns true if it exited.
+    async fn wait_for_exit(&mut self, timeout: Duration) -> bool {
+        // The sender is dropped when the event stream ends, which only happens once the
+        // process is gone.
+        let exited = self.exit.wait_for(Option::is_some);
+        tokio::time::timeout(timeout, exited).await.is_ok()
+    }
+}
+
+/// Set by `force_exit` once the user chose to quit despite jobs in progress.
+struct ExitConfirmed(AtomicBool);
+
+/// Resolve the bundled `backend/` resource folder.
+fn bundled_backend_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     let resource_dir = app
         .path()
         .resource_dir()
         .map_err(|e| format!("Failed to resolve resource dir: {e}"))?;
+    Ok(resource_dir.join("backend"))
+}
 
-    let backend_dir = resource_dir.join("backend");
+fn stage_backend_enabled(app: &tauri::AppHandle) -> bool {
+    let setting = settings::current(app).stage_backend;
+    staging::enabled(app, setting)
+}
+
+/// Resolve the path to the backend executable: the staged copy when staging is on and one
+/// exists, the bundled resources otherwise.
+fn backend_exe_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let exe_path = expected_backend_exe_path(app)?;
+    if !exe_path.exists() {
+        return Err(format!(
+            "Backend executable not found at {}",
+            exe_path.display()
+        ));
+    }
+    Ok(exe_path)
+}
+
+/// Where `backend_exe_path` looks for the executable, whether or not it is there.
+fn expected_backend_exe_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let staged = stage_backend_enabled(app)
+        .then(|| staging::current_dir(app))
+        .flatten();
+    let backend_dir = match staged {
+        Some(dir) => dir,
+        None => bundled_backend_dir(app)?,
+    };
 
     #[cfg(target_os = "windows")]
     let exe_name = "invoice_processor.exe";
     #[cfg(not(target_os = "windows"))]
     let exe_name = "invoice_processor";
 
-    let exe_path = backend_dir.join(exe_name);
-    if !exe_path.exists() {
-        return Err(format!("Backend executable not found at {}", exe_path.display()));
+    Ok(backend_dir.join(exe_name))
+}
+
+/// Bring the staged copy up to date before a launch, if staging is on.
+///
+/// On failure we keep whatever `backend_exe_path` resolves: an older complete copy or the
+/// bundled resources.
+fn sync_staged_backend(app: &tauri::AppHandle) {
+    if !stage_backend_enabled(app) {
+        return;
+    }
+    let result = bundled_backend_dir(app).and_then(|dir| staging::sync(app, &dir));
+    if let Err(e) = result {
+        log::warn!("Failed to stage backend, launching from resources: {}", e);
     }
-    Ok(exe_path)
+}
+
+/// Launch errors worth retrying: the exe exists but is temporarily locked or unreadable.
+fn is_retryable_spawn_error(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    // ERROR_SHARING_VIOLATION: another process (usually an antivirus) has the exe open.
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+
+    matches!(
+        e.kind(),
+        ErrorKind::PermissionDenied
+            | ErrorKind::ResourceBusy
+            | ErrorKind::ExecutableFileBusy
+            | ErrorKind::Interrupted
+    ) || (cfg!(windows) && e.raw_os_error() == Some(ERROR_SHARING_VIOLATION))
+}
+
+/// Pid of the stored backend, unless it has already exited.
+fn running_backend_pid(app: &tauri::AppHandle) -> Option<u32> {
+    let state = app.state::<BackendProcess>();
+    let slot = state.0.lock().unwrap();
+    slot.as_ref()
+        .filter(|child| child.exit_status().is_none())
+        .map(BackendChild::pid)
+}
+
+/// A freshly spawned backend, before it is stored in `BackendProcess`.
+struct Spawned {
+    events: Receiver<CommandEvent>,
+    child: CommandChild,
+    /// Names of the variables in its environment, for diagnostics.
+    env_names: Vec<String>,
+    /// Memory cap actually applied, in MB.
+    memory_limit_mb: Option<u64>,
 }
 
 /// Spawn the backend process with the correct working directory and env vars.
-#[cfg(not(debug_assertions))]
-fn spawn_backend(app: &tauri::AppHandle) -> Result<Child, String> {
+///
+/// Transient launch errors are retried; a missing executable fails immediately.
+async fn spawn_backend(app: &tauri::AppHandle) -> Result<Spawned, String> {
+    let sync_app = app.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || sync_staged_backend(&sync_app)).await;
     let exe_path = backend_exe_path(app)?;
     let backend_dir = exe_path
         .parent()
         .ok_or("Cannot determine backend directory")?
         .to_path_buf();
 
-    // Use Tauri's app_data_dir for persistent storage
-    let data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    // The app data dir unless the user moved the data with `set_data_dir`
+    let data_dir = datadir::path(app)?;
 
     // Ensure data dir exists
     std::fs::create_dir_all(&data_dir)
         .map_err(|e| format!("Failed to create data dir: {e}"))?;
 
+    // The backend still starts on a full disk so data can be viewed; uploads are refused.
+    if let Ok(space) = diskspace::probe(app).await {
+        diskspace::warn_if_low(app, &space);
+    }
+
     log::info!(
         "Spawning backend: exe={}, cwd={}, data_dir={}",
         exe_path.display(),
@@ -72,98 +308,1970 @@ fn spawn_backend(app: &tauri::AppHandle) -> Result<Child, String> {
         data_dir.display()
     );
 
-    Command::new(&exe_path)
-        .current_dir(&backend_dir)
-        .env("DATA_DIR", data_dir.to_string_lossy().to_string())
-        .env("PORT", BACKEND_PORT.to_string())
-        .env("HOST", BACKEND_HOST)
-        .spawn()
-        .map_err(|e| format!("Failed to spawn backend: {e}"))
+    #[cfg(target_os = "macos")]
+    gatekeeper::preflight(&backend_dir, &exe_path);
+
+    let temp_dir = tempdir::path(app)?.to_string_lossy().to_string();
+    let settings = settings::current(app);
+    let mut managed = vec![
+        ("DATA_DIR", data_dir.to_string_lossy().to_string()),
+        ("PORT", port::current(app).to_string()),
+        ("HOST", BACKEND_HOST.to_string()),
+        ("TMPDIR", temp_dir.clone()),
+        ("TEMP", temp_dir.clone()),
+        ("TMP", temp_dir),
+        (auth::AUTH_TOKEN_ENV, auth::rotate()?),
+        (
+            loglevel::LOG_LEVEL_ENV,
+            loglevel::shell().as_str().to_string(),
+        ),
+    ];
+    // The webview still talks TCP, so the backend listens on both.
+    if let Some(socket) = transport::current_socket(app) {
+        managed.push((
+            transport::LOCAL_ENDPOINT_ENV,
+            socket.to_string_lossy().to_string(),
+        ));
+    }
+    if tls::pinned().is_some() {
+        let files = tls::files(app)?;
+        managed.push((tls::CERT_FILE_ENV, files.cert.to_string_lossy().to_string()));
+        managed.push((tls::KEY_FILE_ENV, files.key.to_string_lossy().to_string()));
+    }
+    let env = env::backend_env(&settings.forward_env, &managed);
+    let env_names: Vec<String> = env.iter().map(|(name, _)| name.clone()).collect();
+    log::info!("Backend environment: {}", env_names.join(", "));
+
+    let memory_limit_mb = settings.memory_limit_mb.filter(|mb| *mb > 0);
+    let memory_limit_mb = memlimit::effective(memory_limit_mb);
+    let mut extra_args = settings.extra_backend_args;
+    if let Err(e) = args::validate(&extra_args) {
+        log::error!("Ignoring extra backend arguments: {}", e);
+        extra_args.clear();
+    }
+    let argv: Vec<String> = std::iter::once(exe_path.display().to_string())
+        .chain(extra_args.iter().cloned())
+        .collect();
+    log::info!("Backend argv: {}", args::redacted(&argv));
+
+    // On Unix the app itself is spawned and execs the backend in its own process group.
+    #[cfg(unix)]
+    let (program, launch_args) = (
+        std::env::current_exe().map_err(|e| format!("Failed to locate the app executable: {e}"))?,
+        launcher::args(memory_limit_mb, &exe_path, &extra_args),
+    );
+    #[cfg(not(unix))]
+    let (program, launch_args) = (exe_path.clone(), extra_args.clone());
+
+    // The PyInstaller build is a directory (exe + `_internal/`), which `externalBin` can't
+    // bundle, so it ships as a resource and is resolved by path rather than as a sidecar.
+    let command = || {
+        app.shell()
+            .command(&program)
+            .current_dir(&backend_dir)
+            .env_clear()
+            .envs(env.iter().cloned())
+            .args(&launch_args)
+    };
+
+    let mut attempt = 1;
+    let (events, child) = loop {
+        match command().spawn() {
+            Ok(spawned) => break spawned,
+            Err(tauri_plugin_shell::Error::Io(e))
+                if attempt < SPAWN_ATTEMPTS && is_retryable_spawn_error(&e) =>
+            {
+                log::warn!(
+                    "Backend launch attempt {}/{} failed: {}; retrying in {:?}",
+                    attempt,
+                    SPAWN_ATTEMPTS,
+                    e,
+                    SPAWN_RETRY_DELAY
+                );
+                tokio::time::sleep(SPAWN_RETRY_DELAY).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(format!(
+                    "Failed to spawn backend {} (attempt {}/{}): {e}",
+                    exe_path.display(),
+                    attempt,
+                    SPAWN_ATTEMPTS
+                ));
+            }
+        }
+    };
+    pidfile::write(app, child.pid(), &exe_path, port::current(app));
+
+    #[cfg(windows)]
+    if let Err(e) = job::assign_to_backend_job(&app.state::<job::BackendJob>(), child.pid()) {
+        log::warn!(
+            "{}; the backend may outlive the app if it is force-closed",
+            e
+        );
+    }
+
+    #[cfg(windows)]
+    let memory_limit_mb = match memlimit::apply(app, memory_limit_mb) {
+        Ok(()) => memory_limit_mb,
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    };
+
+    let priority = settings::current(app).backend_priority;
+    if priority != BackendPriority::Normal {
+        if let Err(e) = priority::apply(child.pid(), priority) {
+            log::warn!("{}", e);
+        }
+    }
+
+    Ok(Spawned {
+        events,
+        child,
+        env_names,
+        memory_limit_mb,
+    })
+}
+
+/// Spawn the backend, store it in `BackendProcess` and start watching it for crashes.
+///
+/// Refuses to spawn while a child is still stored, so there is never more than one backend.
+///
+/// Returns the receiver for the child's exit status.
+#[tracing::instrument(name = "backend_spawn", skip_all, fields(port, pid))]
+async fn launch_backend(
+    app: &tauri::AppHandle,
+) -> Result<watch::Receiver<Option<TerminatedPayload>>, StartupError> {
+    if app.state::<BackendProcess>().0.lock().unwrap().is_some() {
+        return Err("Backend is already running".to_string().into());
+    }
+    // A crashed backend never went through `stop_current`.
+    jobevents::unsubscribe_all(app);
+    datalock::acquire(app)?;
+    let settings = settings::current(app);
+    let port = port::allocate(app, settings.backend_port, settings.backend_port_range).await?;
+    tracing::Span::current().record("port", port);
+    // TLS would cover the socket too.
+    let kind = if tls::prepare(app, settings.loopback_tls)? {
+        transport::TransportKind::Tcp
+    } else {
+        settings.backend_transport
+    };
+    transport::set_socket(app, transport::prepare_socket(app, kind)?);
+    let Spawned {
+        events,
+        child,
+        env_names,
+        memory_limit_mb,
+    } = spawn_backend(app).await?;
+    startupmetrics::mark(app, startupmetrics::Milestone::ProcessLaunched);
+    let pid = child.pid();
+    tracing::Span::current().record("pid", pid);
+    tracing::info!(pid, port, "Backend process launched");
+    let (exit_tx, exit) = watch::channel(None);
+    let control = control::spawn_writer(child);
+    *app.state::<BackendProcess>().0.lock().unwrap() = Some(BackendChild {
+        pid,
+        control,
+        exit: exit.clone(),
+        env_names,
+        memory_limit_mb,
+        port,
+        transport: endpoint::transport(app),
+    });
+    let supervisor = app.state::<Supervisor>();
+    supervisor.set_spawned_at(Some(SystemTime::now()));
+    supervisor.set_suspended(false);
+    monitor::forward_events(app.clone(), pid, events, exit_tx);
+    Ok(exit)
 }
 
-/// Poll the health endpoint until the backend is ready (max ~30 seconds).
-#[cfg(not(debug_assertions))]
-async fn wait_for_backend_ready() -> Result<(), String> {
-    let url = format!("http://{}:{}/health", BACKEND_HOST, BACKEND_PORT);
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(2))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+/// Which endpoint the readiness wait is polling.
+#[derive(Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StartupPhase {
+    /// `/health`: waiting for the server to come up.
+    Liveness,
+    /// `/ready`: waiting for the database and migrations.
+    Readiness,
+}
+
+/// Payload of the `backend-starting` event, emitted after each readiness poll.
+#[derive(Clone, serde::Serialize)]
+struct StartupProgress {
+    phase: StartupPhase,
+    attempt: u32,
+    /// Roughly how many polls fit in the readiness timeout.
+    max_attempts: u32,
+    elapsed_ms: u64,
+    /// HTTP status of this poll; `None` while the backend is not reachable yet.
+    last_status: Option<u16>,
+}
 
-    for attempt in 1..=60 {
-        match client.get(&url).send().await {
-            Ok(resp) if resp.status().is_success() => {
-                log::info!("Backend ready after {} attempts", attempt);
-                return Ok(());
+/// Poll `/health` until the backend is alive, then `/ready` until its database is migrated,
+/// or until `config.max_wait` elapses, emitting `backend-starting` progress along the way.
+/// Returns how long the wait took and what `/health` reported.
+///
+/// Gives up right away if the process exits, instead of waiting out the timeout. `exit` is
+/// `None` for an external backend, which we neither own nor expect to echo our launch token.
+#[tracing::instrument(name = "backend_readiness", skip_all, fields(polls, elapsed_ms))]
+async fn wait_for_backend_ready(
+    app: &tauri::AppHandle,
+    exit: Option<&watch::Receiver<Option<TerminatedPayload>>>,
+    config: &ReadinessConfig,
+) -> Result<(Duration, BackendHealth), StartupError> {
+    let mut wait = ReadinessWait::start(config);
+    let mut transport = endpoint::transport(app);
+    let max_attempts = config.expected_polls();
+    // What `/health` said once the backend came up; `/ready` is polled from then on.
+    let mut live: Option<BackendHealth> = None;
+    let mut timings = StartupTimings::default();
+    loop {
+        let attempt = wait.next_attempt();
+        let exited = exit.and_then(|exit| exit.borrow().clone());
+        if let Some(status) = exited {
+            #[cfg(target_os = "macos")]
+            if wait.elapsed() < gatekeeper::INSTANT_EXIT {
+                if let Some(e) = backend_exe_path(app)
+                    .ok()
+                    .and_then(|exe| gatekeeper::explain_instant_exit(&exe, &status))
+                {
+                    return Err(e);
+                }
             }
-            Ok(resp) => {
-                log::debug!("Backend not ready (status {}), attempt {}/60", resp.status(), attempt);
+            return Err(format!(
+                "Backend exited during startup ({}) after {:.1}s",
+                exit_description(&status),
+                wait.elapsed().as_secs_f32()
+            )
+            .into());
+        }
+        // What this poll got back, for progress events and the timeout error.
+        let (last_status, last_response) = match live.as_ref() {
+            // Nothing listens yet: don't bother reqwest (and the logs) with a doomed request.
+            None if timings.port_open.is_none()
+                && !transport
+                    .accepts_connections(config.tcp_probe_timeout)
+                    .await =>
+            {
+                if let Some(tcp) = tcp_fallback(app, &transport, config.tcp_probe_timeout).await {
+                    transport = tcp;
+                    continue;
+                }
+                if let Some(tcp) = loopback::switch(app, &transport, config.tcp_probe_timeout).await
+                {
+                    transport = tcp;
+                    continue;
+                }
+                log::debug!("Backend port not open yet, attempt {}", attempt);
+                (None, "port not open".to_string())
             }
-            Err(_) => {
-                log::debug!("Backend not reachable, attempt {}/60", attempt);
+            None => {
+                if timings.port_open.is_none() {
+                    let at = wait.elapsed();
+                    log::info!("Backend port open after {:.1}s", at.as_secs_f32());
+                    timings.port_open = Some(at);
+                    startupmetrics::mark(app, startupmetrics::Milestone::PortOpen);
+                }
+                let polled = Instant::now();
+                let poll = health::poll(&transport, config.http_timeout).await;
+                let ok = poll.is_healthy();
+                app.state::<HealthLatency>()
+                    .record(polled.elapsed(), ok, true);
+                match poll {
+                    health::HealthCheck::Healthy(health)
+                        if exit.is_some() && !health::is_our_backend(&health) =>
+                    {
+                        let port = port::current(app);
+                        let message = format!("another service is using port {port}");
+                        log::error!(
+                            "Cannot start backend: {} (launch token not echoed)",
+                            message
+                        );
+                        return Err(StartupError::PortInUse {
+                            port,
+                            occupant: None,
+                            message,
+                        });
+                    }
+                    health::HealthCheck::Healthy(health) => {
+                        let at = wait.elapsed();
+                        log::info!(
+                            "Backend HTTP healthy after {:.1}s, waiting for /ready",
+                            at.as_secs_f32()
+                        );
+                        timings.healthy = Some(at);
+                        startupmetrics::mark(app, startupmetrics::Milestone::FirstHealthy);
+                        live = Some(health);
+                        continue;
+                    }
+                    health::HealthCheck::Unhealthy { status, .. } => {
+                        log::debug!("Backend not ready (status {}), attempt {}", status, attempt);
+                        (Some(status), format!("HTTP {status}"))
+                    }
+                    health::HealthCheck::Unreachable { kind, .. } => {
+                        log::debug!("Backend not reachable, attempt {}", attempt);
+                        if kind == health::UnreachableKind::Timeout {
+                            (None, "request timed out".to_string())
+                        } else {
+                            (None, "connection failed".to_string())
+                        }
+                    }
+                }
             }
+            Some(alive) => match health::poll_ready(&transport, config.http_timeout).await {
+                health::ReadyPoll::Ready => {
+                    let elapsed = wait.elapsed();
+                    timings.record_ready(attempt, elapsed);
+                    startupmetrics::mark(app, startupmetrics::Milestone::Ready);
+                    return Ok((elapsed, alive.clone()));
+                }
+                health::ReadyPoll::Missing => {
+                    let elapsed = wait.elapsed();
+                    log::warn!("Backend has no /ready endpoint, treating /health as ready");
+                    timings.record_ready(attempt, elapsed);
+                    startupmetrics::mark(app, startupmetrics::Milestone::Ready);
+                    return Ok((elapsed, alive.clone()));
+                }
+                health::ReadyPoll::Pending(status, detail) => {
+                    log::debug!(
+                        "Backend alive but not ready ({}), attempt {}",
+                        detail,
+                        attempt
+                    );
+                    (status, detail)
+                }
+            },
+        };
+        let phase = if live.is_some() {
+            StartupPhase::Readiness
+        } else {
+            StartupPhase::Liveness
+        };
+
+        let process = match exit {
+            None => "external backend",
+            Some(exit) if exit.borrow().is_none() => "process still running",
+            Some(_) => "process exited",
+        };
+        let elapsed = wait.check_deadline(process, &last_response)?;
+        events::emit(
+            app,
+            "backend-starting",
+            StartupProgress {
+                phase,
+                attempt,
+                max_attempts,
+                elapsed_ms: elapsed.as_millis() as u64,
+                last_status,
+            },
+        );
+        wait.pause().await;
+    }
+}
+
+/// When each startup phase finished, relative to the start of the readiness wait.
+#[derive(Default)]
+struct StartupTimings {
+    port_open: Option<Duration>,
+    healthy: Option<Duration>,
+}
+
+impl StartupTimings {
+    /// "port open after 3.1s, HTTP healthy after 6.4s, ready after 6.5s".
+    fn summary(&self, ready: Duration) -> String {
+        let mut phases = Vec::new();
+        if let Some(at) = self.port_open {
+            phases.push(format!("port open after {:.1}s", at.as_secs_f32()));
         }
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        if let Some(at) = self.healthy {
+            phases.push(format!("HTTP healthy after {:.1}s", at.as_secs_f32()));
+        }
+        phases.push(format!("ready after {:.1}s", ready.as_secs_f32()));
+        phases.join(", ")
+    }
+
+    /// Log the finished wait, and put `attempt` and `elapsed` on the readiness span.
+    fn record_ready(&self, attempt: u32, elapsed: Duration) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let span = tracing::Span::current();
+        span.record("polls", attempt);
+        span.record("elapsed_ms", elapsed_ms);
+        tracing::info!(
+            polls = attempt,
+            elapsed_ms,
+            port_open_ms = self.port_open.map(|at| at.as_millis() as u64),
+            healthy_ms = self.healthy.map(|at| at.as_millis() as u64),
+            "Backend ready: {}",
+            self.summary(elapsed)
+        );
+    }
+}
+
+/// TCP to the backend, if it listens there but never created the socket it was given:
+/// it doesn't support that transport. Clears the socket so later requests use TCP.
+async fn tcp_fallback(
+    app: &tauri::AppHandle,
+    transport: &transport::BackendTransport,
+    timeout: Duration,
+) -> Option<transport::BackendTransport> {
+    if !transport.endpoint_missing() {
+        return None;
+    }
+    let tcp = transport::BackendTransport::Tcp(port::base_url(app));
+    if !tcp.accepts_connections(timeout).await {
+        return None;
+    }
+    log::warn!(
+        "Backend did not listen on {}, falling back to TCP",
+        transport
+    );
+    transport::set_socket(app, None);
+    Some(tcp)
+}
+
+/// Jobs the backend is still processing, or empty if it can't tell us in time.
+async fn active_backend_jobs(transport: &transport::BackendTransport) -> Vec<serde_json::Value> {
+    let body = match transport.get("/jobs/active", ACTIVE_JOBS_TIMEOUT).await {
+        Ok(resp) if resp.is_success() => resp.json::<serde_json::Value>(),
+        _ => return Vec::new(),
+    };
+    match body {
+        Ok(serde_json::Value::Object(mut map)) => match map.remove("jobs") {
+            Some(serde_json::Value::Array(jobs)) => jobs,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Payload of the `exit-blocked` event.
+#[derive(Clone, serde::Serialize)]
+struct ExitBlocked {
+    jobs: Vec<serde_json::Value>,
+}
+
+/// Ask the backend to stop via its `/shutdown` endpoint. Returns true if the request was accepted.
+async fn request_backend_shutdown(transport: &transport::BackendTransport) -> bool {
+    match transport.post("/shutdown", Duration::from_secs(1)).await {
+        Ok(resp) if resp.is_success() => true,
+        Ok(resp) => {
+            log::warn!("Backend rejected shutdown request (status {})", resp.status);
+            false
+        }
+        Err(e) => {
+            log::warn!("Shutdown request failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Send `signal` to the backend's whole process group, whose id is the backend pid (see
+/// `launcher`). Returns false if no process received it.
+#[cfg(unix)]
+fn signal_process_group(pid: u32, signal: libc::c_int) -> bool {
+    // SAFETY: killpg(2) has no memory-safety preconditions; a dead group only yields ESRCH.
+    unsafe { libc::killpg(pid as libc::pid_t, signal) == 0 }
+}
+
+/// Stop the backend in stages so it can finish pending SQLite writes:
+/// `/shutdown` request, then SIGTERM (Unix only), then a hard kill.
+///
+/// Worst case this takes about `grace` + 1s for the request + 3s after SIGTERM.
+async fn shutdown_backend(child: BackendChild, grace: Duration) {
+    let pid = child.pid();
+    // Workers that left the process group (e.g. with setsid) are only found through the tree.
+    let workers = proctree::Descendants::of(pid);
+    let socket = child.transport.socket_path().map(|path| path.to_path_buf());
+    stop_backend_process(child, grace).await;
+    // Workers forked by the backend can outlive it and keep the port and the DB open.
+    #[cfg(unix)]
+    if signal_process_group(pid, libc::SIGKILL) {
+        log::warn!("Killed leftover backend worker processes");
+    }
+    let killed = workers.kill_remaining();
+    if killed > 0 {
+        log::warn!("Killed {} leftover backend worker process(es)", killed);
+    }
+    // A killed backend leaves its socket file behind.
+    if let Some(socket) = socket {
+        transport::remove_socket(&socket);
+    }
+}
+
+async fn stop_backend_process(mut child: BackendChild, grace: Duration) {
+    if let Some(status) = child.exit_status() {
+        log::info!(
+            "Backend process already exited ({})",
+            exit_description(&status)
+        );
+        return;
+    }
+
+    if request_backend_shutdown(&child.transport).await && child.wait_for_exit(grace).await {
+        log::info!("Backend exited after shutdown request");
+        return;
+    }
+
+    #[cfg(unix)]
+    if signal_process_group(child.pid(), libc::SIGTERM)
+        && child.wait_for_exit(SIGTERM_GRACE_PERIOD).await
+    {
+        log::info!("Backend exited after SIGTERM");
+        return;
+    }
+
+    log::warn!("Backend did not exit gracefully, killing it");
+    #[cfg(unix)]
+    signal_process_group(child.pid(), libc::SIGKILL);
+    child.kill();
+    child.wait_for_exit(Duration::from_secs(1)).await;
+    log::info!("Backend process killed");
+}
+
+/// "exit code N" or "signal N", for logs.
+fn exit_description(status: &TerminatedPayload) -> String {
+    match (status.code, status.signal) {
+        (Some(code), _) => format!("exit code {code}"),
+        (None, Some(signal)) => format!("signal {signal}"),
+        (None, None) => "unknown status".to_string(),
     }
+}
+
+/// Whether nothing is listening on `port` any more.
+fn backend_port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind((BACKEND_HOST, port)).is_ok()
+}
+
+/// Health as the frontend sees it: from `HealthCache` unless `force` is set.
+async fn cached_health(app: &tauri::AppHandle, force: Option<bool>) -> health::HealthCheck {
+    let timeout = config::request_timeout(app, TimeoutClass::Fast);
+    let check = app
+        .state::<HealthCache>()
+        .check(&endpoint::transport(app), timeout, force.unwrap_or(false))
+        .await;
+    app.state::<Supervisor>().record_health(check.is_healthy());
+    check
+}
 
-    Err("Backend did not become ready within 30 seconds".to_string())
+/// Tauri command: check if the backend is healthy; `check_backend_health_detailed` says why not.
+#[tauri::command]
+async fn check_backend_health(app: tauri::AppHandle, force: Option<bool>) -> Result<bool, String> {
+    Ok(cached_health(&app, force).await.is_healthy())
 }
 
-/// Tauri command: check if the backend is healthy.
+/// Tauri command: check backend health and say why it failed, if it did.
 #[tauri::command]
-async fn check_backend_health() -> Result<bool, String> {
-    let url = format!("http://{}:{}/health", BACKEND_HOST, BACKEND_PORT);
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(3))
-        .build()
-        .map_err(|e| format!("HTTP client error: {e}"))?;
+async fn check_backend_health_detailed(
+    app: tauri::AppHandle,
+    force: Option<bool>,
+) -> health::HealthCheck {
+    cached_health(&app, force).await
+}
 
-    match client.get(&url).send().await {
-        Ok(resp) => Ok(resp.status().is_success()),
-        Err(_) => Ok(false),
+/// Versions shown on the About screen.
+#[derive(serde::Serialize)]
+struct VersionInfo {
+    app_version: String,
+    tauri_version: &'static str,
+    /// `None` when the backend is unreachable or doesn't report one.
+    backend_version: Option<String>,
+    /// Backend API versions this app accepts, e.g. "^1.0".
+    expected_backend_api: String,
+}
+
+/// Tauri command: report app, Tauri and backend versions.
+#[tauri::command]
+async fn get_version_info(app: tauri::AppHandle) -> VersionInfo {
+    let backend_version = match app.state::<Supervisor>().backend_version() {
+        Some(v) => Some(v),
+        // Dev backends skip the handshake; ask directly.
+        None => version::backend_version(&endpoint::transport(&app))
+            .await
+            .ok()
+            .flatten(),
+    };
+    VersionInfo {
+        app_version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION,
+        backend_version,
+        expected_backend_api: version::expected(),
     }
 }
 
+/// Tauri command: return the backend's current status, as last emitted in `backend-status`.
+#[tauri::command]
+fn get_backend_status(app: tauri::AppHandle) -> supervisor::StatusReport {
+    supervisor::status_report(&app, app.state::<Supervisor>().status())
+}
+
+/// Tauri command: resolve once the backend is ready (immediately if it already is) or
+/// declared broken, or after `timeout_ms`, with the status at that point.
+///
+/// Lets the UI await readiness at mount time instead of racing the `backend-ready` event.
+/// Without `timeout_ms`, waits as long as the readiness timeout.
+#[tauri::command]
+async fn wait_for_backend(
+    app: tauri::AppHandle,
+    timeout_ms: Option<u64>,
+) -> supervisor::StatusReport {
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(app.state::<AppConfig>().readiness.max_wait);
+    let status = app.state::<Supervisor>().wait_until_settled(timeout).await;
+    supervisor::status_report(&app, status)
+}
+
+/// Tauri command: return the latest backend lifecycle event, for listeners registered
+/// after it fired.
+#[tauri::command]
+fn get_last_backend_event(app: tauri::AppHandle) -> Option<events::LastBackendEvent> {
+    app.state::<events::BackendEvents>().last()
+}
+
+/// Tauri command: return recent health check round-trip times with steady-state stats.
+#[tauri::command]
+fn get_health_latency_history(app: tauri::AppHandle) -> LatencyHistory {
+    app.state::<HealthLatency>().history()
+}
+
+/// Tauri command: return what the backend's `/health` endpoint reports.
+///
+/// Fails if the backend is unreachable or answers with an error status.
+#[tauri::command]
+async fn get_backend_health(
+    app: tauri::AppHandle,
+    force: Option<bool>,
+) -> Result<BackendHealth, String> {
+    cached_health(&app, force).await.into_result()
+}
+
+/// Milliseconds since the Unix epoch, for timestamps sent to the frontend.
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// What the app knows about the backend process, for support and the about screen.
+#[derive(serde::Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum BackendProcessInfo {
+    /// A backend spawned and supervised by the app.
+    Managed {
+        pid: Option<u32>,
+        spawned_at_ms: Option<u64>,
+        uptime_secs: Option<u64>,
+        restart_count: u32,
+        last_restart_at_ms: Option<u64>,
+        port: u16,
+        last_health_ok: Option<bool>,
+        /// Spawn-to-ready time of the most recent launch.
+        last_startup_ms: Option<u64>,
+        priority: BackendPriority,
+        suspended: bool,
+        /// How the previous backend process ended, if one did.
+        last_exit: Option<TerminatedPayload>,
+        /// Names (not values) of the variables the backend was started with.
+        env_names: Vec<String>,
+        /// Quarantine and permission state of the backend executable (macOS only).
+        backend_exe: Option<gatekeeper::ExeState>,
+        /// Proxy variables set for the app, which requests to the backend bypass.
+        system_proxy: Vec<String>,
+        /// The loopback address in use and recent attempts to reach the others.
+        loopback: loopback::Report,
+    },
+    /// A backend started outside the app (dev mode, or a configured external URL).
+    External {
+        url: String,
+        port: u16,
+        last_health_ok: Option<bool>,
+        system_proxy: Vec<String>,
+        loopback: loopback::Report,
+    },
+}
+
+/// Tauri command: report pid, uptime and restart count of the backend.
+#[tauri::command]
+async fn get_backend_process_info(app: tauri::AppHandle) -> Result<BackendProcessInfo, String> {
+    if endpoint::connection(&app) == Connection::External {
+        let url = endpoint::base_url(&app);
+        let port = endpoint::socket_addr(&url).map_or(DEFAULT_BACKEND_PORT, |(_, port)| port);
+        let healthy = cached_health(&app, None).await.is_healthy();
+        return Ok(BackendProcessInfo::External {
+            url,
+            port,
+            last_health_ok: Some(healthy),
+            system_proxy: env::proxy_vars(),
+            loopback: loopback::report(&app),
+        });
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let pid = running_backend_pid(&app);
+    let env_names = app
+        .state::<BackendProcess>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|child| child.env_names.clone())
+        .unwrap_or_default();
+    let spawned_at = supervisor.spawned_at().filter(|_| pid.is_some());
+    let stats = supervisor.stats();
+    #[cfg(target_os = "macos")]
+    let backend_exe = backend_exe_path(&app)
+        .ok()
+        .map(|exe| gatekeeper::inspect(&exe));
+    #[cfg(not(target_os = "macos"))]
+    let backend_exe = None;
+    Ok(BackendProcessInfo::Managed {
+        pid,
+        spawned_at_ms: spawned_at.map(unix_millis),
+        uptime_secs: spawned_at
+            .and_then(|at| at.elapsed().ok())
+            .map(|d| d.as_secs()),
+        restart_count: stats.count,
+        last_restart_at_ms: stats.last_restart_at.map(unix_millis),
+        port: port::current(&app),
+        last_health_ok: supervisor.last_health_ok(),
+        last_startup_ms: supervisor.last_startup().map(|d| d.as_millis() as u64),
+        priority: settings::current(&app).backend_priority,
+        suspended: supervisor.is_suspended(),
+        last_exit: supervisor.last_exit(),
+        env_names,
+        backend_exe,
+        system_proxy: env::proxy_vars(),
+        loopback: loopback::report(&app),
+    })
+}
+
+/// Tauri command: change the backend's CPU priority, now and for future launches.
+///
+/// The setting is only saved once the running backend (if any) accepted it.
+#[tauri::command]
+fn set_backend_priority(
+    app: tauri::AppHandle,
+    priority: BackendPriority,
+) -> Result<(), PriorityError> {
+    let pid = running_backend_pid(&app);
+    if let Some(pid) = pid {
+        priority::apply(pid, priority)?;
+    }
+    settings::update(&app, |s| s.backend_priority = priority)?;
+    log::info!("Backend priority set to {:?}", priority);
+    Ok(())
+}
+
+/// Tauri command: kill the backend, spawn a fresh one and wait until it is ready.
+///
+/// Returns `false` if the new backend did not become ready in time. A second call
+/// while a restart is running fails instead of spawning another child.
+#[tauri::command]
+async fn restart_backend(app: tauri::AppHandle) -> Result<bool, String> {
+    if cfg!(debug_assertions) {
+        return Err(
+            "Dev mode: restart the backend manually (cd backend && python run_server.py)".into(),
+        );
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("Backend restart already in progress".to_string());
+    };
+
+    supervisor::stop_current(&app).await;
+    supervisor.record_restart();
+    match supervisor::launch_and_wait(&app, &mut stop).await? {
+        Startup::Ready => Ok(true),
+        Startup::NotReady | Startup::Cancelled | Startup::Incompatible => Ok(false),
+        Startup::AlreadyStarted => Ok(supervisor.state() == SpawnState::Running),
+    }
+}
+
+/// Tauri command: start the backend if it isn't running and wait until it is ready.
+///
+/// A no-op when the backend is already running or starting; returns the current state.
+#[tauri::command]
+async fn start_backend(app: tauri::AppHandle) -> Result<SpawnState, String> {
+    if cfg!(debug_assertions) {
+        return Err(
+            "Dev mode: start the backend manually (cd backend && python run_server.py)".into(),
+        );
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Ok(supervisor.state());
+    };
+
+    supervisor::launch_and_wait(&app, &mut stop).await?;
+    Ok(supervisor.state())
+}
+
+/// Tauri command: stop the backend without quitting the app.
+///
+/// Cancels a readiness wait or crash restart in progress, then emits `backend-stopped`.
+#[tauri::command]
+async fn stop_backend(app: tauri::AppHandle) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend is not managed by the app".into());
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    supervisor.request_stop();
+    let _guard = supervisor.lock_lifecycle().await;
+    supervisor::stop_current(&app).await;
+    pidfile::remove(&app);
+    log::info!("Backend stopped by user");
+    events::emit(&app, "backend-stopped", ());
+    Ok(())
+}
+
+/// Tauri command: clear the crash-loop verdict and try launching the backend again.
+#[tauri::command]
+fn reset_backend_failure_state(app: tauri::AppHandle) {
+    let supervisor = app.state::<Supervisor>();
+    if !supervisor.is_fatal() {
+        return;
+    }
+    supervisor.reset_failures();
+    log::info!("Backend failure state reset by user");
+
+    let running = app.state::<BackendProcess>().0.lock().unwrap().is_some();
+    if !cfg!(debug_assertions) && !running {
+        supervisor::restart_after_crash(app.clone());
+    }
+}
+
+/// Tauri command: freeze the backend to save battery while the app is idle.
+///
+/// Emits `backend-suspended`. The watchdog leaves a suspended backend alone.
+#[tauri::command]
+fn suspend_backend(app: tauri::AppHandle) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend is not managed by the app".into());
+    }
+    if endpoint::external_url(&app).is_some() {
+        return Err("External backend: the backend is not managed by the app".into());
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("Backend start, stop or restart in progress".to_string());
+    };
+    if supervisor.is_suspended() {
+        return Ok(());
+    }
+    if supervisor.state() != SpawnState::Running {
+        return Err("Backend is not running".to_string());
+    }
+    let pid = running_backend_pid(&app);
+    let pid = pid.ok_or("Backend is not running")?;
+
+    suspend::suspend(pid)?;
+    supervisor.set_suspended(true);
+    log::info!("Backend process {} suspended", pid);
+    events::emit(&app, "backend-suspended", ());
+    Ok(())
+}
+
+/// Tauri command: let a suspended backend run again.
+///
+/// Checks health right away and emits `backend-ready` if it answers. Returns whether it did.
+#[tauri::command]
+async fn resume_backend(app: tauri::AppHandle) -> Result<bool, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend is not managed by the app".into());
+    }
+    if endpoint::external_url(&app).is_some() {
+        return Err("External backend: the backend is not managed by the app".into());
+    }
+
+    let supervisor = app.state::<Supervisor>();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("Backend start, stop or restart in progress".to_string());
+    };
+    if !supervisor.is_suspended() {
+        return Ok(supervisor.state() == SpawnState::Running);
+    }
+    let pid = running_backend_pid(&app);
+    let pid = pid.ok_or("Backend is not running")?;
+
+    suspend::resume(pid)?;
+    supervisor.set_suspended(false);
+    log::info!("Backend process {} resumed", pid);
+
+    let timeout = config::request_timeout(&app, TimeoutClass::Fast);
+    let health = health::fetch(&endpoint::transport(&app), timeout)
+        .await
+        .ok();
+    supervisor.record_health(health.is_some());
+    let healthy = health.is_some();
+    if healthy {
+        let ready = BackendReady::new(&app, None, health);
+        events::emit(&app, "backend-ready", ready);
+    }
+    Ok(healthy)
+}
+
+/// Tauri command: write a control message such as `{"cmd":"reload-config"}` to the
+/// backend's stdin, bypassing HTTP.
+#[tauri::command]
+async fn send_backend_control(
+    app: tauri::AppHandle,
+    message: serde_json::Value,
+) -> Result<(), String> {
+    let control = {
+        let state = app.state::<BackendProcess>();
+        let slot = state.0.lock().unwrap();
+        match slot.as_ref() {
+            Some(child) if child.exit_status().is_none() => child.control.clone(),
+            Some(_) => return Err("Backend process has exited".to_string()),
+            None => return Err("Backend is not running".to_string()),
+        }
+    };
+    control.send(&message).await
+}
+
+/// Tauri command: report how much disk space the app's data takes up, by category, and
+/// how much is left. Sizes are from the last minute's scan unless `refresh`.
+#[tauri::command]
+async fn get_storage_usage(
+    app: tauri::AppHandle,
+    refresh: bool,
+) -> Result<storage::StorageUsage, String> {
+    storage::usage(&app, refresh).await
+}
+
+/// Tauri command: delete the caches of `kinds`, leaving files that may be in use, and
+/// report what each freed. Emits `cache-purged`.
+#[tauri::command]
+async fn purge_cache(
+    app: tauri::AppHandle,
+    kinds: Vec<purge::CacheKind>,
+) -> Result<purge::CachePurge, String> {
+    purge::purge(&app, &kinds).await
+}
+
+/// Tauri command: choose whether the page renders and temp files are deleted when the app
+/// exits.
+#[tauri::command]
+fn set_clear_caches_on_exit(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.clear_caches_on_exit = enabled)
+}
+
+/// Tauri command: stop polling backend health. Unresponsive-backend restarts pause too.
+#[tauri::command]
+fn pause_health_monitor(app: tauri::AppHandle) {
+    app.state::<Supervisor>().set_monitor_paused(true);
+    log::info!("Backend health monitor paused");
+}
+
+/// Tauri command: poll backend health again after `pause_health_monitor`.
+#[tauri::command]
+fn resume_health_monitor(app: tauri::AppHandle) {
+    app.state::<Supervisor>().set_monitor_paused(false);
+    log::info!("Backend health monitor resumed");
+}
+
+/// Tauri command: change how often backend health is polled, effective immediately.
+#[tauri::command]
+fn set_health_monitor_interval(app: tauri::AppHandle, interval_secs: u64) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs);
+    if interval < MIN_HEALTH_MONITOR_INTERVAL {
+        return Err(format!(
+            "Health monitor interval must be at least {}s",
+            MIN_HEALTH_MONITOR_INTERVAL.as_secs()
+        ));
+    }
+    app.state::<Supervisor>().set_monitor_interval(interval);
+    Ok(())
+}
+
+/// Tauri command: change how often `backend-heartbeat` is emitted, effective immediately.
+///
+/// Lets the UI slow the heartbeat down while its status widget is hidden.
+#[tauri::command]
+fn set_heartbeat_interval(app: tauri::AppHandle, interval_secs: u64) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs);
+    if interval < heartbeat::MIN_INTERVAL {
+        return Err(format!(
+            "Heartbeat interval must be at least {}s",
+            heartbeat::MIN_INTERVAL.as_secs()
+        ));
+    }
+    app.state::<heartbeat::HeartbeatInterval>()
+        .0
+        .send_replace(interval);
+    Ok(())
+}
+
+/// Tauri command: save extra command-line arguments for the backend.
+///
+/// They take effect on the next launch; pass `restart` to relaunch the backend right away.
+#[tauri::command]
+async fn set_extra_backend_args(
+    app: tauri::AppHandle,
+    args: Vec<String>,
+    restart: bool,
+) -> Result<(), String> {
+    args::validate(&args)?;
+    settings::update(&app, |s| s.extra_backend_args = args)?;
+    if restart {
+        restart_backend(app).await?;
+    }
+    Ok(())
+}
+
+/// Tauri command: show or hide a console window echoing the backend's output (Windows only).
+///
+/// Saved in settings, so the console also opens on the next launch until turned off.
+#[tauri::command]
+fn set_show_backend_console(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if !cfg!(windows) {
+        return Err("The backend console is only available on Windows".into());
+    }
+    #[cfg(windows)]
+    if enabled {
+        console::show()?;
+    } else {
+        console::hide();
+    }
+    settings::update(&app, |s| s.show_backend_console = enabled)
+}
+
+/// Tauri command: attach to the backend at `url` instead of spawning the bundled one, or go
+/// back to the bundled one with `None`.
+///
+/// Takes effect on the next launch; the env vars `INVOICE_BACKEND_URL` and
+/// `INVOICE_BACKEND_HOST`/`INVOICE_BACKEND_PORT` win over this setting.
+#[tauri::command]
+fn set_external_backend_url(app: tauri::AppHandle, url: Option<String>) -> Result<(), String> {
+    let url = url.as_deref().map(endpoint::normalize).transpose()?;
+    settings::update(&app, |s| s.backend_url = url)
+}
+
+/// Tauri command: serve the bundled backend over HTTPS with a pinned self-signed
+/// certificate, or go back to plain HTTP.
+///
+/// Takes effect when the backend is next started or restarted.
+#[tauri::command]
+fn set_loopback_tls(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.loopback_tls = enabled)
+}
+
+/// Tauri command: choose whether closing the main window also stops the backend.
+#[tauri::command]
+fn set_quit_on_close(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.quit_on_close = enabled)
+}
+
+/// Tauri command: quit even though the backend reported jobs in progress.
+#[tauri::command]
+fn force_exit(app: tauri::AppHandle) {
+    app.state::<ExitConfirmed>().0.store(true, Ordering::SeqCst);
+    app.exit(0);
+}
+
 /// Tauri command: return the backend base URL for the frontend.
+///
+/// That is the configured external backend, if any; otherwise the port is chosen at
+/// launch, so this can change after a restart.
+#[tauri::command]
+fn get_backend_url(app: tauri::AppHandle) -> String {
+    endpoint::base_url(&app)
+}
+
+/// Tauri command: call the backend on behalf of the webview, which does no HTTP itself.
+#[tauri::command]
+async fn backend_request(
+    app: tauri::AppHandle,
+    request: proxy::ProxyRequest,
+) -> Result<proxy::ProxyResponse, proxy::ProxyError> {
+    proxy::send(&app, request).await
+}
+
+/// Tauri command: stream the file at `path` to the backend for analysis, emitting
+/// `upload-progress`, and return the backend's answer.
+#[tauri::command]
+async fn upload_invoice(
+    app: tauri::AppHandle,
+    path: std::path::PathBuf,
+    upload_id: Option<String>,
+) -> Result<serde_json::Value, upload::UploadError> {
+    upload::upload(&app, path, upload_id).await
+}
+
+/// Tauri command: one page of the invoice list, cached; see `invoices`.
+#[tauri::command]
+async fn list_invoices(
+    app: tauri::AppHandle,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    filter: Option<String>,
+) -> Result<invoices::InvoicePage, String> {
+    let page_size = page_size.unwrap_or(invoices::DEFAULT_PAGE_SIZE);
+    let query = invoices::ListQuery::new(page.unwrap_or(0), page_size, filter)?;
+    invoices::list(&app, query).await
+}
+
+/// Tauri command: search invoices by vendor, amount and date; see `search::InvoiceQuery`.
+#[tauri::command]
+async fn search_invoices(
+    app: tauri::AppHandle,
+    query: search::InvoiceQuery,
+) -> Result<search::SearchPage, search::SearchError> {
+    search::search(&app, query).await
+}
+
+/// Tauri command: cancel the upload `upload_id`.
+#[tauri::command]
+fn cancel_upload(app: tauri::AppHandle, upload_id: String) -> Result<(), String> {
+    cancel_backend_request(app, upload_id)
+}
+
+/// Tauri command: cancel the `backend_request`, upload or download started with
+/// `request_id`. The call then fails with its "cancelled" error.
+#[tauri::command]
+fn cancel_backend_request(app: tauri::AppHandle, request_id: String) -> Result<(), String> {
+    if app.state::<cancel::Cancellations>().cancel(&request_id) {
+        Ok(())
+    } else {
+        Err(format!("No request {request_id} in progress"))
+    }
+}
+
+/// Tauri command: save the backend's response for `path` to `destination`, emitting
+/// `download-progress`; the frontend gets `destination` from a save dialog.
+#[tauri::command]
+async fn download_to_file(
+    app: tauri::AppHandle,
+    path: String,
+    query: Option<std::collections::BTreeMap<String, String>>,
+    destination: std::path::PathBuf,
+    request_id: Option<String>,
+) -> Result<download::Downloaded, proxy::ProxyError> {
+    let query = query.unwrap_or_default();
+    download::download(&app, &path, &query, &destination, request_id.as_deref()).await
+}
+
+/// Tauri command: forward the backend's progress events for `job_id` as
+/// `job-progress:{job_id}` until the job finishes or `unsubscribe_job_events` is called.
+#[tauri::command]
+fn subscribe_job_events(app: tauri::AppHandle, job_id: String) -> Result<(), String> {
+    jobevents::subscribe(&app, &job_id)
+}
+
+/// Tauri command: stop forwarding the events of `job_id`.
+#[tauri::command]
+fn unsubscribe_job_events(app: tauri::AppHandle, job_id: String) {
+    jobevents::unsubscribe(&app, &job_id);
+}
+
+/// Tauri command: drop cached responses for `prefix` and below it ("/" for all), and
+/// the cached invoice list if it is covered. Returns how many responses were dropped.
+#[tauri::command]
+fn invalidate_cache(app: tauri::AppHandle, prefix: String) -> usize {
+    if cache::under(invoices::PATH, &prefix) {
+        invoices::invalidate(&app);
+    }
+    cache::invalidate(&app, &prefix)
+}
+
+/// Tauri command: size and hit ratio of the response cache.
+#[tauri::command]
+fn get_cache_stats(app: tauri::AppHandle) -> cache::CacheStats {
+    cache::stats(&app)
+}
+
+/// Tauri command: send backend output to the webview as `backend-log` events, for a
+/// console panel, at least as severe as `min_level` if given. Off at launch; the log file
+/// gets every line either way.
+#[tauri::command]
+fn set_backend_log_forwarding(
+    app: tauri::AppHandle,
+    enabled: bool,
+    min_level: Option<loglevel::LogLevel>,
+) {
+    backendlog::set_forwarding(&app, enabled, min_level);
+}
+
+/// Tauri command: open the backend console window, or focus it if it is open. While it
+/// is, `backend-log` events go to it only. Async, as building a window from a sync command
+/// can deadlock on Windows.
+#[tauri::command]
+async fn open_backend_console(app: tauri::AppHandle) -> Result<(), String> {
+    logwindow::open(&app)
+}
+
+/// Tauri command: the last backend output kept in memory, oldest first, for a console to
+/// show before live `backend-log` events arrive. Covers earlier backend launches too.
+#[tauri::command]
+fn get_log_backlog(app: tauri::AppHandle) -> Vec<logrecord::BackendLogRecord> {
+    backendlog::backlog(&app)
+}
+
+/// Tauri command: change how much backend log is kept. Applies from the next rotation and
+/// is saved for later launches.
+#[tauri::command]
+fn set_log_retention(
+    app: tauri::AppHandle,
+    retention: backendlog::Retention,
+) -> Result<(), String> {
+    retention.validate()?;
+    settings::update(&app, |s| {
+        s.log_max_size_mb = retention.max_size_mb;
+        s.log_max_files = retention.max_files;
+        s.log_max_age_days = retention.max_age_days;
+    })?;
+    backendlog::set_retention(&app, retention);
+    Ok(())
+}
+
+/// Tauri command: change when `backend-resource-warning` fires and whether an idle backend
+/// over its memory threshold is restarted. Applies from the next sample and is saved for
+/// later launches.
+#[tauri::command]
+fn set_resource_thresholds(
+    app: tauri::AppHandle,
+    thresholds: resources::Thresholds,
+) -> Result<(), String> {
+    thresholds.validate()?;
+    settings::update(&app, |s| {
+        s.resource_rss_warn_mb = thresholds.rss_mb;
+        s.resource_cpu_warn_percent = thresholds.cpu_percent;
+        s.resource_cpu_warn_secs = thresholds.cpu_sustain_secs;
+        s.resource_warn_cooldown_secs = thresholds.cooldown_secs;
+        s.restart_on_memory_warning = thresholds.restart_on_memory;
+    })
+}
+
+/// Tauri command: the thresholds in effect for `backend-resource-warning`.
+#[tauri::command]
+fn get_resource_thresholds(app: tauri::AppHandle) -> resources::Thresholds {
+    settings::current(&app).resource_thresholds()
+}
+
+/// Tauri command: free space on the volume holding the backend's data, with the upload floor
+/// and warning threshold.
+#[tauri::command]
+async fn get_disk_space(app: tauri::AppHandle) -> Result<diskspace::DiskSpace, String> {
+    diskspace::probe(&app).await
+}
+
+/// Tauri command: change the free space uploads need and under which `low-disk-space` fires.
+/// Saved for later launches.
+#[tauri::command]
+fn set_disk_space_thresholds(
+    app: tauri::AppHandle,
+    thresholds: diskspace::Thresholds,
+) -> Result<(), String> {
+    thresholds.validate()?;
+    settings::update(&app, |s| {
+        s.min_free_space_mb = thresholds.floor_mb;
+        s.low_disk_space_warn_mb = thresholds.warn_mb;
+    })
+}
+
+/// Tauri command: the thresholds in effect for uploads and `low-disk-space`.
+#[tauri::command]
+fn get_disk_space_thresholds(app: tauri::AppHandle) -> diskspace::Thresholds {
+    settings::current(&app).disk_space_thresholds()
+}
+
+/// Tauri command: where the backend keeps invoice data, and whether that is the default.
+#[tauri::command]
+fn get_data_dir(app: tauri::AppHandle) -> Result<datadir::DataDir, String> {
+    datadir::current(&app)
+}
+
+/// Tauri command: move the backend's data to `new_path`, an empty folder with room for it,
+/// or back into the app data dir. Stops the backend, copies with
+/// `data-dir-migration-progress` events and starts it again on the new location; on
+/// failure the data stays where it was.
+#[tauri::command]
+async fn set_data_dir(
+    app: tauri::AppHandle,
+    new_path: std::path::PathBuf,
+) -> Result<datadir::DataDir, String> {
+    datadir::migrate(&app, new_path).await
+}
+
+/// Tauri command: data of the old standalone backend that can still be imported.
+#[tauri::command]
+fn get_legacy_data(app: tauri::AppHandle) -> Option<legacy::LegacyFound> {
+    legacy::offered(&app)
+}
+
+/// Tauri command: answer `legacy-data-found`. With `confirm`, copy the old standalone
+/// backend's data in, restart the backend on it and report what it holds; without, don't
+/// offer it again. The legacy folder is left as it is either way.
+#[tauri::command]
+async fn migrate_legacy_data(
+    app: tauri::AppHandle,
+    confirm: bool,
+) -> Result<Option<legacy::LegacyImport>, String> {
+    legacy::migrate(&app, confirm).await
+}
+
+/// Tauri command: phase timings of the last backend launches, oldest first.
+#[tauri::command]
+fn get_startup_metrics(app: tauri::AppHandle) -> startupmetrics::StartupHistory {
+    startupmetrics::history(&app)
+}
+
+/// Tauri command: show the app's log folder in the file manager. Returns its path.
+#[tauri::command]
+fn open_logs_folder(app: tauri::AppHandle) -> Result<std::path::PathBuf, folders::OpenFolderError> {
+    folders::open(&app, folders::Folder::Logs)
+}
+
+/// Tauri command: show the app's data folder (the database; settings too unless the data
+/// was moved) in the file manager.
+#[tauri::command]
+fn open_data_folder(app: tauri::AppHandle) -> Result<std::path::PathBuf, folders::OpenFolderError> {
+    folders::open(&app, folders::Folder::Data)
+}
+
+/// Tauri command: log levels of the shell and of the backend.
+#[tauri::command]
+async fn get_log_level(app: tauri::AppHandle) -> loglevel::LogLevels {
+    loglevel::get(&app).await
+}
+
+/// Tauri command: change the log level of the shell and the backend. The level is saved,
+/// handed to the next backend spawn through `LOG_LEVEL`, and pushed to the running backend
+/// through `/api/v1/admin/loglevel` if it supports that. Levels more verbose than info
+/// lapse after a day.
 #[tauri::command]
-fn get_backend_url() -> String {
-    format!("http://{}:{}", BACKEND_HOST, BACKEND_PORT)
+async fn set_log_level(
+    app: tauri::AppHandle,
+    level: loglevel::LogLevel,
+) -> Result<loglevel::LogLevels, String> {
+    loglevel::set(&app, level).await
+}
+
+/// Tauri command: versions, paths, backend executable and status, and free disk space,
+/// for the Diagnostics panel. A probe that fails is reported as unavailable on its own.
+#[tauri::command]
+async fn collect_diagnostics(app: tauri::AppHandle) -> Result<diagnostics::Diagnostics, String> {
+    let exe = expected_backend_exe_path(&app);
+    tauri::async_runtime::spawn_blocking(move || diagnostics::collect(&app, exe))
+        .await
+        .map_err(|e| format!("Collecting diagnostics failed: {e}"))
+}
+
+/// Tauri command: memory, CPU, open files and worker count of the backend, for the status
+/// bar. Cheap to poll every few seconds.
+#[tauri::command]
+async fn get_backend_resource_usage(
+    app: tauri::AppHandle,
+) -> Result<resources::BackendResources, String> {
+    let pid = running_backend_pid(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        resources::sample(&app.state::<resources::ResourceMonitor>(), pid)
+    })
+    .await
+    .map_err(|e| format!("Sampling backend resources failed: {e}"))
+}
+
+/// Tauri command: copy the diagnostics report to the clipboard as Markdown for a support
+/// chat or GitHub issue, with the home directory and credentials masked. Returns the text
+/// that was copied.
+#[tauri::command]
+async fn copy_diagnostics_to_clipboard(app: tauri::AppHandle) -> Result<String, String> {
+    let exe = expected_backend_exe_path(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        let report = diagnostics::collect(&app, exe);
+        let text = diagnostics::markdown(&report, app.path().home_dir().ok().as_deref());
+        clipboard::write_text(&text)?;
+        Ok(text)
+    })
+    .await
+    .map_err(|e| format!("Collecting diagnostics failed: {e}"))?
+}
+
+/// Tauri command: opt in to or out of anonymous crash and startup telemetry. The UI
+/// only calls this on an explicit user choice.
+#[tauri::command]
+fn set_telemetry_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    telemetry::set_enabled(&app, enabled)
+}
+
+/// Tauri command: whether telemetry is on, where it goes and the exact batch it would send
+/// next.
+#[tauri::command]
+fn get_telemetry_preview(app: tauri::AppHandle) -> telemetry::TelemetryPreview {
+    telemetry::preview(&app)
+}
+
+/// Tauri command: zip the backend log tail, a diagnostics report and the redacted settings
+/// for a support request, to `destination` or a timestamped file in it if it is a folder.
+/// The frontend gets `destination` from a save dialog.
+#[tauri::command]
+async fn create_support_bundle(
+    app: tauri::AppHandle,
+    destination: std::path::PathBuf,
+) -> Result<support::SupportBundle, String> {
+    let process = match get_backend_process_info(app.clone()).await {
+        Ok(info) => serde_json::to_value(info).unwrap_or_default(),
+        Err(e) => serde_json::json!({ "error": e }),
+    };
+    let diagnostics = serde_json::json!({
+        "created_at_ms": unix_millis(SystemTime::now()),
+        "environment": collect_diagnostics(app.clone()).await?,
+        "versions": get_version_info(app.clone()).await,
+        "process": process,
+        "health": cached_health(&app, None).await,
+        "health_latency": app.state::<HealthLatency>().history(),
+        "last_event": app.state::<events::BackendEvents>().last(),
+        "log_levels": loglevel::get(&app).await,
+        "response_cache": cache::stats(&app),
+        "request_queue": concurrency::stats(&app),
+    });
+    tauri::async_runtime::spawn_blocking(move || support::create(&app, &destination, &diagnostics))
+        .await
+        .map_err(|e| format!("Creating the support bundle failed: {e}"))?
+}
+
+/// Tauri command: zip every file in the app log dir, the shell's and panic logs and
+/// rotated backend logs included, to `destination` or a dated file in it if it is a
+/// folder. Without `destination` a save dialog asks; `None` means it was cancelled.
+#[tauri::command]
+async fn export_logs(
+    app: tauri::AppHandle,
+    destination: Option<std::path::PathBuf>,
+) -> Result<Option<support::LogExport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let destination = match destination {
+            Some(path) => path,
+            None => {
+                let picked = app
+                    .dialog()
+                    .file()
+                    .set_title("Export logs")
+                    .set_file_name(support::default_log_export_name(SystemTime::now()))
+                    .add_filter("Zip archive", &["zip"])
+                    .blocking_save_file();
+                match picked {
+                    Some(path) => path
+                        .into_path()
+                        .map_err(|e| format!("Unusable export location: {e}"))?,
+                    None => return Ok(None),
+                }
+            }
+        };
+        support::export_logs(&app, &destination).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Exporting the logs failed: {e}"))?
+}
+
+/// Tauri command: zip the backend's data dir, with a consistent copy of the database, to
+/// `destination` or a dated file in it if it is a folder. Without `destination` a save
+/// dialog asks; `None` means it was cancelled. Emits `backup-progress` while writing.
+#[tauri::command]
+async fn backup_data(
+    app: tauri::AppHandle,
+    destination: Option<std::path::PathBuf>,
+) -> Result<Option<backup::DataBackup>, String> {
+    let destination = match destination {
+        Some(path) => path,
+        None => {
+            let dialog = app.clone();
+            let picked = tauri::async_runtime::spawn_blocking(move || {
+                dialog
+                    .dialog()
+                    .file()
+                    .set_title("Back up data")
+                    .set_file_name(support::default_backup_name(SystemTime::now()))
+                    .add_filter("Zip archive", &["zip"])
+                    .blocking_save_file()
+            })
+            .await
+            .map_err(|e| format!("Choosing the backup location failed: {e}"))?;
+            match picked {
+                Some(path) => path
+                    .into_path()
+                    .map_err(|e| format!("Unusable backup location: {e}"))?,
+                None => return Ok(None),
+            }
+        }
+    };
+    backup::backup(&app, &destination).await.map(Some)
+}
+
+/// Tauri command: copy the backend's database, as a consistent snapshot, to `destination`
+/// or a dated file in it if it is a folder, keeping the copy only if it passes SQLite's
+/// integrity check. Without `destination` a save dialog asks; `None` means it was cancelled.
+#[tauri::command]
+async fn export_database(
+    app: tauri::AppHandle,
+    destination: Option<std::path::PathBuf>,
+) -> Result<Option<dbexport::DatabaseExport>, dbexport::ExportError> {
+    let unavailable = |message: String| dbexport::ExportError::Unavailable { message };
+    let destination = match destination {
+        Some(path) => path,
+        None => {
+            let dialog = app.clone();
+            let picked = tauri::async_runtime::spawn_blocking(move || {
+                dialog
+                    .dialog()
+                    .file()
+                    .set_title("Export database")
+                    .set_file_name(support::default_database_export_name(SystemTime::now()))
+                    .add_filter("SQLite database", &["db"])
+                    .blocking_save_file()
+            })
+            .await
+            .map_err(|e| unavailable(format!("Choosing the export location failed: {e}")))?;
+            match picked {
+                Some(path) => path
+                    .into_path()
+                    .map_err(|e| unavailable(format!("Unusable export location: {e}")))?,
+                None => return Ok(None),
+            }
+        }
+    };
+    dbexport::export(&app, &destination).await.map(Some)
+}
+
+/// Tauri command: replace the backend's data with a `backup_data` zip. The backend is
+/// stopped, the current data moved to a `.bak` folder beside the data dir and the zip
+/// extracted with `restore-progress` events; if the backend doesn't become ready on it,
+/// the previous data is put back. Errors mean nothing was changed.
+#[tauri::command]
+async fn restore_data(
+    app: tauri::AppHandle,
+    archive_path: std::path::PathBuf,
+) -> Result<restore::RestoreOutcome, String> {
+    restore::restore(&app, archive_path).await
+}
+
+/// Tauri command: back the database and settings up into `DATA_DIR/backups` before
+/// `version` is installed, keeping the newest `pre_update_backups_kept`. Emits
+/// `pre-update-backup` with the outcome, which is also recorded for
+/// `list_pre_update_backups`.
+#[tauri::command]
+async fn prepare_for_update(
+    app: tauri::AppHandle,
+    version: String,
+) -> Result<preupdate::PreUpdateBackup, String> {
+    preupdate::prepare(&app, &version).await
+}
+
+/// Tauri command: the recorded pre-update backups, newest first, for the restore screen to
+/// list apart from the user's own.
+#[tauri::command]
+async fn list_pre_update_backups(
+    app: tauri::AppHandle,
+) -> Result<Vec<preupdate::PreUpdateBackup>, String> {
+    preupdate::list(&app).await
+}
+
+/// Tauri command: how many pre-update backups to keep; 0 keeps them all. Applies from the
+/// next one.
+#[tauri::command]
+fn set_pre_update_backups_kept(app: tauri::AppHandle, kept: u32) -> Result<(), String> {
+    settings::update(&app, |s| s.pre_update_backups_kept = kept)
+}
+
+/// Tauri command: the token `factory_reset` must be given, valid once for two minutes.
+#[tauri::command]
+fn request_factory_reset(app: tauri::AppHandle) -> Result<reset::ResetRequest, String> {
+    reset::request(&app)
+}
+
+/// Tauri command: stop the backend and delete its data and the settings, plus the caches
+/// and logs per `options`, then start a fresh backend or relaunch the app. Each step is
+/// logged to a file in the OS temp dir, which the reset leaves alone.
+#[tauri::command]
+async fn factory_reset(
+    app: tauri::AppHandle,
+    confirm_token: String,
+    options: Option<reset::ResetOptions>,
+) -> Result<reset::FactoryReset, String> {
+    reset::reset(&app, &confirm_token, options.unwrap_or_default()).await
+}
+
+/// Tauri command: the last `lines` records of backend output, optionally of one stream
+/// only and at least as severe as `min_level`, with the path of the log file so the user
+/// can open it.
+#[tauri::command]
+async fn get_backend_logs(
+    app: tauri::AppHandle,
+    lines: usize,
+    stream: Option<backendlog::Stream>,
+    min_level: Option<loglevel::LogLevel>,
+) -> Result<backendlog::LogTail, String> {
+    let count = lines.min(backendlog::MAX_TAIL_LINES);
+    tauri::async_runtime::spawn_blocking(move || backendlog::tail(&app, count, stream, min_level))
+        .await
+        .map_err(|e| format!("Reading the backend log failed: {e}"))?
+}
+
+/// Tauri command: calls to the backend in flight and queued, per concurrency lane.
+#[tauri::command]
+fn get_request_queue_stats(app: tauri::AppHandle) -> concurrency::QueueStats {
+    concurrency::stats(&app)
+}
+
+/// Tauri command: change how many uploads and proxied requests reach the backend at once.
+///
+/// Applies immediately, without restarting the backend, and is saved for later launches.
+#[tauri::command]
+fn set_request_concurrency(
+    app: tauri::AppHandle,
+    uploads: Option<u32>,
+    requests: Option<u32>,
+) -> Result<(), String> {
+    for limit in uploads.iter().chain(&requests) {
+        concurrency::validate(*limit)?;
+    }
+    settings::update(&app, |s| {
+        s.max_concurrent_uploads = uploads.unwrap_or(s.max_concurrent_uploads);
+        s.max_concurrent_requests = requests.unwrap_or(s.max_concurrent_requests);
+    })?;
+    let limits = app.state::<concurrency::RequestLimits>();
+    if let Some(uploads) = uploads {
+        limits.set_limit(concurrency::Lane::Uploads, uploads);
+    }
+    if let Some(requests) = requests {
+        limits.set_limit(concurrency::Lane::Requests, requests);
+    }
+    Ok(())
+}
+
+/// Tauri command: the token the backend expects as `Authorization: Bearer`, or `None` when
+/// it requires none (dev mode, external backend).
+///
+/// Rotates with every backend launch; fetch it again on `backend-ready`.
+#[tauri::command]
+fn get_backend_auth() -> Option<String> {
+    auth::token()
+}
+
+/// Whether quitting has to wait: the backend reported jobs in progress and the user has not
+/// confirmed with `force_exit`. Emits `exit-blocked` for the frontend's dialog.
+fn exit_blocked_by_jobs(app: &tauri::AppHandle) -> bool {
+    // Don't throw away a batch mid-OCR without asking the user first
+    let has_backend = app
+        .state::<BackendProcess>()
+        .0
+        .lock()
+        .is_ok_and(|g| g.is_some());
+    if !has_backend || app.state::<ExitConfirmed>().0.load(Ordering::SeqCst) {
+        return false;
+    }
+    let jobs = tauri::async_runtime::block_on(active_backend_jobs(&endpoint::transport(app)));
+    if jobs.is_empty() {
+        return false;
+    }
+    log::info!("Exit blocked: backend has {} active job(s)", jobs.len());
+    let _ = app.emit("exit-blocked", ExitBlocked { jobs });
+    true
+}
+
+/// On macOS the app stays in the dock after its last window closes; with quit-on-close
+/// off, the backend keeps running for the window the dock icon reopens.
+fn keep_running_without_windows(app: &tauri::AppHandle, exit_code: Option<i32>) -> bool {
+    cfg!(target_os = "macos") && exit_code.is_none() && !settings::current(app).quit_on_close
+}
+
+/// Stop the backend for good because the app is going away. Later calls are no-ops.
+fn shutdown_for_exit(app: &tauri::AppHandle) {
+    let supervisor = app.state::<Supervisor>();
+    supervisor.set_exiting();
+    supervisor.request_stop();
+    let state = app.state::<BackendProcess>();
+    let child = match state.0.lock() {
+        Ok(mut g) => g.take(),
+        Err(_) => return,
+    };
+    if let Some(child) = child {
+        log::info!("Shutting down backend process...");
+        let port = child.port;
+        supervisor::resume_if_suspended(app, child.pid());
+        tauri::async_runtime::block_on(shutdown_backend(child, SHUTDOWN_GRACE_PERIOD));
+        log::info!("Backend process terminated");
+        pidfile::remove(app);
+        if !backend_port_is_free(port) {
+            log::warn!("Port {} is still in use after backend shutdown", port);
+        }
+        tempdir::sweep_logged(app, temp_max_age(app));
+        purge::purge_on_exit(app);
+    }
+    datalock::release(app);
+}
+
+/// Age after which files in the backend temp dir are swept, from settings.
+fn temp_max_age(app: &tauri::AppHandle) -> Duration {
+    Duration::from_secs(
+        settings::current(app)
+            .temp_max_age_hours
+            .saturating_mul(3600),
+    )
+}
+
+/// Shut the backend down when the main window goes away, rather than relying on
+/// `ExitRequested`, which some close paths deliver late or not at all.
+fn on_main_window_event(window: &tauri::Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW_LABEL {
+        return;
+    }
+    let app = window.app_handle();
+    if !settings::current(app).quit_on_close {
+        return;
+    }
+    match event {
+        WindowEvent::CloseRequested { api, .. } if exit_blocked_by_jobs(app) => {
+            api.prevent_close();
+        }
+        WindowEvent::Destroyed => {
+            let already_exiting = app.state::<Supervisor>().is_exiting();
+            shutdown_for_exit(app);
+            if !already_exiting {
+                app.exit(0);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Recreate the main window from the config when the dock icon is clicked, and tell it
+/// about the backend that kept running in the meantime.
+#[cfg(target_os = "macos")]
+fn reopen_main_window(app: &tauri::AppHandle) {
+    if app.get_webview_window(MAIN_WINDOW_LABEL).is_some() {
+        return;
+    }
+    let Some(config) = app.config().app.windows.first() else {
+        return;
+    };
+    match tauri::WebviewWindowBuilder::from_config(app, config).and_then(|b| b.build()) {
+        Ok(_) => {
+            if app.state::<Supervisor>().state() == SpawnState::Running {
+                let ready = BackendReady::new(app, None, None);
+                events::emit(app, "backend-ready", ready);
+            }
+        }
+        Err(e) => log::error!("Failed to reopen main window: {}", e),
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    #[cfg(unix)]
+    launcher::exec_if_requested();
+    panics::install();
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .on_window_event(|window, event| {
+            on_main_window_event(window, event);
+            logwindow::on_window_event(window, event);
+        })
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                events::replay(webview);
+            }
+        })
         .manage(BackendProcess(Mutex::new(None)))
-        .invoke_handler(tauri::generate_handler![check_backend_health, get_backend_url])
+        .manage(Supervisor::default())
+        .manage(BackendStderr(Mutex::new(VecDeque::new())))
+        .manage(loopback::Loopback::default())
+        .manage(ExitConfirmed(AtomicBool::new(false)))
+        .manage(HealthLatency::default())
+        .manage(HealthCache::default())
+        .manage(events::BackendEvents::default())
+        .manage(heartbeat::HeartbeatInterval::default())
+        .manage(port::BackendPort::default())
+        .manage(transport::BackendSocket::default())
+        .manage(cancel::Cancellations::default())
+        .manage(jobevents::JobSubscriptions::default())
+        .manage(invoices::InvoiceCache::default())
+        .manage(cache::ResponseCache::default())
+        .manage(storage::StorageCache::default())
+        .manage(reset::ResetToken::default())
+        .manage(backendlog::BackendLog::default())
+        .manage(redact::Redactor::default())
+        .manage(startupmetrics::StartupMetrics::default())
+        .manage(resources::ResourceMonitor::default())
+        .manage(telemetry::Telemetry::default())
+        .manage(wake::Wake::default())
+        .manage(legacy::LegacyData::default())
+        .invoke_handler(tauri::generate_handler![
+            check_backend_health,
+            check_backend_health_detailed,
+            get_backend_health,
+            get_backend_status,
+            wait_for_backend,
+            get_last_backend_event,
+            get_health_latency_history,
+            get_version_info,
+            restart_backend,
+            start_backend,
+            stop_backend,
+            reset_backend_failure_state,
+            get_backend_process_info,
+            set_backend_priority,
+            send_backend_control,
+            suspend_backend,
+            resume_backend,
+            pause_health_monitor,
+            resume_health_monitor,
+            set_health_monitor_interval,
+            set_heartbeat_interval,
+            set_quit_on_close,
+            set_extra_backend_args,
+            set_show_backend_console,
+            set_external_backend_url,
+            get_storage_usage,
+            purge_cache,
+            set_clear_caches_on_exit,
+            force_exit,
+            get_backend_url,
+            get_backend_auth,
+            backend_request,
+            upload_invoice,
+            cancel_upload,
+            cancel_backend_request,
+            download_to_file,
+            subscribe_job_events,
+            unsubscribe_job_events,
+            get_request_queue_stats,
+            set_request_concurrency,
+            set_loopback_tls,
+            list_invoices,
+            search_invoices,
+            invalidate_cache,
+            get_cache_stats,
+            set_backend_log_forwarding,
+            open_backend_console,
+            get_log_backlog,
+            get_backend_logs,
+            open_logs_folder,
+            open_data_folder,
+            get_log_level,
+            set_log_level,
+            create_support_bundle,
+            export_logs,
+            backup_data,
+            export_database,
+            restore_data,
+            prepare_for_update,
+            list_pre_update_backups,
+            set_pre_update_backups_kept,
+            request_factory_reset,
+            factory_reset,
+            collect_diagnostics,
+            set_log_retention,
+            get_startup_metrics,
+            get_backend_resource_usage,
+            set_resource_thresholds,
+            get_resource_thresholds,
+            get_disk_space,
+            set_disk_space_thresholds,
+            get_disk_space_thresholds,
+            get_data_dir,
+            set_data_dir,
+            get_legacy_data,
+            migrate_legacy_data,
+            copy_diagnostics_to_clipboard,
+            set_telemetry_enabled,
+            get_telemetry_preview
+        ])
         .setup(|app| {
             let handle = app.handle().clone();
+            panics::attach(&handle);
+            if let Err(e) = shelllog::init(&handle) {
+                // Reaches whichever logger got installed first; diagnostics report it too.
+                log::error!("{}", e);
+            }
+
+            let loaded = settings::load(&handle);
+            loglevel::init(loaded.log_level);
+            redact::configure(&handle, &loaded);
+            backendlog::start(&handle, loaded.log_retention());
+            startupmetrics::load(&handle);
+            telemetry::load(&handle);
+            telemetry::record(
+                &handle,
+                telemetry::EventKind::AppStart {
+                    app_version: handle.package_info().version.to_string(),
+                    os: std::env::consts::OS.to_string(),
+                    arch: std::env::consts::ARCH.to_string(),
+                },
+            );
+            app.manage(AppConfig::load(&loaded));
+            app.manage(concurrency::RequestLimits::new(&loaded));
+            let proxy_vars = env::proxy_vars();
+            if !proxy_vars.is_empty() {
+                log::info!(
+                    "System proxy set ({}); requests to the backend bypass it",
+                    proxy_vars.join(", ")
+                );
+            }
+            app.manage(AppSettings(Mutex::new(loaded)));
+            #[cfg(windows)]
+            if settings::current(&handle).show_backend_console {
+                if let Err(e) = console::show() {
+                    log::warn!("{}", e);
+                }
+            }
+            #[cfg(windows)]
+            app.manage(job::BackendJob(Mutex::new(None)));
+
+            // A bundled backend left over from a crashed run would answer on our port
+            // with stale settings (and would shadow the dev backend too). One belonging to
+            // another running instance is no orphan.
+            if datalock::held_elsewhere(&handle) {
+                log::warn!("Another instance of the app holds the data dir");
+            } else {
+                pidfile::reap_orphan(&handle);
+            }
+            let sweep_handle = handle.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                tempdir::sweep_logged(&sweep_handle, temp_max_age(&sweep_handle));
+            });
+            supervisor::start_watchdog(handle.clone(), supervisor::WatchdogConfig::DEFAULT);
+            heartbeat::start(handle.clone());
+            resources::start_watch(handle.clone());
+            diskspace::start_watch(handle.clone());
+            telemetry::start(handle.clone());
 
             #[cfg(debug_assertions)]
             {
                 // Dev mode: don't spawn backend, just check if it's already running
                 log::info!("Dev mode: skipping backend spawn, checking if backend is running...");
                 tauri::async_runtime::spawn(async move {
-                    let url = format!("http://{}:{}/health", BACKEND_HOST, BACKEND_PORT);
-                    let client = reqwest::Client::builder()
-                        .timeout(std::time::Duration::from_secs(2))
-                        .build()
-                        .unwrap();
-
-                    match client.get(&url).send().await {
-                        Ok(resp) if resp.status().is_success() => {
+                    // A backend started by hand may listen on ::1 only.
+                    let port = port::current(&handle);
+                    loopback::resolve(&handle, port, Duration::from_millis(500)).await;
+                    match health::fetch(&endpoint::transport(&handle), Duration::from_secs(2)).await {
+                        Ok(health) => {
                             log::info!("Dev backend already running");
-                            let _ = handle.emit("backend-ready", ());
+                            supervisor::set_status(&handle, supervisor::ready_now());
+                            let ready = BackendReady::new(&handle, None, Some(health));
+                            events::emit(&handle, "backend-ready", ready);
                         }
-                        _ => {
+                        Err(_) => {
                             log::warn!(
                                 "Dev backend not detected at {}:{}. Start it manually: cd backend && python run_server.py",
-                                BACKEND_HOST, BACKEND_PORT
+                                BACKEND_HOST, DEFAULT_BACKEND_PORT
                             );
-                            let _ = handle.emit(
+                            events::emit(
+                                &handle,
                                 "backend-error",
-                                "Backend not running. Start it manually: cd backend && python run_server.py",
+                                supervisor::BackendError::bare(StartupError::from(
+                                    "Backend not running. Start it manually: cd backend && python run_server.py".to_string(),
+                                )),
                             );
                         }
                     }
@@ -173,50 +2281,79 @@ pub fn run() {
             #[cfg(not(debug_assertions))]
             {
                 // Production mode: spawn and wait for backend
-                match spawn_backend(&handle) {
-                    Ok(child) => {
-                        let state = handle.state::<BackendProcess>();
-                        *state.0.lock().unwrap() = Some(child);
-                        log::info!("Backend process spawned, waiting for ready...");
-
-                        tauri::async_runtime::spawn(async move {
-                            match wait_for_backend_ready().await {
-                                Ok(()) => {
-                                    log::info!("Backend is ready");
-                                    let _ = handle.emit("backend-ready", ());
-                                }
-                                Err(e) => {
-                                    log::error!("Backend failed to start: {}", e);
-                                    let _ = handle.emit("backend-error", e);
-                                }
-                            }
-                        });
-                    }
-                    Err(e) => {
-                        log::error!("Failed to spawn backend: {}", e);
-                        let _ = handle.emit("backend-error", e);
-                    }
-                }
+                restore::recover(&handle);
+                legacy::detect(&handle);
+                tauri::async_runtime::spawn(async move {
+                    let supervisor = handle.state::<Supervisor>();
+                    let mut stop = supervisor.stop_signal();
+                    let _guard = supervisor.lock_lifecycle().await;
+                    let _ = supervisor::launch_and_wait(&handle, &mut stop).await;
+                });
             }
 
             Ok(())
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|app_handle, event| {
-            if let RunEvent::ExitRequested { .. } = event {
-                // Kill the backend process on exit
-                let state = app_handle.state::<BackendProcess>();
-                let mut guard = match state.0.lock() {
-                    Ok(g) => g,
-                    Err(_) => return,
-                };
-                if let Some(ref mut child) = *guard {
-                    log::info!("Shutting down backend process...");
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    log::info!("Backend process terminated");
+        .run(|app_handle, event| match event {
+            RunEvent::ExitRequested { api, code, .. } => {
+                if keep_running_without_windows(app_handle, code) {
+                    log::info!("Last window closed, keeping the backend for the next window");
+                    api.prevent_exit();
+                    return;
+                }
+                if exit_blocked_by_jobs(app_handle) {
+                    api.prevent_exit();
+                    return;
                 }
+                shutdown_for_exit(app_handle);
             }
+            RunEvent::Exit => backendlog::flush(app_handle),
+            #[cfg(target_os = "macos")]
+            RunEvent::Reopen {
+                has_visible_windows: false,
+                ..
+            } => reopen_main_window(app_handle),
+            _ => {}
         });
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    /// A worker still holding the port goes down with the group, so the next launch can
+    /// bind it again.
+    #[test]
+    fn killing_the_process_group_frees_the_port() {
+        let listener = std::net::TcpListener::bind((BACKEND_HOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let fd = listener.as_raw_fd();
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", "sleep 300 & wait"]).process_group(0);
+        // SAFETY: fcntl(2) is async-signal-safe; clearing FD_CLOEXEC lets the shell and its
+        // worker inherit the listener, like uvicorn workers do.
+        unsafe {
+            command.pre_exec(move || match libc::fcntl(fd, libc::F_SETFD, 0) {
+                -1 => Err(std::io::Error::last_os_error()),
+                _ => Ok(()),
+            });
+        }
+        let mut backend = command.spawn().unwrap();
+        drop(listener);
+        assert!(
+            !backend_port_is_free(port),
+            "the group should hold the port"
+        );
+
+        assert!(signal_process_group(backend.id(), libc::SIGKILL));
+        backend.wait().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !backend_port_is_free(port) {
+            assert!(Instant::now() < deadline, "port {port} still in use");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Log verbosity of the shell and the backend, adjustable at runtime for support sessions.
+//!
+//! The level is saved with the time it was set; anything more verbose than info goes back
+//! to info a day later, so debug output doesn't stay on long after the session.
+
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::proxy::TimeoutClass;
+use crate::{config, endpoint, settings, transport, unix_millis};
+
+/// Backend environment variable holding the level it starts with.
+pub(crate) const LOG_LEVEL_ENV: &str = "LOG_LEVEL";
+const BACKEND_PATH: &str = "/api/v1/admin/loglevel";
+/// How long a level more verbose than info survives restarts.
+const VERBOSE_FOR: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Error,
+    #[serde(alias = "warning")]
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> log::LevelFilter {
+        match self {
+            Self::Error => log::LevelFilter::Error,
+            Self::Warn => log::LevelFilter::Warn,
+            Self::Info => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace,
+        }
+    }
+
+    fn from_filter(filter: log::LevelFilter) -> Self {
+        match filter {
+            log::LevelFilter::Off | log::LevelFilter::Error => Self::Error,
+            log::LevelFilter::Warn => Self::Warn,
+            log::LevelFilter::Info => Self::Info,
+            log::LevelFilter::Debug => Self::Debug,
+            log::LevelFilter::Trace => Self::Trace,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+/// A level chosen with `set_log_level`, as saved in the settings.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct SavedLevel {
+    pub level: LogLevel,
+    /// Unix time in milliseconds.
+    pub set_at: u64,
+}
+
+/// Payload of `get_log_level` and `set_log_level`.
+#[derive(Debug, Serialize)]
+pub(crate) struct LogLevels {
+    pub shell: LogLevel,
+    /// `None` when the backend is not running or too old to say.
+    pub backend: Option<LogLevel>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LevelBody {
+    level: LogLevel,
+}
+
+/// The saved level if it is still in force, else info.
+pub(crate) fn effective(saved: Option<SavedLevel>) -> LogLevel {
+    let Some(saved) = saved else {
+        return LogLevel::default();
+    };
+    let age = unix_millis(SystemTime::now()).saturating_sub(saved.set_at);
+    if saved.level > LogLevel::Info && age > VERBOSE_FOR.as_millis() as u64 {
+        LogLevel::default()
+    } else {
+        saved.level
+    }
+}
+
+/// Filter the shell's logging at the saved level, at launch.
+pub(crate) fn init(saved: Option<SavedLevel>) {
+    log::set_max_level(effective(saved).filter());
+}
+
+/// The shell's level now.
+pub(crate) fn shell() -> LogLevel {
+    LogLevel::from_filter(log::max_level())
+}
+
+/// Switch the shell to `level` right away, save it for later launches and pass it on to
+/// the running backend. A backend that can't take it live picks it up when next spawned.
+pub(crate) async fn set(app: &AppHandle, level: LogLevel) -> Result<LogLevels, String> {
+    log::set_max_level(level.filter());
+    let saved = (level != LogLevel::default()).then(|| SavedLevel {
+        level,
+        set_at: unix_millis(SystemTime::now()),
+    });
+    settings::update(app, |s| s.log_level = saved)?;
+    log::info!("Log level set to {}", level.as_str());
+
+    let body = serde_json::to_vec(&LevelBody { level }).map_err(|e| e.to_string())?;
+    let mut request = transport::Request::new(
+        reqwest::Method::PUT,
+        BACKEND_PATH,
+        config::request_timeout(app, TimeoutClass::Fast),
+    );
+    request.body = Some(transport::Body::json(body));
+    let backend = match endpoint::transport(app).send(request).await {
+        Ok(resp) if resp.is_success() => resp.json::<LevelBody>().ok().map(|b| b.level),
+        Ok(resp) => {
+            log::warn!(
+                "Backend kept its log level (HTTP {}); it applies at next start",
+                resp.status
+            );
+            None
+        }
+        Err(e) => {
+            log::debug!("Backend log level applies at next start: {}", e);
+            None
+        }
+    };
+    Ok(LogLevels {
+        shell: level,
+        backend,
+    })
+}
+
+/// Both levels in effect, asking the backend for its own.
+pub(crate) async fn get(app: &AppHandle) -> LogLevels {
+    let timeout = config::request_timeout(app, TimeoutClass::Fast);
+    let backend = match endpoint::transport(app).get(BACKEND_PATH, timeout).await {
+        Ok(resp) if resp.is_success() => resp.json::<LevelBody>().ok().map(|b| b.level),
+        _ => None,
+    };
+    LogLevels {
+        shell: shell(),
+        backend,
+    }
+}
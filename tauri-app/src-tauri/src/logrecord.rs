@@ -0,0 +1,171 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Structured form of a backend output line, for filtering the console by level.
+//!
+//! JSON lines (`{"level": "error", "msg": ...}`) are read field by field. Other lines are
+//! kept as text, with the level taken from the prefixes uvicorn (`WARNING:  ...`) and
+//! structlog's console renderer (`... [warning  ] ...`) print, when there is one.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::loglevel::LogLevel;
+
+/// JSON keys tried, in order, for each field.
+const LEVEL_KEYS: &[&str] = &["level", "levelname", "severity"];
+const MESSAGE_KEYS: &[&str] = &["msg", "message", "event"];
+const MODULE_KEYS: &[&str] = &["module", "logger", "name"];
+/// How far into a text line a level prefix is looked for.
+const PREFIX_BYTES: usize = 64;
+
+/// One line of backend output, as `get_backend_logs` and `backend-log` send it.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BackendLogRecord {
+    /// "stdout" or "stderr"; "note" for the shell's own markers in the log file.
+    pub stream: &'static str,
+    /// The line as the backend printed it.
+    pub line: String,
+    /// Milliseconds since the Unix epoch, when the line arrived.
+    pub ts: u64,
+    /// `None` when a text line carries no recognisable level.
+    pub level: Option<LogLevel>,
+    pub message: String,
+    pub module: Option<String>,
+    /// Whether the line was JSON.
+    pub structured: bool,
+    /// The remaining fields of a JSON line.
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+}
+
+impl BackendLogRecord {
+    pub(crate) fn parse(stream: &'static str, line: &str, ts: u64) -> Self {
+        let trimmed = line.trim();
+        if trimmed.starts_with('{') {
+            if let Ok(Value::Object(fields)) = serde_json::from_str(trimmed) {
+                return Self::from_json(stream, line, ts, fields);
+            }
+        }
+        let text = strip_ansi(line);
+        let (level, message) = match text_level(&text) {
+            Some((level, message)) => (Some(level), message.to_string()),
+            None => (None, text.clone()),
+        };
+        Self {
+            stream,
+            line: line.to_string(),
+            ts,
+            level,
+            message,
+            module: None,
+            structured: false,
+            fields: Map::new(),
+        }
+    }
+
+    fn from_json(
+        stream: &'static str,
+        line: &str,
+        ts: u64,
+        mut fields: Map<String, Value>,
+    ) -> Self {
+        let mut take = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| fields.remove(*key))
+                .map(|value| match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                })
+        };
+        let level = take(LEVEL_KEYS).and_then(|level| parse_level(&level));
+        let message = take(MESSAGE_KEYS).unwrap_or_default();
+        let module = take(MODULE_KEYS);
+        Self {
+            stream,
+            line: line.to_string(),
+            ts,
+            level,
+            message,
+            module,
+            structured: true,
+            fields,
+        }
+    }
+
+    /// A marker the shell wrote into the log file, e.g. when the backend started.
+    pub(crate) fn note(text: &str, ts: u64) -> Self {
+        Self {
+            stream: "note",
+            line: text.to_string(),
+            ts,
+            level: Some(LogLevel::Info),
+            message: text.to_string(),
+            module: None,
+            structured: false,
+            fields: Map::new(),
+        }
+    }
+
+    /// Whether the record is at least as severe as `min`. Lines without a level count as
+    /// info, so tracebacks and other bare output show unless only warnings are asked for.
+    pub(crate) fn at_least(&self, min: LogLevel) -> bool {
+        self.level.unwrap_or(LogLevel::Info) <= min
+    }
+}
+
+/// Python's and the usual other level names, in any case.
+fn parse_level(name: &str) -> Option<LogLevel> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "critical" | "fatal" | "error" | "err" | "exception" => Some(LogLevel::Error),
+        "warning" | "warn" => Some(LogLevel::Warn),
+        "info" | "notice" => Some(LogLevel::Info),
+        "debug" => Some(LogLevel::Debug),
+        "trace" => Some(LogLevel::Trace),
+        _ => None,
+    }
+}
+
+/// The level of a text line and the message after it, from a `LEVEL:` start (uvicorn,
+/// Python's default format) or a `[level]` near the start (structlog).
+fn text_level(text: &str) -> Option<(LogLevel, &str)> {
+    if let Some((name, rest)) = text.split_once(':') {
+        if let Some(level) = parse_level(name).filter(|_| !name.contains(' ')) {
+            return Some((level, rest.trim_start()));
+        }
+    }
+    let head = &text[..text.floor_char_boundary(PREFIX_BYTES)];
+    let open = head.find('[')?;
+    let close = open + head[open..].find(']')?;
+    let level = parse_level(&head[open + 1..close])?;
+    Some((level, text[close + 1..].trim_start()))
+}
+
+/// `text` without terminal colour codes (`ESC [ ... m`).
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip to the end of the escape sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,52 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A window of its own with a live tail of backend output.
+//!
+//! While it is open, `backend-log` goes to it alone, so the main window isn't sent lines
+//! it doesn't show. It starts from `backendlog::backlog`, which outlives backend restarts.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Window, WindowEvent};
+
+use crate::backendlog;
+
+pub(crate) const LABEL: &str = "backend-console";
+/// The frontend renders the console instead of the app for this query.
+const URL: &str = "index.html?view=backend-console";
+
+/// Open the console window, or bring it to the front if it is already open.
+pub(crate) fn open(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus the backend console: {e}"));
+    }
+    WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App(URL.into()))
+        .title("Backend console")
+        .inner_size(900.0, 500.0)
+        .build()
+        .map_err(|e| format!("Failed to open the backend console: {e}"))?;
+    backendlog::set_target(app, Some(LABEL.to_string()));
+    backendlog::set_forwarding(app, true, None);
+    Ok(())
+}
+
+/// Stop forwarding once the console window is gone.
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != LABEL || !matches!(event, WindowEvent::Destroyed) {
+        return;
+    }
+    let app = window.app_handle();
+    backendlog::set_forwarding(app, false, None);
+    backendlog::set_target(app, None);
+}
@@ -0,0 +1,202 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Which loopback address reaches the backend.
+//!
+//! `localhost` resolves to `::1` only on some machines, and a backend started by hand may
+//! bind there. We try `127.0.0.1`, `::1` and whatever the backend said it bound to, and
+//! keep the one that answered.
+
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::transport::BackendTransport;
+use crate::{endpoint, tls, unix_millis};
+
+const CANDIDATES: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::LOCALHOST),
+    IpAddr::V6(Ipv6Addr::LOCALHOST),
+];
+/// Probe outcomes kept for `get_backend_process_info`.
+const MAX_PROBES: usize = 20;
+
+/// The address in use and how we got there, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct Loopback(Mutex<State>);
+
+#[derive(Default)]
+struct State {
+    /// The address that last accepted a connection.
+    working: Option<IpAddr>,
+    /// Where the running backend said it listens, from its startup output.
+    reported: Option<IpAddr>,
+    probes: VecDeque<Probe>,
+}
+
+impl State {
+    fn host(&self) -> IpAddr {
+        self.working.or(self.reported).unwrap_or(CANDIDATES[0])
+    }
+}
+
+/// One connection attempt to a candidate address.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Probe {
+    address: String,
+    connected: bool,
+    error: Option<String>,
+    at_ms: u64,
+}
+
+/// What `get_backend_process_info` reports about loopback resolution.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Report {
+    host: IpAddr,
+    reported: Option<IpAddr>,
+    probes: Vec<Probe>,
+}
+
+/// The loopback address to use for the backend: the one that last worked, else the one
+/// it reported, else `127.0.0.1`.
+pub(crate) fn host(app: &AppHandle) -> IpAddr {
+    app.state::<Loopback>().0.lock().unwrap().host()
+}
+
+/// Try the candidate addresses on `port`, the reported and last working ones first, and
+/// keep the first that accepts a connection.
+pub(crate) async fn resolve(app: &AppHandle, port: u16, timeout: Duration) -> Option<IpAddr> {
+    let candidates = {
+        let state = app.state::<Loopback>();
+        let state = state.0.lock().unwrap();
+        let mut candidates: Vec<IpAddr> = Vec::new();
+        for ip in state
+            .reported
+            .into_iter()
+            .chain(state.working)
+            .chain(CANDIDATES)
+        {
+            if !candidates.contains(&ip) {
+                candidates.push(ip);
+            }
+        }
+        candidates
+    };
+    for ip in candidates {
+        let addr = SocketAddr::new(ip, port);
+        let error = match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(addr)).await
+        {
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("no answer within {}ms", timeout.as_millis())),
+        };
+        let connected = error.is_none();
+        record(app, addr, error);
+        if connected {
+            let state = app.state::<Loopback>();
+            let previous = state.0.lock().unwrap().working.replace(ip);
+            if previous.is_some_and(|previous| previous != ip) {
+                log::warn!("Backend now answers on {}, switching to it", addr);
+            }
+            return Some(ip);
+        }
+    }
+    None
+}
+
+/// A TCP transport on another loopback address, if `transport` points at ours on one
+/// that doesn't answer but another does.
+pub(crate) async fn switch(
+    app: &AppHandle,
+    transport: &BackendTransport,
+    timeout: Duration,
+) -> Option<BackendTransport> {
+    let BackendTransport::Tcp(base) = transport else {
+        return None;
+    };
+    let (host, port) = endpoint::socket_addr(base)?;
+    let current: IpAddr = host.trim_matches(['[', ']']).parse().ok()?;
+    if !current.is_loopback() {
+        return None;
+    }
+    let ip = resolve(app, port, timeout).await?;
+    (ip != current).then(|| BackendTransport::Tcp(base_url(ip, port)))
+}
+
+/// `http(s)://host:port`, with brackets around an IPv6 host.
+pub(crate) fn base_url(ip: IpAddr, port: u16) -> String {
+    format!("{}://{}", tls::scheme(), SocketAddr::new(ip, port))
+}
+
+/// Note the address in a line like uvicorn's "Uvicorn running on http://[::1]:8000".
+pub(crate) fn note_output(app: &AppHandle, line: &str) {
+    let Some(start) = line
+        .find("running on http://")
+        .or_else(|| line.find("running on https://"))
+    else {
+        return;
+    };
+    let url = line[start + "running on ".len()..]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let Some(host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+    else {
+        return;
+    };
+    let ip = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        // Bound to every interface: loopback of the same family reaches it.
+        Ok(IpAddr::V4(ip)) if ip.is_unspecified() => CANDIDATES[0],
+        Ok(IpAddr::V6(ip)) if ip.is_unspecified() => CANDIDATES[1],
+        Ok(ip) if ip.is_loopback() => ip,
+        _ => return,
+    };
+    log::debug!("Backend reports listening on {}", ip);
+    app.state::<Loopback>().0.lock().unwrap().reported = Some(ip);
+}
+
+/// Forget what the previous backend reported, before spawning the next one.
+pub(crate) fn clear_reported(app: &AppHandle) {
+    app.state::<Loopback>().0.lock().unwrap().reported = None;
+}
+
+pub(crate) fn report(app: &AppHandle) -> Report {
+    let state = app.state::<Loopback>();
+    let state = state.0.lock().unwrap();
+    Report {
+        host: state.host(),
+        reported: state.reported,
+        probes: state.probes.iter().cloned().collect(),
+    }
+}
+
+fn record(app: &AppHandle, addr: SocketAddr, error: Option<String>) {
+    let state = app.state::<Loopback>();
+    let mut state = state.0.lock().unwrap();
+    if state.probes.len() == MAX_PROBES {
+        state.probes.pop_front();
+    }
+    state.probes.push_back(Probe {
+        address: addr.to_string(),
+        connected: error.is_none(),
+        error,
+        at_ms: unix_millis(SystemTime::now()),
+    });
+}
@@ -0,0 +1,97 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hard memory cap on the backend, so a malformed PDF that makes OCR balloon can't take
+//! the whole machine down.
+//!
+//! Linux caps the address space with `RLIMIT_AS`, set by the launch trampoline before the
+//! backend execs, so the backend and every worker it forks start under it. Windows caps the
+//! backend's Job Object after spawn. macOS accepts `RLIMIT_AS` but doesn't enforce it, so
+//! the cap is skipped there rather than reported as applied.
+//!
+//! The two caps measure different things. `RLIMIT_AS` counts every mapping the process
+//! reserves, touched or not: torch and the Florence-2 weights map shared libraries and
+//! files well beyond what they use, and glibc reserves an allocation arena per thread, so
+//! the backend's virtual size runs several times its resident memory. A cap sized from
+//! RSS makes it fail at startup. The Job Object limit counts committed memory, which is
+//! closer to what the process really uses. Either way the cap is only set when the user
+//! asks for one.
+
+/// Lines the backend prints when an allocation fails under the cap.
+const OUT_OF_MEMORY_MARKERS: &[&str] = &[
+    "MemoryError",
+    "Cannot allocate memory",
+    "std::bad_alloc",
+    "out of memory",
+];
+
+/// The cap the backend will actually run under: `limit_mb`, unless this platform can't
+/// enforce one.
+pub(crate) fn effective(limit_mb: Option<u64>) -> Option<u64> {
+    if cfg!(any(target_os = "linux", windows)) {
+        return limit_mb;
+    }
+    if let Some(limit_mb) = limit_mb {
+        log::debug!(
+            "Backend memory limits are not enforced on this platform, ignoring {limit_mb} MB"
+        );
+    }
+    None
+}
+
+/// Cap the address space of the calling process at `limit_mb`. Called from the trampoline's
+/// `pre_exec`, before logging is set up.
+#[cfg(target_os = "linux")]
+pub(crate) fn limit_self(limit_mb: u64) -> std::io::Result<()> {
+    let bytes = limit_mb.saturating_mul(1024 * 1024);
+    let limit = libc::rlimit {
+        rlim_cur: bytes,
+        rlim_max: bytes,
+    };
+    // SAFETY: `limit` is a valid rlimit.
+    if unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) } != 0 {
+        let e = std::io::Error::last_os_error();
+        return Err(std::io::Error::new(
+            e.kind(),
+            format!("cannot limit memory to {limit_mb} MB: {e}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Cap the committed memory of every process in the backend job at `limit_mb`.
+#[cfg(windows)]
+pub(crate) fn apply(app: &tauri::AppHandle, limit_mb: Option<u64>) -> Result<(), String> {
+    use tauri::Manager;
+
+    let bytes = limit_mb.map(|mb| mb.saturating_mul(1024 * 1024) as usize);
+    crate::job::limit_backend_memory(&app.state::<crate::job::BackendJob>(), bytes)
+        .map_err(|e| format!("Failed to limit backend memory: {e}"))
+}
+
+/// Whether an exit under a memory cap looks like the cap was hit: a failed allocation in the
+/// last output, or SIGKILL (the kernel OOM killer).
+pub(crate) fn looks_like_limit_hit(signal: Option<i32>, stderr_tail: &[String]) -> bool {
+    #[cfg(unix)]
+    if signal == Some(libc::SIGKILL) {
+        return true;
+    }
+    #[cfg(not(unix))]
+    let _ = signal;
+    stderr_tail.iter().any(|line| {
+        OUT_OF_MEMORY_MARKERS
+            .iter()
+            .any(|marker| line.contains(marker))
+    })
+}
@@ -0,0 +1,198 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Output forwarding and crash detection for the spawned backend process.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::process::{CommandEvent, TerminatedPayload};
+use tokio::sync::watch;
+
+use crate::supervisor::{self, BackendStatus, SpawnState, Supervisor};
+use crate::{backendlog, events, redact, telemetry};
+use crate::{exit_description, memlimit, BackendProcess};
+
+/// Number of stderr lines kept for crash reports.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Last lines the backend wrote to stderr.
+pub(crate) struct BackendStderr(pub(crate) Mutex<VecDeque<String>>);
+
+/// Payload of the `backend-crashed` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BackendCrash {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub stderr_tail: Vec<String>,
+    /// The last lines of stdout and stderr together, capped in size.
+    pub recent_output: Vec<String>,
+    /// The configured memory cap, when one was applied to this process.
+    pub memory_limit_mb: Option<u64>,
+    /// Whether the exit looks like the process ran into `memory_limit_mb`.
+    pub memory_limit_hit: bool,
+}
+
+/// Drain the shell plugin's events for the backend with `pid`: log its output, keep the
+/// last stderr lines for crash reports, and publish its exit status on `exit`.
+///
+/// The plugin blocks the process's pipes until each event is received, so this has to
+/// run for as long as the process does.
+pub(crate) fn forward_events(
+    app: AppHandle,
+    pid: u32,
+    mut events: Receiver<CommandEvent>,
+    exit: watch::Sender<Option<TerminatedPayload>>,
+) {
+    app.state::<BackendStderr>().0.lock().unwrap().clear();
+    crate::loopback::clear_reported(&app);
+    backendlog::started(&app, pid);
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => {
+                    let line = String::from_utf8_lossy(&line);
+                    let line = redact::apply(&app, line.trim_end());
+                    let line = line.as_ref();
+                    log::debug!("[backend] {}", line);
+                    backendlog::line(&app, "stdout", line);
+                    crate::loopback::note_output(&app, line);
+                    #[cfg(windows)]
+                    crate::console::echo(line);
+                }
+                CommandEvent::Stderr(line) => {
+                    let line = String::from_utf8_lossy(&line);
+                    let line = redact::apply(&app, line.trim_end()).into_owned();
+                    log::debug!("[backend] {}", line);
+                    backendlog::line(&app, "stderr", &line);
+                    crate::loopback::note_output(&app, &line);
+                    #[cfg(windows)]
+                    crate::console::echo(&line);
+
+                    let state = app.state::<BackendStderr>();
+                    let mut tail = state.0.lock().unwrap();
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+                CommandEvent::Error(e) => {
+                    log::warn!("Backend process {} event error: {}", pid, e);
+                }
+                CommandEvent::Terminated(status) => {
+                    let exited = format!("backend pid {pid} exited, {}", exit_description(&status));
+                    backendlog::note(&app, &exited);
+                    exit.send_replace(Some(status.clone()));
+                    on_exit(&app, pid, status);
+                }
+                _ => {}
+            }
+        }
+        log::debug!("Backend event stream for pid {} closed", pid);
+
+        // The plugin only ends the stream without `Terminated` when waiting on the process
+        // failed. Nobody can reap it any more, so don't keep reporting it as alive.
+        if exit.borrow().is_none() {
+            log::warn!(
+                "Lost track of backend process {} without an exit status",
+                pid
+            );
+            let status = TerminatedPayload {
+                code: None,
+                signal: None,
+            };
+            exit.send_replace(Some(status.clone()));
+            on_exit(&app, pid, status);
+        }
+    });
+}
+
+/// Emit `backend-crashed` if the backend with `pid` exited on its own, then hand over to
+/// the supervisor to bring it back.
+///
+/// Intentional stops take the child out of `BackendProcess` before killing it, and restarts
+/// replace it with a child that has a different pid; either way there is nothing to report.
+fn on_exit(app: &AppHandle, pid: u32, status: TerminatedPayload) {
+    app.state::<Supervisor>().record_exit(status.clone());
+    let child = {
+        let state = app.state::<BackendProcess>();
+        let mut slot = state.0.lock().unwrap();
+        if slot.as_ref().is_none_or(|c| c.pid() != pid) {
+            log::debug!(
+                "Backend process {} exited ({})",
+                pid,
+                exit_description(&status)
+            );
+            return;
+        }
+        slot.take()
+    };
+
+    let memory_limit_mb = child.and_then(|c| c.memory_limit_mb);
+    let stderr_tail: Vec<String> = app
+        .state::<BackendStderr>()
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect();
+    log::error!(
+        "Backend process {} exited unexpectedly ({})",
+        pid,
+        exit_description(&status)
+    );
+    let memory_limit_hit =
+        memory_limit_mb.is_some() && memlimit::looks_like_limit_hit(status.signal, &stderr_tail);
+    if memory_limit_hit {
+        log::error!(
+            "Backend appears to have hit its {} MB memory limit",
+            memory_limit_mb.unwrap_or_default()
+        );
+    }
+    let crash = BackendCrash {
+        code: status.code,
+        signal: status.signal,
+        stderr_tail,
+        recent_output: backendlog::recent_output(app),
+        memory_limit_mb,
+        memory_limit_hit,
+    };
+    telemetry::record(
+        app,
+        telemetry::EventKind::BackendCrash {
+            code: status.code,
+            signal: status.signal,
+            memory_limit_hit,
+            restart_count: app.state::<Supervisor>().stats().count,
+        },
+    );
+    events::emit(app, "backend-crashed", crash.clone());
+
+    let supervisor = app.state::<Supervisor>();
+    supervisor.set_spawned_at(None);
+    supervisor.set_state(SpawnState::Stopped);
+    supervisor::set_status(app, BackendStatus::Crashed { code: status.code });
+    if let Some(fatal) = supervisor.record_crash(crash) {
+        log::error!("{}", fatal.reason);
+        supervisor::set_status(app, BackendStatus::Fatal);
+        events::emit(app, "backend-fatal", fatal);
+    } else if !supervisor.is_exiting() && !supervisor.is_fatal() {
+        supervisor::restart_after_crash(app.clone());
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Panic hook that keeps a record of shell panics, which a bundled app would otherwise
+//! print to a stderr nobody sees.
+
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::panic::PanicHookInfo;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backendlog;
+
+const PANIC_LOG_NAME: &str = "shell-panics.log";
+
+/// Set once the app is up; panics before that only reach the default hook.
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+thread_local! {
+    /// Set while this thread is inside the hook, so a panic in the hook is not recorded.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Payload of the `shell-error` event.
+#[derive(Clone, Debug, Serialize)]
+struct ShellError {
+    message: String,
+    thread: String,
+    location: Option<String>,
+}
+
+/// Install the hook in front of the default one, which still prints to the console.
+pub(crate) fn install() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !IN_HOOK.with(|flag| flag.replace(true)) {
+            record(info);
+            IN_HOOK.with(|flag| flag.set(false));
+        }
+        default(info);
+    }));
+}
+
+/// Give the hook the app, for the log dir and the `shell-error` event.
+pub(crate) fn attach(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Append the panic to `shell-panics.log` and tell the UI. Every step ignores its own
+/// failure: the hook must never panic.
+fn record(info: &PanicHookInfo) {
+    let Some(app) = APP.get() else { return };
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let thread = std::thread::current()
+        .name()
+        .unwrap_or("<unnamed>")
+        .to_string();
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+    if let Ok(dir) = app.path().app_log_dir() {
+        let _ = std::fs::create_dir_all(&dir);
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(PANIC_LOG_NAME))
+        {
+            let _ = writeln!(
+                file,
+                "{} panic in thread '{}' at {}: {}\n{}\n",
+                backendlog::timestamp(SystemTime::now()),
+                thread,
+                location.as_deref().unwrap_or("unknown location"),
+                message,
+                Backtrace::force_capture()
+            );
+        }
+    }
+    // Straight to the webview: `events::emit` takes a lock the panicking code may hold.
+    let _ = app.emit(
+        "shell-error",
+        ShellError {
+            message,
+            thread,
+            location,
+        },
+    );
+}
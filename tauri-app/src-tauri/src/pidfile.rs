@@ -0,0 +1,157 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! PID file used to find and reap a backend orphaned by a previous app crash.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::proctree::Descendants;
+
+pub(crate) const PID_FILE_NAME: &str = "backend.pid";
+/// Allowed gap between our recorded spawn time and the OS process start time.
+const START_TIME_TOLERANCE_SECS: u64 = 5;
+const ORPHAN_EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What we know about the backend we spawned last.
+#[derive(Debug, Serialize, Deserialize)]
+struct PidFile {
+    pid: u32,
+    started_at: u64,
+    port: u16,
+    exe: PathBuf,
+}
+
+/// Payload of the `backend-orphan-reaped` event.
+#[derive(Clone, Debug, Serialize)]
+struct OrphanReaped {
+    pid: u32,
+    port: u16,
+}
+
+fn pid_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(PID_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Record the freshly spawned backend so a later launch can recognise it.
+pub(crate) fn write(app: &AppHandle, pid: u32, exe: &Path, port: u16) {
+    let record = PidFile {
+        pid,
+        started_at: unix_now(),
+        port,
+        exe: exe.to_path_buf(),
+    };
+    let result = pid_file_path(app).and_then(|path| {
+        let json = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to write backend PID file: {}", e);
+    }
+}
+
+/// Remove the PID file after a clean backend shutdown.
+pub(crate) fn remove(app: &AppHandle) {
+    if let Ok(path) = pid_file_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Whether the live process matches the recorded backend (same exe, started when we spawned it).
+fn is_our_backend(record: &PidFile, process: &sysinfo::Process) -> bool {
+    let same_exe = process.exe().is_some_and(|exe| exe == record.exe);
+    let same_start = process.start_time().abs_diff(record.started_at) <= START_TIME_TOLERANCE_SECS;
+    same_exe && same_start
+}
+
+/// Kill the orphan together with the workers it forked.
+fn terminate(process: &sysinfo::Process) {
+    let workers = Descendants::of(process.pid().as_u32());
+    process.kill();
+    workers.kill_remaining();
+}
+
+/// Kill a backend left running by a previous app instance, if the PID file points at one.
+pub(crate) fn reap_orphan(app: &AppHandle) {
+    let Ok(path) = pid_file_path(app) else { return };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    let _ = std::fs::remove_file(&path);
+    let record: PidFile = match serde_json::from_str(&contents) {
+        Ok(record) => record,
+        Err(e) => {
+            log::warn!("Ignoring unreadable backend PID file: {}", e);
+            return;
+        }
+    };
+
+    let pid = Pid::from_u32(record.pid);
+    let refresh = ProcessRefreshKind::nothing().with_exe(UpdateKind::Always);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh);
+
+    let Some(process) = system.process(pid) else {
+        log::debug!(
+            "Backend from previous run (pid {}) is no longer running",
+            record.pid
+        );
+        return;
+    };
+    if !is_our_backend(&record, process) {
+        log::debug!(
+            "Pid {} was reused by another program, leaving it alone",
+            record.pid
+        );
+        return;
+    }
+
+    log::warn!(
+        "Found orphaned backend from a previous run (pid {}, port {}), terminating it",
+        record.pid,
+        record.port
+    );
+    terminate(process);
+
+    let deadline = Instant::now() + ORPHAN_EXIT_TIMEOUT;
+    while Instant::now() < deadline {
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh);
+        if system.process(pid).is_none() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let _ = app.emit(
+        "backend-orphan-reaped",
+        OrphanReaped {
+            pid: record.pid,
+            port: record.port,
+        },
+    );
+}
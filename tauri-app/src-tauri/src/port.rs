@@ -0,0 +1,323 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Choose the backend port and detect another process holding it before we spawn.
+
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::supervisor::StartupError;
+use crate::{loopback, transport};
+use crate::{BACKEND_HOST, DEFAULT_BACKEND_PORT};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Port the current (or next) backend listens on.
+///
+/// Starts at `DEFAULT_BACKEND_PORT`, which is where the dev-mode backend runs.
+pub(crate) struct BackendPort(AtomicU16);
+
+impl Default for BackendPort {
+    fn default() -> Self {
+        Self(AtomicU16::new(DEFAULT_BACKEND_PORT))
+    }
+}
+
+pub(crate) fn current(app: &AppHandle) -> u16 {
+    app.state::<BackendPort>().0.load(Ordering::Relaxed)
+}
+
+/// `http://host:port` of the managed backend, on whichever loopback address answers.
+pub(crate) fn base_url(app: &AppHandle) -> String {
+    loopback::base_url(loopback::host(app), current(app))
+}
+
+/// Inclusive range of ports to try for the backend, e.g. 8000 to 8010.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PortRange {
+    pub first: u16,
+    pub last: u16,
+}
+
+/// Pick the port for the next backend and store it in `BackendPort`.
+///
+/// A configured port is tried first, then the configured range, and the first one nobody
+/// listens on wins. With neither configured, the OS hands out a free ephemeral port,
+/// which is released again for the backend to bind.
+pub(crate) async fn allocate(
+    app: &AppHandle,
+    fixed: Option<u16>,
+    range: Option<PortRange>,
+) -> Result<u16, StartupError> {
+    let candidates = candidates(fixed, range);
+    let port = if candidates.is_empty() {
+        TcpListener::bind((BACKEND_HOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .map_err(|e| format!("Failed to find a free port for the backend: {e}"))?
+    } else {
+        match first_free(BACKEND_HOST, &candidates) {
+            Some(port) => port,
+            None => return Err(conflict(BACKEND_HOST, &candidates).await),
+        }
+    };
+    if candidates.first().is_some_and(|first| *first != port) {
+        log::warn!(
+            "Port {} is busy, backend falls back to port {}",
+            candidates[0],
+            port
+        );
+    }
+    app.state::<BackendPort>().0.store(port, Ordering::Relaxed);
+    log::info!("Backend will listen on port {}", port);
+    Ok(port)
+}
+
+/// The ports to try, in order: `fixed`, then the rest of `range`.
+fn candidates(fixed: Option<u16>, range: Option<PortRange>) -> Vec<u16> {
+    let mut candidates: Vec<u16> = fixed.into_iter().collect();
+    if let Some(range) = range {
+        if range.first > range.last {
+            log::warn!(
+                "Ignoring empty backend port range {}-{}",
+                range.first,
+                range.last
+            );
+        }
+        candidates.extend((range.first.max(1)..=range.last).filter(|p| Some(*p) != fixed));
+    }
+    candidates
+}
+
+/// The first of `candidates` nobody listens on.
+fn first_free(host: &str, candidates: &[u16]) -> Option<u16> {
+    candidates.iter().copied().find(|p| is_free(host, *p))
+}
+
+/// Whether nothing listens on `host:port`.
+fn is_free(host: &str, port: u16) -> bool {
+    match TcpListener::bind((host, port)) {
+        Ok(_) => true,
+        // Anything else (e.g. no permission to bind) is for the backend itself to report.
+        Err(e) => e.kind() != std::io::ErrorKind::AddrInUse,
+    }
+}
+
+/// `StartupError::PortInUse` for ports that were all taken, naming who holds the first.
+async fn conflict(host: &str, tried: &[u16]) -> StartupError {
+    let port = tried[0];
+    let occupant = port_owner(port);
+    let holder = if answers_like_backend(host, port).await {
+        "another Invoicator backend".to_string()
+    } else {
+        match &occupant {
+            Some(name) => format!("another application ({name})"),
+            None => "another application".to_string(),
+        }
+    };
+    let message = match tried {
+        [_] => format!("port {port} is in use by {holder}"),
+        _ => format!(
+            "ports {} are all in use; port {port} is held by {holder}",
+            describe(tried)
+        ),
+    };
+    log::error!("Cannot start backend: {}", message);
+    StartupError::PortInUse {
+        port,
+        occupant,
+        message,
+    }
+}
+
+/// "8000-8010" for a contiguous run, else "9000, 8000, 8001".
+fn describe(ports: &[u16]) -> String {
+    let contiguous = ports.windows(2).all(|w| w[1] == w[0].wrapping_add(1));
+    match (ports.first(), ports.last()) {
+        (Some(first), Some(last)) if contiguous => format!("{first}-{last}"),
+        _ => ports
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Whether the occupant answers `/health` the way our backend does.
+///
+/// Asked without the auth token: the occupant may be anyone.
+async fn answers_like_backend(host: &str, port: u16) -> bool {
+    let url = format!("http://{}:{}/health", host, port);
+    let Ok(client) = transport::client() else {
+        return false;
+    };
+    let Ok(resp) = client.get(&url).timeout(PROBE_TIMEOUT).send().await else {
+        return false;
+    };
+    let Ok(body) = resp.json::<serde_json::Value>().await else {
+        return false;
+    };
+    body.get("status").and_then(|s| s.as_str()) == Some("healthy")
+}
+
+/// Name and pid of the process listening on `port`, where the platform tools allow it.
+#[cfg(unix)]
+fn port_owner(port: u16) -> Option<String> {
+    // -F pc prints one field per line: "p<pid>" then "c<command>".
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-Fpc"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut pid = None;
+    for line in stdout.lines() {
+        if let Some(p) = line.strip_prefix('p') {
+            pid = Some(p.to_string());
+        } else if let Some(name) = line.strip_prefix('c') {
+            return Some(match &pid {
+                Some(pid) => format!("{name}, pid {pid}"),
+                None => name.to_string(),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+fn port_owner(port: u16) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = Command::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let suffix = format!(":{port}");
+    let pid: u32 = stdout.lines().find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        match cols.as_slice() {
+            ["TCP", local, _, "LISTENING", pid] if local.ends_with(&suffix) => pid.parse().ok(),
+            _ => None,
+        }
+    })?;
+
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    let name = system
+        .process(pid)
+        .map(|p| p.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| "unknown".to_string());
+    Some(format!("{name}, pid {pid}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A listener on an ephemeral port, and that port.
+    fn occupy() -> (TcpListener, u16) {
+        let listener = TcpListener::bind((BACKEND_HOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (listener, port)
+    }
+
+    /// Accept and drop connections, so the `/health` probe fails fast instead of timing out.
+    fn refuse_http(listener: TcpListener) {
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                drop(stream);
+            }
+        });
+    }
+
+    #[test]
+    fn fixed_port_comes_first_and_is_not_repeated() {
+        let range = PortRange {
+            first: 8000,
+            last: 8003,
+        };
+        assert_eq!(
+            candidates(Some(8002), Some(range)),
+            [8002, 8000, 8001, 8003]
+        );
+        assert_eq!(candidates(None, Some(range)), [8000, 8001, 8002, 8003]);
+        let empty = PortRange {
+            first: 8003,
+            last: 8000,
+        };
+        assert_eq!(candidates(Some(9000), Some(empty)), [9000]);
+        assert!(candidates(None, None).is_empty());
+    }
+
+    #[test]
+    fn occupied_port_falls_back_to_a_free_one() {
+        let (_held, busy) = occupy();
+        let (released, free) = occupy();
+        drop(released);
+        assert!(!is_free(BACKEND_HOST, busy));
+        assert_eq!(first_free(BACKEND_HOST, &[busy, free]), Some(free));
+    }
+
+    #[test]
+    fn all_ports_taken_is_a_conflict_naming_every_port() {
+        let (first, a) = occupy();
+        let (second, b) = occupy();
+        assert_eq!(first_free(BACKEND_HOST, &[a, b]), None);
+        refuse_http(first);
+        refuse_http(second);
+
+        let error = tauri::async_runtime::block_on(conflict(BACKEND_HOST, &[a, b]));
+        let StartupError::PortInUse { port, message, .. } = error else {
+            panic!("expected PortInUse, got {error:?}");
+        };
+        assert_eq!(port, a);
+        let expected = format!(
+            "ports {} are all in use; port {a} is held by another application",
+            describe(&[a, b])
+        );
+        assert!(message.starts_with(&expected), "{message}");
+    }
+
+    #[test]
+    fn single_taken_port_is_named() {
+        let (held, port) = occupy();
+        refuse_http(held);
+        let error = tauri::async_runtime::block_on(conflict(BACKEND_HOST, &[port]));
+        let StartupError::PortInUse { message, .. } = error else {
+            panic!("expected PortInUse, got {error:?}");
+        };
+        let expected = format!("port {port} is in use by another application");
+        assert!(message.starts_with(&expected), "{message}");
+    }
+
+    #[test]
+    fn port_lists_are_described_compactly() {
+        assert_eq!(describe(&[8000, 8001, 8002]), "8000-8002");
+        assert_eq!(describe(&[9000, 8000, 8001]), "9000, 8000, 8001");
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `prepare_for_update`: a safety backup of the database and settings before an update,
+//! which may migrate the database to a newer schema.
+//!
+//! The zip goes to `DATA_DIR/backups/pre-update-<version>-<date>.zip`, in the
+//! `backup_data` format but holding only `data/` (its manifest says so, for `restore_data`
+//! to replace that alone) and the settings file under `shell/`, which restores leave out.
+//! The last `pre_update_backups_kept` of them are kept. Each attempt, failed ones included,
+//! is recorded in `backups/pre-update.json` for the restore screen and emitted as
+//! `pre-update-backup`.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backup::{self, Manifest};
+use crate::supervisor::Supervisor;
+use crate::{datadir, endpoint, settings, support, unix_millis};
+
+const RECORD_FILE: &str = "pre-update.json";
+/// What the zip replaces when restored: the folder holding the database.
+const DATABASE_DIR: &str = "data";
+/// Attempts remembered in the record, retained or not.
+const MAX_RECORDS: usize = 20;
+pub(crate) const DEFAULT_KEPT: u32 = 3;
+
+/// One `prepare_for_update` attempt, as recorded and emitted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct PreUpdateBackup {
+    pub path: PathBuf,
+    /// Size of the zip; 0 if it wasn't written.
+    pub bytes: u64,
+    pub success: bool,
+    pub error: Option<String>,
+    /// The version about to be installed.
+    pub target_version: String,
+    /// The version that made the backup.
+    pub app_version: String,
+    pub created_ms: u64,
+}
+
+fn backups_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(backup::BACKUPS_DIR)
+}
+
+/// `version` with only characters that are safe in a file name everywhere.
+fn file_version(version: &str) -> String {
+    version
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Back the database and settings up before `target_version` is installed.
+pub(crate) async fn prepare(
+    app: &AppHandle,
+    target_version: &str,
+) -> Result<PreUpdateBackup, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    let data_dir = datadir::path(app)?;
+    let now = SystemTime::now();
+    let path = backups_dir(&data_dir).join(support::pre_update_backup_name(
+        &file_version(target_version),
+        now,
+    ));
+    let mut record = PreUpdateBackup {
+        path: path.clone(),
+        bytes: 0,
+        success: false,
+        error: None,
+        target_version: target_version.to_string(),
+        app_version: app.package_info().version.to_string(),
+        created_ms: unix_millis(now),
+    };
+
+    let written = write(app, &data_dir, &path, now, &record.app_version).await;
+    match written {
+        Ok(bytes) => {
+            record.bytes = bytes;
+            record.success = true;
+            log::info!(
+                "Pre-update backup for {} written to {} ({} bytes)",
+                target_version,
+                path.display(),
+                bytes
+            );
+        }
+        Err(ref e) => {
+            record.error = Some(e.clone());
+            log::error!("Pre-update backup for {} failed: {}", target_version, e);
+        }
+    }
+    let kept = settings::current(app).pre_update_backups_kept;
+    let saved = record.clone();
+    let dir = backups_dir(&data_dir);
+    let recorded = tauri::async_runtime::spawn_blocking(move || save_record(&dir, saved, kept))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|saved| saved);
+    if let Err(e) = recorded {
+        log::warn!("Failed to record the pre-update backup: {}", e);
+    }
+    let _ = app.emit("pre-update-backup", &record);
+    written.map(|_| record)
+}
+
+async fn write(
+    app: &AppHandle,
+    data_dir: &Path,
+    path: &Path,
+    now: SystemTime,
+    app_version: &str,
+) -> Result<u64, String> {
+    let supervisor = app.state::<Supervisor>();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let settings_file = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(settings::SETTINGS_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    let snapshot = backup::snapshot(app, data_dir).await?;
+    let written = {
+        let (path, snapshot) = (path.to_path_buf(), snapshot.clone());
+        let app_version = app_version.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            write_zip(&path, &snapshot, &settings_file, now, app_version)
+        })
+        .await
+        .map_err(|e| format!("Pre-update backup failed: {e}"))
+    };
+    if let Err(e) = fs::remove_file(&snapshot) {
+        log::warn!("Failed to remove {}: {}", snapshot.display(), e);
+    }
+    written?
+}
+
+fn write_zip(
+    path: &Path,
+    snapshot: &Path,
+    settings_file: &Path,
+    now: SystemTime,
+    app_version: String,
+) -> Result<u64, String> {
+    let size = |file: &Path| fs::metadata(file).map(|meta| meta.len());
+    let database_bytes =
+        size(snapshot).map_err(|e| format!("Failed to read {}: {e}", snapshot.display()))?;
+    let settings_bytes = size(settings_file).ok();
+    let manifest = Manifest {
+        format: backup::FORMAT,
+        app_version,
+        created_ms: unix_millis(now),
+        files: 1 + usize::from(settings_bytes.is_some()),
+        bytes: database_bytes + settings_bytes.unwrap_or_default(),
+        replaces: Some(vec![DATABASE_DIR.to_string()]),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    support::write_atomically(path, now, true, |zip| {
+        zip.entry(backup::MANIFEST_FILE, |out| {
+            serde_json::to_writer_pretty(&mut *out, &manifest).map_err(io::Error::other)
+        })?;
+        zip.entry(backup::DATABASE_FILE, |out| {
+            io::copy(&mut File::open(snapshot)?, out).map(|_| ())
+        })?;
+        if settings_bytes.is_some() {
+            let name = format!("{}{}", backup::SHELL_PREFIX, settings::SETTINGS_FILE_NAME);
+            zip.entry(&name, |out| {
+                io::copy(&mut File::open(settings_file)?, out).map(|_| ())
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn read_records(dir: &Path) -> Vec<PreUpdateBackup> {
+    let path = dir.join(RECORD_FILE);
+    match fs::read(&path) {
+        Ok(json) => serde_json::from_slice(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable {}: {}", path.display(), e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Add `record`, delete the zips beyond the newest `kept` (0 keeps them all) and forget
+/// the records of deleted zips.
+fn save_record(dir: &Path, record: PreUpdateBackup, kept: u32) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let mut records = read_records(dir);
+    // Made again the same day for the same version: the zip was replaced.
+    records.retain(|old| old.path != record.path);
+    records.push(record);
+    records.sort_by_key(|record| record.created_ms);
+
+    let mut zips = 0;
+    for record in records.iter_mut().rev().filter(|record| record.success) {
+        zips += 1;
+        if kept == 0 || zips <= kept {
+            continue;
+        }
+        match fs::remove_file(&record.path) {
+            Ok(()) => log::info!("Removed old pre-update backup {}", record.path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                log::warn!("Failed to remove {}: {}", record.path.display(), e);
+                continue;
+            }
+        }
+        record.success = false;
+        record.error = Some("Removed to keep the newest ones".into());
+    }
+    // Successes whose zip is gone, e.g. deleted by hand, are no use to a restore.
+    records.retain(|record| !record.success || record.path.exists());
+    let records: Vec<_> = records.split_off(records.len().saturating_sub(MAX_RECORDS));
+    let json = serde_json::to_vec_pretty(&records).map_err(|e| e.to_string())?;
+    let path = dir.join(RECORD_FILE);
+    let temp = support::temp_path(&path)?;
+    fs::write(&temp, json)
+        .and_then(|()| fs::rename(&temp, &path))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// The recorded pre-update backups, newest first, for the restore screen; only those
+/// whose zip is still there count as `success`.
+pub(crate) async fn list(app: &AppHandle) -> Result<Vec<PreUpdateBackup>, String> {
+    let dir = backups_dir(&datadir::path(app)?);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut records = read_records(&dir);
+        for record in records.iter_mut().filter(|record| record.success) {
+            if !record.path.exists() {
+                record.success = false;
+                record.error = Some("The backup has been deleted".into());
+            }
+        }
+        records.reverse();
+        records
+    })
+    .await
+    .map_err(|e| format!("Listing the pre-update backups failed: {e}"))
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduling priority of the backend, so OCR doesn't starve the UI on small machines.
+
+use serde::{Deserialize, Serialize};
+
+/// How much CPU the backend may take from the rest of the system.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BackendPriority {
+    #[default]
+    Normal,
+    BelowNormal,
+    Low,
+}
+
+/// Why the priority could not be changed.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum PriorityError {
+    /// The OS refused, typically when raising priority back without privileges.
+    PermissionDenied {
+        message: String,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for PriorityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PermissionDenied { message } | Self::Failed { message } => f.write_str(message),
+        }
+    }
+}
+
+impl PriorityError {
+    fn from_os(pid: u32, priority: BackendPriority, e: std::io::Error) -> Self {
+        let message = format!("Failed to set backend (pid {pid}) priority to {priority:?}: {e}");
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            Self::PermissionDenied { message }
+        } else {
+            Self::Failed { message }
+        }
+    }
+}
+
+impl From<String> for PriorityError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+/// Set the priority of the running process `pid`.
+#[cfg(unix)]
+pub(crate) fn apply(pid: u32, priority: BackendPriority) -> Result<(), PriorityError> {
+    let nice = match priority {
+        BackendPriority::Normal => 0,
+        BackendPriority::BelowNormal => 10,
+        BackendPriority::Low => 19,
+    };
+    // SAFETY: setpriority(2) has no memory-safety preconditions.
+    let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as libc::id_t, nice) };
+    if rc != 0 {
+        return Err(PriorityError::from_os(
+            pid,
+            priority,
+            std::io::Error::last_os_error(),
+        ));
+    }
+    Ok(())
+}
+
+/// Set the priority class of the running process `pid`.
+#[cfg(windows)]
+pub(crate) fn apply(pid: u32, priority: BackendPriority) -> Result<(), PriorityError> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, IDLE_PRIORITY_CLASS,
+        NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+
+    let class = match priority {
+        BackendPriority::Normal => NORMAL_PRIORITY_CLASS,
+        BackendPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        BackendPriority::Low => IDLE_PRIORITY_CLASS,
+    };
+    // SAFETY: OpenProcess has no pointer arguments; a null result is handled below.
+    let process = unsafe { OpenProcess(PROCESS_SET_INFORMATION, 0, pid) };
+    if process.is_null() {
+        return Err(PriorityError::from_os(
+            pid,
+            priority,
+            std::io::Error::last_os_error(),
+        ));
+    }
+    // SAFETY: `process` is a valid handle opened with PROCESS_SET_INFORMATION.
+    let ok = unsafe { SetPriorityClass(process, class) };
+    let error = std::io::Error::last_os_error();
+    // SAFETY: we opened `process` above and close it exactly once.
+    unsafe { CloseHandle(process) };
+    if ok == 0 {
+        return Err(PriorityError::from_os(pid, priority, error));
+    }
+    Ok(())
+}
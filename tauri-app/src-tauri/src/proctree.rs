@@ -0,0 +1,72 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Processes forked by the backend, which keep the port and the DB open if they outlive it.
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Snapshot of a process's descendants, taken while it is still alive: once it exits its
+/// children are reparented and can no longer be found by walking the tree.
+pub(crate) struct Descendants(Vec<(Pid, u64)>);
+
+impl Descendants {
+    pub(crate) fn of(pid: u32) -> Self {
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+
+        let mut found = Vec::new();
+        let mut parents = vec![Pid::from_u32(pid)];
+        while let Some(parent) = parents.pop() {
+            for (pid, process) in system.processes() {
+                if process.parent() == Some(parent) {
+                    found.push((*pid, process.start_time()));
+                    parents.push(*pid);
+                }
+            }
+        }
+        Self(found)
+    }
+
+    pub(crate) fn pids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().map(|(pid, _)| pid.as_u32())
+    }
+
+    /// Kill the descendants that are still running. The start time is compared so a pid
+    /// reused since the snapshot is left alone. Returns how many were killed.
+    pub(crate) fn kill_remaining(&self) -> usize {
+        if self.0.is_empty() {
+            return 0;
+        }
+        let pids: Vec<Pid> = self.0.iter().map(|(pid, _)| *pid).collect();
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+
+        self.0
+            .iter()
+            .filter(|(pid, started)| {
+                system
+                    .process(*pid)
+                    .is_some_and(|p| p.start_time() == *started && p.kill())
+            })
+            .count()
+    }
+}
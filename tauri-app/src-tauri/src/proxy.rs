@@ -0,0 +1,498 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `backend_request`: the webview's way to call the backend without doing HTTP itself.
+//!
+//! Requests go through the current transport with the auth token attached, and can only
+//! reach paths on our backend.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::concurrency::{self, Lane};
+use crate::transport::{Body, Request};
+use crate::{cache, cancel, config, endpoint, invoices};
+
+/// Files are too big for JSON bodies; they go through the streaming upload.
+const MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
+/// Past this, read the response with the streaming download instead.
+const MAX_RESPONSE_BODY_BYTES: usize = 8 * 1024 * 1024;
+/// Retries of a GET or HEAD that hit a restarting backend: after 250ms, 500ms, 1s.
+const MAX_RETRIES: u32 = 3;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(250);
+/// No retry starts later than this after the first attempt, and retries get only what
+/// is left of it, so a flapping backend can't keep the UI waiting.
+const RETRY_DEADLINE: Duration = Duration::from_secs(8);
+
+/// Numbers the requests that came without a `request_id`, for the request span.
+static NEXT_REQUEST: AtomicU64 = AtomicU64::new(1);
+
+/// How long a backend call may take before the shell gives up on it. The limits live
+/// in `AppConfig::timeouts`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TimeoutClass {
+    /// Health checks and lookups that answer at once from a healthy backend.
+    Fast,
+    #[default]
+    Normal,
+    /// Work that takes minutes, such as re-processing an invoice. Cancel these with
+    /// `cancel_backend_request` rather than waiting for the limit.
+    LongRunning,
+    /// Downloads and event streams; stalls are caught per chunk as well.
+    Streaming,
+}
+
+impl TimeoutClass {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Normal => "normal",
+            Self::LongRunning => "long_running",
+            Self::Streaming => "streaming",
+        }
+    }
+}
+
+/// Argument of `backend_request`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProxyRequest {
+    method: String,
+    /// Path on the backend, e.g. "/api/v1/invoices"; the query goes in `query`.
+    path: String,
+    #[serde(default)]
+    query: BTreeMap<String, String>,
+    #[serde(default)]
+    json_body: Option<serde_json::Value>,
+    #[serde(default)]
+    timeout_class: TimeoutClass,
+    /// Shortens the class's limit for this call; it can't lengthen it.
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    /// Lets `cancel_backend_request` abort the call.
+    #[serde(default)]
+    request_id: Option<String>,
+    #[serde(default)]
+    response_type: ResponseType,
+}
+
+/// How `ProxyResponse::body` carries the response body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ResponseType {
+    /// Parsed JSON for JSON responses, otherwise the body as text.
+    #[default]
+    Auto,
+    /// A base64 string, for binary endpoints such as PDF previews.
+    Base64,
+    /// An array of byte values, for `new Uint8Array(body)`.
+    Bytes,
+}
+
+/// What `backend_request` returns, whatever the status.
+#[derive(Debug, Serialize)]
+pub(crate) struct ProxyResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    /// Decompressed, in the form `ProxyRequest::response_type` asked for.
+    body: serde_json::Value,
+    /// The status is one repeating the request may fix (502, 503).
+    retryable: bool,
+    /// Served from the response cache without asking the backend.
+    cached: bool,
+}
+
+/// Why `backend_request` got no response.
+#[derive(Debug, Serialize)]
+pub(crate) struct ProxyError {
+    message: String,
+    /// Sending the same request again may work; GET and HEAD were already retried.
+    retryable: bool,
+    /// Aborted by `cancel_backend_request`.
+    cancelled: bool,
+}
+
+impl ProxyError {
+    pub(crate) fn cancelled(message: String) -> Self {
+        Self {
+            cancelled: true,
+            ..Self::from(message)
+        }
+    }
+}
+
+impl From<String> for ProxyError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+            cancelled: false,
+        }
+    }
+}
+
+/// Send `request` to the current backend.
+///
+/// Errors mean no response arrived; HTTP error statuses are returned as responses.
+#[tracing::instrument(
+    name = "backend_request",
+    skip_all,
+    fields(
+        request_id = %span_id(&request),
+        method = %request.method,
+        path = %request.path,
+        status,
+        elapsed_ms,
+    )
+)]
+pub(crate) async fn send(
+    app: &AppHandle,
+    request: ProxyRequest,
+) -> Result<ProxyResponse, ProxyError> {
+    let method = parse_method(&request.method)?;
+    validate_path(&request.path)?;
+    let path = with_query(&request.path, &request.query)?;
+    let json_body = request
+        .json_body
+        .as_ref()
+        .map(serde_json::to_vec)
+        .transpose()
+        .map_err(|e| format!("Invalid JSON body: {e}"))?;
+    if json_body
+        .as_ref()
+        .is_some_and(|body| body.len() > MAX_REQUEST_BODY_BYTES)
+    {
+        return Err(format!(
+            "Request body is larger than {MAX_REQUEST_BODY_BYTES} bytes; \
+             use the streaming upload command for files"
+        )
+        .into());
+    }
+    let class_limit = config::request_timeout(app, request.timeout_class);
+    let timeout = request
+        .timeout_ms
+        .map(Duration::from_millis)
+        .map_or(class_limit, |ms| ms.min(class_limit));
+
+    let idempotent = matches!(method, reqwest::Method::GET | reqwest::Method::HEAD);
+    let started = Instant::now();
+    let mut attempt = 0;
+    let attempts = async {
+        loop {
+            // Looked up on every attempt: a restart may have moved the backend.
+            let transport = endpoint::transport(app);
+            let budget = if attempt == 0 {
+                timeout
+            } else {
+                timeout.min(RETRY_DEADLINE.saturating_sub(started.elapsed()))
+            };
+            let result = {
+                let _permit = concurrency::acquire(app, Lane::Requests).await;
+                transport
+                    .send(Request {
+                        method: method.clone(),
+                        path: &path,
+                        body: json_body.clone().map(Body::json),
+                        timeout: budget,
+                        max_response_bytes: Some(MAX_RESPONSE_BODY_BYTES),
+                    })
+                    .await
+            };
+            let retryable = match &result {
+                Ok(resp) => is_retryable_status(resp.status),
+                Err(e) => e.connection_failed,
+            };
+            let delay = INITIAL_RETRY_DELAY * 2u32.pow(attempt);
+            let in_time = started.elapsed() + delay < RETRY_DEADLINE;
+            if !(retryable && idempotent && attempt < MAX_RETRIES && in_time) {
+                return result.map_err(|e| ProxyError {
+                    message: if e.timed_out {
+                        format!(
+                            "{} {} timed out after {} ({} timeout)",
+                            request.method,
+                            request.path,
+                            describe(budget),
+                            request.timeout_class.name()
+                        )
+                    } else if e.too_large {
+                        format!(
+                            "{} {}: {e}; use the streaming download command for large responses",
+                            request.method, request.path
+                        )
+                    } else {
+                        format!("{} {} failed: {e}", request.method, request.path)
+                    },
+                    retryable,
+                    cancelled: false,
+                });
+            }
+            attempt += 1;
+            tracing::debug!(
+                attempt,
+                max_retries = MAX_RETRIES,
+                delay_ms = delay.as_millis() as u64,
+                "{} {} failed transiently, retrying",
+                request.method,
+                request.path
+            );
+            tokio::time::sleep(delay).await;
+        }
+    };
+    let is_get = method == reqwest::Method::GET;
+    let cached = if is_get {
+        cache::lookup(app, &path)
+    } else {
+        None
+    };
+    let from_cache = cached.is_some();
+    let resp = match cached {
+        Some(resp) => resp,
+        None => match cancel::run(app, request.request_id.as_deref(), attempts).await? {
+            Some(result) => result?,
+            None => {
+                return Err(ProxyError::cancelled(format!(
+                    "{} {} was cancelled",
+                    request.method, request.path
+                )))
+            }
+        },
+    };
+
+    if is_get && !from_cache {
+        cache::store(app, &path, &resp);
+    }
+    if !idempotent && resp.is_success() {
+        cache::invalidate_after(app, &request.path);
+        invoices::invalidate(app);
+    }
+
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in &resp.headers {
+        headers
+            .entry(name.clone())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(value);
+            })
+            .or_insert_with(|| value.clone());
+    }
+    if resp.wire_bytes != resp.body.len() {
+        log::debug!(
+            "{} {}: {} bytes on the wire, {} decoded",
+            request.method,
+            request.path,
+            resp.wire_bytes,
+            resp.body.len()
+        );
+    }
+    let is_json = headers
+        .get("content-type")
+        .is_some_and(|ct| ct.contains("json"));
+    let body = match request.response_type {
+        ResponseType::Base64 => serde_json::Value::String(BASE64_STANDARD.encode(&resp.body)),
+        ResponseType::Bytes => serde_json::Value::from(resp.body.as_slice()),
+        ResponseType::Auto => match resp.json::<serde_json::Value>() {
+            Ok(value) if is_json => value,
+            _ if resp.body.is_empty() => serde_json::Value::Null,
+            _ => serde_json::Value::String(resp.text()),
+        },
+    };
+    let span = tracing::Span::current();
+    span.record("status", resp.status);
+    span.record("elapsed_ms", started.elapsed().as_millis() as u64);
+    Ok(ProxyResponse {
+        status: resp.status,
+        headers,
+        body,
+        retryable: is_retryable_status(resp.status),
+        cached: from_cache,
+    })
+}
+
+/// The caller's `request_id`, or a shell-made one for calls that can't be cancelled.
+fn span_id(request: &ProxyRequest) -> String {
+    match &request.request_id {
+        Some(id) => id.clone(),
+        None => format!("shell-{}", NEXT_REQUEST.fetch_add(1, Ordering::Relaxed)),
+    }
+}
+
+/// "90s", or "1500ms" below a second.
+pub(crate) fn describe(timeout: Duration) -> String {
+    if timeout < Duration::from_secs(1) {
+        format!("{}ms", timeout.as_millis())
+    } else {
+        format!("{}s", timeout.as_secs())
+    }
+}
+
+/// Bad gateway and service unavailable: the backend is restarting or overloaded.
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 502 | 503)
+}
+
+fn parse_method(method: &str) -> Result<reqwest::Method, String> {
+    match method.to_ascii_uppercase().as_str() {
+        "GET" => Ok(reqwest::Method::GET),
+        "HEAD" => Ok(reqwest::Method::HEAD),
+        "POST" => Ok(reqwest::Method::POST),
+        "PUT" => Ok(reqwest::Method::PUT),
+        "PATCH" => Ok(reqwest::Method::PATCH),
+        "DELETE" => Ok(reqwest::Method::DELETE),
+        _ => Err(format!("Unsupported method {method:?}")),
+    }
+}
+
+/// Only plain paths on our backend: no URLs, no way out of the path with `..`.
+pub(crate) fn validate_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') || path.starts_with("//") || path.contains("://") {
+        return Err(format!(
+            "Path {path:?} must be a path on the backend, not a URL"
+        ));
+    }
+    // Checked as the backend will see it, so escapes can't sneak these past.
+    let decoded = percent_decoded(path);
+    if decoded.contains("..") {
+        return Err(format!("Path {path:?} must not contain \"..\""));
+    }
+    if path.contains(['?', '#']) || decoded.contains('\\') || decoded.chars().any(char::is_control)
+    {
+        return Err(format!(
+            "Path {path:?} contains characters that are not allowed; pass the query separately"
+        ));
+    }
+    Ok(())
+}
+
+/// `path` with its `%XX` escapes decoded.
+fn percent_decoded(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// `path` with `query` appended, percent-encoded.
+pub(crate) fn with_query(path: &str, query: &BTreeMap<String, String>) -> Result<String, String> {
+    if query.is_empty() {
+        return Ok(path.to_string());
+    }
+    let mut url = reqwest::Url::parse(&format!("http://localhost{path}"))
+        .map_err(|e| format!("Invalid path {path:?}: {e}"))?;
+    url.query_pairs_mut().extend_pairs(query);
+    Ok(format!(
+        "{}?{}",
+        url.path(),
+        url.query().unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_paths_are_allowed() {
+        for path in [
+            "/api/v1/invoices",
+            "/api/v1/invoices/42/document",
+            "/api/v1/invoices/facture%20été.pdf",
+            "/api/v1/files/a.b.c",
+            "/",
+        ] {
+            assert_eq!(validate_path(path), Ok(()), "{path}");
+        }
+    }
+
+    #[test]
+    fn dot_dot_is_refused_raw_and_encoded() {
+        for path in [
+            "/api/v1/../admin",
+            "/api/v1/..",
+            "/api/v1/%2e%2e/admin",
+            "/api/v1/%2E%2E/admin",
+            "/api/v1/.%2e/admin",
+            "/api/v1/%2e./admin",
+        ] {
+            let error = validate_path(path).unwrap_err();
+            assert!(error.contains("\"..\""), "{path}: {error}");
+        }
+    }
+
+    #[test]
+    fn urls_and_hosts_are_refused() {
+        for path in [
+            "//evil.example/api",
+            "http://evil.example/api",
+            "https://evil.example/api",
+            "/redirect?to=http://evil.example",
+            "api/v1/invoices",
+            "",
+        ] {
+            let error = validate_path(path).unwrap_err();
+            assert!(error.contains("not a URL"), "{path}: {error}");
+        }
+    }
+
+    #[test]
+    fn backslashes_control_characters_and_queries_are_refused() {
+        for path in [
+            "/api/v1\\..\\admin",
+            "/api/v1/a\\b",
+            "/api/v1/a%5cb",
+            "/api/v1/a\nb",
+            "/api/v1/a\0b",
+            "/api/v1/a%0d%0aX-Injected: 1",
+            "/api/v1/a%00",
+            "/api/v1/a\u{7f}",
+            "/api/v1/invoices?limit=5",
+            "/api/v1/invoices#top",
+        ] {
+            let error = validate_path(path).unwrap_err();
+            assert!(
+                error.contains("not allowed") || error.contains("\"..\""),
+                "{path}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn percent_decoding_leaves_bad_escapes_alone() {
+        assert_eq!(percent_decoded("/a%20b"), "/a b");
+        assert_eq!(percent_decoded("/100%"), "/100%");
+        assert_eq!(percent_decoded("/%zz%4"), "/%zz%4");
+        assert_eq!(percent_decoded("/%+f"), "/%+f");
+        assert_eq!(percent_decoded("/caf%C3%A9"), "/café");
+    }
+}
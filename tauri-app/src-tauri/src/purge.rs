@@ -0,0 +1,302 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `purge_cache`: derived data that is rebuilt when needed, deleted on request or on exit.
+//!
+//! Each kind of cache on disk has one root, and only files below it are deleted; links are
+//! deleted rather than followed, and a root that is itself a link is refused. Files that
+//! may be in use are left: page renders a job under review may still show, temp files a
+//! job may still be writing, files the OS won't let go of. The backend's own cache is
+//! cleared through its `/cleanup` endpoint.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::proxy::TimeoutClass;
+use crate::{backup, cache, config, datadir, endpoint, settings, storage, tempdir};
+
+const CLEANUP_PATH: &str = "/api/v1/cleanup";
+/// The backend expires an analysed job an hour after analysis; until then its page renders
+/// may be on the review screen.
+const REVIEW_WINDOW: Duration = Duration::from_secs(3600);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CacheKind {
+    /// Page renders the backend keeps in its `temp` folder for the review screen.
+    Thumbnails,
+    /// The temp dir given to the backend for OCR intermediates.
+    Temp,
+    /// Cached GET responses, in memory.
+    HttpCache,
+    /// Files of expired jobs, cleared by the backend itself.
+    BackendCache,
+}
+
+/// What was purged of one kind.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct PurgedCache {
+    pub kind: CacheKind,
+    /// `None` when it can't be measured, as for an external backend's files.
+    pub freed_bytes: Option<u64>,
+    /// Files, or cached responses, removed.
+    pub removed: u64,
+    /// Files left because they may be in use.
+    pub skipped: u64,
+    /// Why this kind was not purged, or not completely; the other kinds are unaffected.
+    pub error: Option<String>,
+}
+
+/// Payload of `purge_cache` and the `cache-purged` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct CachePurge {
+    pub caches: Vec<PurgedCache>,
+    /// Sum of the bytes measured.
+    pub freed_bytes: u64,
+}
+
+/// The backend's answer from `/cleanup`.
+#[derive(Deserialize)]
+struct CleanupBody {
+    files_deleted: u64,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+#[derive(Default)]
+struct Tally {
+    removed: u64,
+    bytes: u64,
+    skipped: u64,
+}
+
+impl PurgedCache {
+    fn new(kind: CacheKind) -> Self {
+        Self {
+            kind,
+            freed_bytes: None,
+            removed: 0,
+            skipped: 0,
+            error: None,
+        }
+    }
+
+    fn from_files(kind: CacheKind, tally: Result<Tally, String>) -> Self {
+        let mut purged = Self::new(kind);
+        match tally {
+            Ok(tally) => {
+                purged.freed_bytes = Some(tally.bytes);
+                purged.removed = tally.removed;
+                purged.skipped = tally.skipped;
+            }
+            Err(e) => purged.error = Some(e),
+        }
+        purged
+    }
+}
+
+/// The backend's `temp` folder, if the app manages the backend's data.
+fn backend_temp(app: &AppHandle) -> Result<PathBuf, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    Ok(datadir::path(app)?.join(backup::BACKEND_TEMP))
+}
+
+/// `{job_id}_page_{n}.png` and `{job_id}_preprocessed.png`, as the backend names them.
+pub(crate) fn is_render(name: &OsStr) -> bool {
+    let name = name.to_string_lossy();
+    name.ends_with("_preprocessed.png")
+        || name
+            .strip_suffix(".png")
+            .is_some_and(|stem| stem.contains("_page_"))
+}
+
+/// Purge the caches of `kinds` and emit `cache-purged`.
+pub(crate) async fn purge(app: &AppHandle, kinds: &[CacheKind]) -> Result<CachePurge, String> {
+    let mut caches: Vec<PurgedCache> = Vec::with_capacity(kinds.len());
+    for &kind in kinds {
+        if caches.iter().any(|cache| cache.kind == kind) {
+            continue;
+        }
+        caches.push(match kind {
+            CacheKind::Thumbnails | CacheKind::Temp => {
+                let handle = app.clone();
+                tauri::async_runtime::spawn_blocking(move || purge_files(&handle, kind))
+                    .await
+                    .map_err(|e| format!("Purging the cache failed: {e}"))?
+            }
+            CacheKind::HttpCache => purge_responses(app),
+            CacheKind::BackendCache => purge_backend(app).await,
+        });
+    }
+    let purge = CachePurge {
+        freed_bytes: caches.iter().filter_map(|cache| cache.freed_bytes).sum(),
+        caches,
+    };
+    log::info!("Purged caches: {:?}", purge);
+    storage::forget(app);
+    let _ = app.emit("cache-purged", &purge);
+    Ok(purge)
+}
+
+/// Purge what is on disk when the app exits, if `clear_caches_on_exit` is on. Call once
+/// the backend has stopped.
+pub(crate) fn purge_on_exit(app: &AppHandle) {
+    if !settings::current(app).clear_caches_on_exit {
+        return;
+    }
+    for kind in [CacheKind::Thumbnails, CacheKind::Temp] {
+        let purged = purge_files(app, kind);
+        match purged.error {
+            Some(e) => log::warn!("Not purging {:?} on exit: {}", kind, e),
+            None => log::info!(
+                "Purged {:?} on exit: {} file(s), {} bytes; {} in use",
+                kind,
+                purged.removed,
+                purged.freed_bytes.unwrap_or_default(),
+                purged.skipped
+            ),
+        }
+    }
+}
+
+fn purge_files(app: &AppHandle, kind: CacheKind) -> PurgedCache {
+    let tally = match kind {
+        CacheKind::Thumbnails => {
+            backend_temp(app).and_then(|root| purge_dir(&root, REVIEW_WINDOW, &is_render))
+        }
+        _ => tempdir::path(app)
+            .and_then(|root| purge_dir(&root, tempdir::ACTIVE_FILE_AGE, &|_| true)),
+    };
+    PurgedCache::from_files(kind, tally)
+}
+
+fn purge_responses(app: &AppHandle) -> PurgedCache {
+    let before = cache::stats(app).bytes;
+    let removed = cache::invalidate(app, "/");
+    let after = cache::stats(app).bytes;
+    PurgedCache {
+        freed_bytes: Some(before.saturating_sub(after) as u64),
+        removed: removed as u64,
+        ..PurgedCache::new(CacheKind::HttpCache)
+    }
+}
+
+async fn purge_backend(app: &AppHandle) -> PurgedCache {
+    let mut purged = PurgedCache::new(CacheKind::BackendCache);
+    // Only measurable when its temp folder is ours to look at.
+    let temp = backend_temp(app).ok();
+    let usage = |temp: Option<PathBuf>| async move {
+        let temp = temp?;
+        tauri::async_runtime::spawn_blocking(move || tempdir::usage(&temp))
+            .await
+            .ok()
+    };
+    let before = usage(temp.clone()).await;
+
+    let timeout = config::request_timeout(app, TimeoutClass::LongRunning);
+    let body = match endpoint::transport(app).post(CLEANUP_PATH, timeout).await {
+        Ok(resp) if resp.is_success() => resp.json::<CleanupBody>(),
+        Ok(resp) => Err(format!("HTTP {} {}", resp.status, resp.text())),
+        Err(e) => Err(e.to_string()),
+    };
+    match body {
+        Ok(body) => {
+            purged.removed = body.files_deleted;
+            if !body.errors.is_empty() {
+                purged.error = Some(format!(
+                    "The backend could not delete {} file(s): {}",
+                    body.errors.len(),
+                    body.errors.join("; ")
+                ));
+            }
+        }
+        Err(e) => {
+            purged.error = Some(format!("The backend could not clear its cache: {e}"));
+            return purged;
+        }
+    }
+    if let (Some(before), Some(after)) = (before, usage(temp).await) {
+        purged.freed_bytes = Some(before.saturating_sub(after));
+    }
+    purged
+}
+
+/// Delete the files below `root` that `purgeable` accepts and that weren't modified in the
+/// last `in_use`, then the directories left empty.
+fn purge_dir(
+    root: &Path,
+    in_use: Duration,
+    purgeable: &dyn Fn(&OsStr) -> bool,
+) -> Result<Tally, String> {
+    match fs::symlink_metadata(root) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            return Err(format!("{} is a link; not purging it", root.display()));
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Tally::default()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", root.display())),
+    }
+    let mut tally = Tally::default();
+    purge_below(root, SystemTime::now(), in_use, purgeable, &mut tally);
+    Ok(tally)
+}
+
+fn purge_below(
+    dir: &Path,
+    now: SystemTime,
+    in_use: Duration,
+    purgeable: &dyn Fn(&OsStr) -> bool,
+    tally: &mut Tally,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // symlink_metadata: a link is a file here, so nothing outside the root is reached.
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            purge_below(&path, now, in_use, purgeable, tally);
+            // Only succeeds once the directory is empty.
+            let _ = fs::remove_dir(&path);
+            continue;
+        }
+        if !purgeable(&entry.file_name()) {
+            continue;
+        }
+        let age = meta
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        // Windows refuses to delete a file that is open.
+        if age < in_use || fs::remove_file(&path).is_err() {
+            tally.skipped += 1;
+            continue;
+        }
+        tally.removed += 1;
+        tally.bytes += meta.len();
+    }
+}
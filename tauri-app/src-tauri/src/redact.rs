@@ -0,0 +1,231 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Redaction of invoice data in captured backend output.
+//!
+//! The backend logs extracted fields at debug level (vendor names, amounts, IBANs), and
+//! its output now reaches the log file, the console panel and support bundles. Every
+//! stdout/stderr line goes through `Redactor::apply` before any of those see it.
+//! Turning it off takes the `debug_disable_log_redaction` setting, which has no command.
+
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+use regex::Regex;
+use tauri::{AppHandle, Manager};
+
+use crate::settings::Settings;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Field names whose values are redacted wherever they appear as `"name": value`,
+/// `'name': value` or `name=value`.
+const FIELDS: &[&str] = &[
+    "vendor",
+    "vendor_name",
+    "supplier",
+    "customer",
+    "client",
+    "buyer",
+    "seller",
+    "amount",
+    "total",
+    "total_amount",
+    "subtotal",
+    "tax_amount",
+    "vat_amount",
+    "iban",
+    "bic",
+    "vat_number",
+    "tax_id",
+    "siret",
+    "address",
+    "email",
+    "phone",
+    "account_number",
+];
+/// IBAN: country code, check digits, then 11 to 30 letters and digits, optionally
+/// grouped by four.
+const IBAN: &str = r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b";
+const EMAIL: &str = r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b";
+
+/// The rules in force, managed by Tauri; `None` when redaction is switched off.
+pub(crate) struct Redactor(RwLock<Option<Rules>>);
+
+impl Default for Redactor {
+    /// On, with the built-in rules, until `configure` reads the settings.
+    fn default() -> Self {
+        Self(RwLock::new(Some(Rules::new(&[], &[]))))
+    }
+}
+
+struct Rules {
+    /// Quoted keys in JSON or Python reprs: the key and separator stay, the value goes.
+    quoted_fields: Regex,
+    /// structlog's `key=value`.
+    bare_fields: Regex,
+    patterns: Vec<Regex>,
+}
+
+impl Rules {
+    /// The built-in rules plus the extra field names and patterns from the settings.
+    /// Patterns that don't compile are logged and skipped.
+    fn new(extra_fields: &[String], extra_patterns: &[String]) -> Self {
+        let names: Vec<String> = FIELDS
+            .iter()
+            .map(|name| regex::escape(name))
+            .chain(extra_fields.iter().map(|name| regex::escape(name.trim())))
+            .filter(|name| !name.is_empty())
+            .collect();
+        let names = names.join("|");
+        let quoted_fields = Regex::new(&format!(
+            r#"(?i)(["'](?:{names})["']\s*:\s*)("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|[^,{{}}\[\]\s]+)"#
+        ))
+        .expect("built-in field rule compiles");
+        let bare_fields = Regex::new(&format!(
+            r#"(?i)\b((?:{names})=)("(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|\S+)"#
+        ))
+        .expect("built-in field rule compiles");
+
+        let mut patterns = vec![
+            Regex::new(IBAN).expect("built-in IBAN rule compiles"),
+            Regex::new(EMAIL).expect("built-in email rule compiles"),
+        ];
+        for pattern in extra_patterns {
+            match Regex::new(pattern) {
+                Ok(regex) => patterns.push(regex),
+                Err(e) => log::warn!("Ignoring log redaction pattern {:?}: {}", pattern, e),
+            }
+        }
+        Self {
+            quoted_fields,
+            bare_fields,
+            patterns,
+        }
+    }
+
+    fn apply<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let quoted = format!("${{1}}\"{REDACTED}\"");
+        let bare = format!("${{1}}{REDACTED}");
+        let mut out = Cow::Borrowed(line);
+        out = replace(out, &self.quoted_fields, &quoted);
+        out = replace(out, &self.bare_fields, &bare);
+        for pattern in &self.patterns {
+            out = replace(out, pattern, REDACTED);
+        }
+        out
+    }
+}
+
+/// `text` with every match of `regex` replaced, copied only if something matched.
+fn replace<'a>(text: Cow<'a, str>, regex: &Regex, replacement: &str) -> Cow<'a, str> {
+    if regex.is_match(&text) {
+        Cow::Owned(regex.replace_all(&text, replacement).into_owned())
+    } else {
+        text
+    }
+}
+
+/// Build the rules from `settings`, or switch redaction off if the debug setting says so.
+pub(crate) fn configure(app: &AppHandle, settings: &Settings) {
+    let rules = if settings.debug_disable_log_redaction {
+        log::warn!("Log redaction is off: backend output may contain invoice data");
+        None
+    } else {
+        Some(Rules::new(
+            &settings.log_redaction_fields,
+            &settings.log_redaction_patterns,
+        ))
+    };
+    *app.state::<Redactor>().0.write().unwrap() = rules;
+}
+
+/// `line` with invoice data replaced by `[REDACTED]`.
+pub(crate) fn apply<'a>(app: &AppHandle, line: &'a str) -> Cow<'a, str> {
+    match app.state::<Redactor>().0.read().unwrap().as_ref() {
+        Some(rules) => rules.apply(line),
+        None => Cow::Borrowed(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact(line: &str) -> String {
+        Rules::new(&[], &[]).apply(line).into_owned()
+    }
+
+    #[test]
+    fn ibans_are_redacted_grouped_or_not() {
+        assert_eq!(
+            redact("Paying DE89370400440532013000 now"),
+            "Paying [REDACTED] now"
+        );
+        assert_eq!(
+            redact("IBAN DE89 3704 0044 0532 0130 00."),
+            "IBAN [REDACTED]."
+        );
+    }
+
+    #[test]
+    fn emails_are_redacted() {
+        assert_eq!(
+            redact("Invoice sent to jane.doe@example.co.uk today"),
+            "Invoice sent to [REDACTED] today"
+        );
+    }
+
+    #[test]
+    fn json_fields_keep_their_key() {
+        assert_eq!(
+            redact(r#"{"vendor": "ACME \"Paris\" GmbH", "total": 118.5, "pages": 2}"#),
+            r#"{"vendor": "[REDACTED]", "total": "[REDACTED]", "pages": 2}"#
+        );
+    }
+
+    #[test]
+    fn python_reprs_are_redacted() {
+        assert_eq!(
+            redact("fields={'Vendor': 'ACME GmbH', 'amount': 118.5, 'currency': 'EUR'}"),
+            r#"fields={'Vendor': "[REDACTED]", 'amount': "[REDACTED]", 'currency': 'EUR'}"#
+        );
+    }
+
+    #[test]
+    fn structlog_pairs_are_redacted() {
+        assert_eq!(
+            redact(r#"event=extracted vendor="ACME GmbH" total=118.50 pages=2"#),
+            "event=extracted vendor=[REDACTED] total=[REDACTED] pages=2"
+        );
+    }
+
+    #[test]
+    fn lines_without_invoice_data_are_not_copied() {
+        let rules = Rules::new(&[], &[]);
+        let line = "INFO: Processed 3 pages in 1.2s (pid 4242, port 8000)";
+        assert!(matches!(rules.apply(line), Cow::Borrowed(l) if l == line));
+    }
+
+    #[test]
+    fn extra_fields_and_patterns_apply_and_bad_patterns_are_skipped() {
+        let rules = Rules::new(
+            &[" reference ".to_string()],
+            &[r"INV-\d+".to_string(), "(".to_string()],
+        );
+        assert_eq!(
+            rules.apply("reference=abc for INV-2024"),
+            "reference=[REDACTED] for [REDACTED]"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `factory_reset`: delete the app's data and settings, for a clean slate.
+//!
+//! It takes a token from `request_factory_reset`, used once and only for `TOKEN_TTL`, so a
+//! stray invoke can't wipe anything. The backend is stopped, whatever jobs it was running,
+//! then the contents of `DATA_DIR`, the settings file, the restore marker and the `.bak`
+//! folder of the last restore are deleted. Downloaded models, the shell's temp dir and the
+//! logs go too if asked. Then a fresh backend is started, or the app relaunched.
+//!
+//! Each step is appended to `AUDIT_FILE_NAME` in the OS temp dir, outside everything the
+//! reset deletes, so what was removed can be told afterwards.
+
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::supervisor::{self, Startup, Supervisor};
+use crate::{
+    auth, cache, datadir, datalock, endpoint, pidfile, restore, settings, storage, support, tempdir,
+};
+
+pub(crate) const AUDIT_FILE_NAME: &str = "invoice-processor-factory-reset.log";
+const TOKEN_BYTES: usize = 16;
+const TOKEN_TTL: Duration = Duration::from_secs(120);
+/// Time for the command's answer to reach the frontend before the app goes away.
+const RELAUNCH_DELAY: Duration = Duration::from_millis(500);
+/// Top-level entries of `DATA_DIR`, after the backend's `config.py`.
+const LOG_DIR: &str = "logs";
+const MODELS_DIR: &str = "models";
+
+/// The last token `request_factory_reset` issued, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct ResetToken(Mutex<Option<(String, Instant)>>);
+
+/// Payload of `request_factory_reset`.
+#[derive(Debug, Serialize)]
+pub(crate) struct ResetRequest {
+    pub token: String,
+    pub expires_in_secs: u64,
+    /// The folder whose contents would be deleted.
+    pub data_dir: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AfterReset {
+    /// Start a backend on the empty data dir.
+    #[default]
+    Respawn,
+    /// Relaunch the app, so settings read at launch are back to their defaults too.
+    Relaunch,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ResetOptions {
+    /// Also delete the downloaded OCR models and the shell's temp dir.
+    pub clear_caches: bool,
+    /// Also delete the shell's and the backend's logs.
+    pub clear_logs: bool,
+    pub after: AfterReset,
+}
+
+/// What `factory_reset` did.
+#[derive(Debug, Serialize)]
+pub(crate) struct FactoryReset {
+    /// Files and folders deleted, counting a folder once.
+    pub removed: usize,
+    /// What could not be deleted, and why; the rest was.
+    pub failed: Vec<String>,
+    pub audit_log: PathBuf,
+    /// Whether the fresh backend became ready; `None` when relaunching.
+    pub backend_ready: Option<bool>,
+}
+
+/// Appends timestamped steps to the audit file, and to the log while it lasts.
+struct Audit {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl Audit {
+    fn open() -> Self {
+        let path = std::env::temp_dir().join(AUDIT_FILE_NAME);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| log::warn!("Failed to open {}: {}", path.display(), e))
+            .ok();
+        Self { path, file }
+    }
+
+    fn write(&mut self, message: &str) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let line = format!(
+            "{} [pid {}] {}\n",
+            support::utc_timestamp(SystemTime::now()),
+            std::process::id(),
+            message
+        );
+        // Synced line by line: the app may not live to the end of the reset.
+        if let Err(e) = file
+            .write_all(line.as_bytes())
+            .and_then(|()| file.sync_data())
+        {
+            log::warn!("Failed to write {}: {}", self.path.display(), e);
+            self.file = None;
+        }
+    }
+
+    fn step(&mut self, message: impl AsRef<str>) {
+        log::info!("Factory reset: {}", message.as_ref());
+        self.write(message.as_ref());
+    }
+
+    fn failed(&mut self, message: impl AsRef<str>) {
+        log::warn!("Factory reset: {}", message.as_ref());
+        self.write(&format!("FAILED {}", message.as_ref()));
+    }
+}
+
+/// Where the reset deletes things.
+struct Paths {
+    data_dir: PathBuf,
+    /// The default data dir, which holds the settings whether or not `DATA_DIR` moved.
+    app_dir: PathBuf,
+    log_dir: Option<PathBuf>,
+    restore_backup: Option<PathBuf>,
+}
+
+fn check_managed(app: &AppHandle) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    Ok(())
+}
+
+/// Issue the token `factory_reset` asks for, replacing any earlier one.
+pub(crate) fn request(app: &AppHandle) -> Result<ResetRequest, String> {
+    check_managed(app)?;
+    let data_dir = datadir::path(app)?;
+    let token = auth::random_hex(TOKEN_BYTES)?;
+    *app.state::<ResetToken>().0.lock().unwrap() = Some((token.clone(), Instant::now()));
+    log::info!("Factory reset of {} requested", data_dir.display());
+    Ok(ResetRequest {
+        token,
+        expires_in_secs: TOKEN_TTL.as_secs(),
+        data_dir,
+    })
+}
+
+/// Use up the issued token; a wrong one uses it up too.
+fn take_token(app: &AppHandle, token: &str) -> Result<(), String> {
+    match app.state::<ResetToken>().0.lock().unwrap().take() {
+        Some((issued, at)) if issued == token && at.elapsed() < TOKEN_TTL => Ok(()),
+        Some((issued, _)) if issued == token => {
+            Err("The confirmation has expired; request the reset again".into())
+        }
+        _ => Err("The confirmation does not match; request the reset again".into()),
+    }
+}
+
+/// Delete the app's data and settings, then start a fresh backend or relaunch.
+pub(crate) async fn reset(
+    app: &AppHandle,
+    token: &str,
+    options: ResetOptions,
+) -> Result<FactoryReset, String> {
+    check_managed(app)?;
+    take_token(app, token)?;
+    if datalock::held_elsewhere(app) {
+        return Err("Another instance of the app is using the data; close it first".into());
+    }
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    let data_dir = datadir::path(app)?;
+    let paths = Paths {
+        restore_backup: restore::backup_dir(&data_dir).ok(),
+        log_dir: app.path().app_log_dir().ok(),
+        data_dir,
+        app_dir,
+    };
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let mut audit = Audit::open();
+    audit.step(format!(
+        "Factory reset of {} by version {}, {:?}",
+        paths.data_dir.display(),
+        app.package_info().version,
+        options
+    ));
+    audit.step("Stopping the backend");
+    supervisor::stop_current(app).await;
+    pidfile::remove(app);
+
+    let (mut audit, options, removed, failed) = tauri::async_runtime::spawn_blocking(move || {
+        let (removed, failed) = wipe(&paths, &options, &mut audit);
+        (audit, options, removed, failed)
+    })
+    .await
+    .map_err(|e| format!("Factory reset failed: {e}"))?;
+    settings::reset(app);
+    cache::invalidate(app, "/");
+    storage::forget(app);
+    audit.step(format!(
+        "Deleted {removed} item(s), {} failure(s); settings back to defaults",
+        failed.len()
+    ));
+
+    let backend_ready = match options.after {
+        AfterReset::Respawn => {
+            audit.step("Starting a fresh backend");
+            let started = supervisor::launch_and_wait(app, &mut stop).await;
+            match &started {
+                Ok(startup) => audit.step(format!("Backend start: {startup:?}")),
+                Err(e) => audit.failed(format!("Backend start: {e}")),
+            }
+            Some(matches!(started, Ok(Startup::Ready)))
+        }
+        AfterReset::Relaunch => {
+            audit.step("Relaunching the app");
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(RELAUNCH_DELAY).await;
+                handle.request_restart();
+            });
+            None
+        }
+    };
+    Ok(FactoryReset {
+        removed,
+        failed,
+        audit_log: audit.path,
+        backend_ready,
+    })
+}
+
+/// Why the top-level entry `name` of `DATA_DIR` is kept, if it is.
+fn kept(paths: &Paths, options: &ResetOptions, name: &OsStr, path: &Path) -> Option<&'static str> {
+    if name == datadir::POINTER_FILE_NAME {
+        return Some("where the data dir is");
+    }
+    let holds_logs = paths
+        .log_dir
+        .as_ref()
+        .is_some_and(|dir| dir.starts_with(path));
+    if !options.clear_logs && (name == LOG_DIR || holds_logs) {
+        return Some("logs");
+    }
+    if !options.clear_caches && (name == MODELS_DIR || name == tempdir::TEMP_DIR_NAME) {
+        return Some("cache");
+    }
+    None
+}
+
+/// Delete what the reset covers, going on past failures. Returns what was deleted and what
+/// wasn't.
+fn wipe(paths: &Paths, options: &ResetOptions, audit: &mut Audit) -> (usize, Vec<String>) {
+    let mut failed = Vec::new();
+    let mut doomed = Vec::new();
+    match fs::read_dir(&paths.data_dir) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                match kept(paths, options, &entry.file_name(), &path) {
+                    Some(reason) => audit.step(format!("Keeping {} ({reason})", path.display())),
+                    None => doomed.push(path),
+                }
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => {
+            let message = format!("listing {}: {e}", paths.data_dir.display());
+            audit.failed(&message);
+            failed.push(message);
+        }
+    }
+    // Already listed above unless the data dir was moved.
+    doomed.push(paths.app_dir.join(settings::SETTINGS_FILE_NAME));
+    doomed.push(paths.app_dir.join(restore::MARKER_FILE));
+    doomed.extend(paths.restore_backup.clone());
+    if options.clear_caches {
+        doomed.push(paths.app_dir.join(tempdir::TEMP_DIR_NAME));
+    }
+    if let Some(log_dir) = paths.log_dir.as_ref().filter(|_| options.clear_logs) {
+        // The folder itself stays for the logger, which may still write the current file.
+        if let Ok(entries) = fs::read_dir(log_dir) {
+            doomed.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+
+    let mut removed = 0;
+    let mut done: Vec<PathBuf> = Vec::with_capacity(doomed.len());
+    for path in doomed {
+        if done.iter().any(|parent| path.starts_with(parent)) {
+            continue;
+        }
+        match remove(&path) {
+            Ok(true) => {
+                removed += 1;
+                audit.step(format!("Deleted {}", path.display()));
+            }
+            Ok(false) => {}
+            Err(e) => {
+                let message = format!("deleting {}: {e}", path.display());
+                audit.failed(&message);
+                failed.push(message);
+            }
+        }
+        done.push(path);
+    }
+    (removed, failed)
+}
+
+/// Delete the file, link or folder at `path`; `false` if there was none.
+fn remove(path: &Path) -> io::Result<bool> {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    // remove_dir_all deletes links inside rather than following them.
+    if meta.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,322 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Memory and CPU of the backend and the workers it forked, for the status bar readout.
+//!
+//! A watch task samples the backend too and emits `backend-resource-warning` when it goes
+//! over the thresholds in settings, at most once per cooldown for each metric. With
+//! `restart_on_memory` on, a backend over the memory threshold is restarted if it has no
+//! jobs running.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, MINIMUM_CPU_UPDATE_INTERVAL};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::supervisor::{SpawnState, Supervisor};
+use crate::{endpoint, settings};
+
+pub(crate) const DEFAULT_RSS_MB: u64 = 1536;
+pub(crate) const DEFAULT_CPU_PERCENT: u32 = 90;
+pub(crate) const DEFAULT_CPU_SUSTAIN_SECS: u64 = 60;
+pub(crate) const DEFAULT_COOLDOWN_SECS: u64 = 600;
+const MIN_RSS_MB: u64 = 64;
+const MAX_CPU_SUSTAIN_SECS: u64 = 3600;
+const MIN_COOLDOWN_SECS: u64 = 10;
+const MAX_COOLDOWN_SECS: u64 = 24 * 60 * 60;
+/// How often the watch task samples the backend.
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The `System` reused across calls, managed by Tauri. CPU usage is the difference between
+/// two refreshes, so keeping it also gives every call after the first a real reading.
+#[derive(Default)]
+pub(crate) struct ResourceMonitor(Mutex<Sampler>);
+
+#[derive(Default)]
+struct Sampler {
+    system: System,
+    /// Backend pid of the previous refresh.
+    last_pid: Option<u32>,
+}
+
+/// Payload of `get_backend_resource_usage`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum BackendResources {
+    NotRunning,
+    Running(ResourceUsage),
+}
+
+/// Totals over the backend and its descendants.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ResourceUsage {
+    pub pid: u32,
+    pub rss_bytes: u64,
+    /// Share of the whole machine, 0 to 100, like a task manager shows it.
+    pub cpu_percent: f32,
+    /// `None` where the OS doesn't tell (or not for every process).
+    pub open_files: Option<usize>,
+    /// Processes the backend spawned, e.g. OCR workers.
+    pub child_processes: usize,
+}
+
+/// Sample the backend `pid`, or report it is not running. Blocks for a moment on the first
+/// sample of a new pid, which has no earlier CPU reading to compare with.
+pub(crate) fn sample(monitor: &ResourceMonitor, pid: Option<u32>) -> BackendResources {
+    let Some(pid) = pid else {
+        return BackendResources::NotRunning;
+    };
+    let mut sampler = monitor.0.lock().unwrap();
+    let refresh = ProcessRefreshKind::nothing()
+        .with_memory()
+        .with_cpu()
+        .without_tasks();
+    // All processes: the workers are only found through their parent links.
+    sampler
+        .system
+        .refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+    if sampler.last_pid != Some(pid) {
+        std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+        sampler
+            .system
+            .refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        sampler.last_pid = Some(pid);
+    }
+
+    let system = &sampler.system;
+    let root = Pid::from_u32(pid);
+    let Some(backend) = system.process(root) else {
+        return BackendResources::NotRunning;
+    };
+    let mut processes = vec![backend];
+    let mut next = 0;
+    while let Some(parent) = processes.get(next).map(|p| p.pid()) {
+        processes.extend(
+            system
+                .processes()
+                .values()
+                .filter(|p| p.parent() == Some(parent) && p.thread_kind().is_none()),
+        );
+        next += 1;
+    }
+
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as f32;
+    let cpu: f32 = processes.iter().map(|p| p.cpu_usage()).sum();
+    BackendResources::Running(ResourceUsage {
+        pid,
+        rss_bytes: processes.iter().map(|p| p.memory()).sum(),
+        cpu_percent: (cpu / cores).min(100.0),
+        open_files: processes.iter().map(|p| p.open_files()).sum(),
+        child_processes: processes.len() - 1,
+    })
+}
+
+/// When the backend's usage is worth a warning, from the `resource_*` settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Thresholds {
+    /// Memory of the backend and its workers together.
+    pub rss_mb: u64,
+    /// CPU share, 1 to 100, that has to last `cpu_sustain_secs`.
+    pub cpu_percent: u32,
+    pub cpu_sustain_secs: u64,
+    /// Least time between two warnings about the same metric.
+    pub cooldown_secs: u64,
+    /// Restart a backend over `rss_mb` that has no jobs running.
+    pub restart_on_memory: bool,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            rss_mb: DEFAULT_RSS_MB,
+            cpu_percent: DEFAULT_CPU_PERCENT,
+            cpu_sustain_secs: DEFAULT_CPU_SUSTAIN_SECS,
+            cooldown_secs: DEFAULT_COOLDOWN_SECS,
+            restart_on_memory: false,
+        }
+    }
+}
+
+impl Thresholds {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.rss_mb < MIN_RSS_MB {
+            return Err(format!(
+                "Memory threshold must be at least {MIN_RSS_MB} MB, got {}",
+                self.rss_mb
+            ));
+        }
+        if !(1..=100).contains(&self.cpu_percent) {
+            return Err(format!(
+                "CPU threshold must be between 1 and 100%, got {}",
+                self.cpu_percent
+            ));
+        }
+        if self.cpu_sustain_secs > MAX_CPU_SUSTAIN_SECS {
+            return Err(format!(
+                "CPU duration must be at most {MAX_CPU_SUSTAIN_SECS}s, got {}",
+                self.cpu_sustain_secs
+            ));
+        }
+        if !(MIN_COOLDOWN_SECS..=MAX_COOLDOWN_SECS).contains(&self.cooldown_secs) {
+            return Err(format!(
+                "Warning cooldown must be between {MIN_COOLDOWN_SECS} and {MAX_COOLDOWN_SECS}s, got {}",
+                self.cooldown_secs
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What a `backend-resource-warning` is about; the names carry the unit of the values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Metric {
+    RssMb,
+    CpuPercent,
+}
+
+/// Payload of the `backend-resource-warning` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ResourceWarning {
+    pub metric: Metric,
+    pub value: f64,
+    pub threshold: f64,
+    pub pid: u32,
+    /// The backend is restarted because of it.
+    pub restarting: bool,
+}
+
+/// What the watch task remembers between samples.
+#[derive(Default)]
+struct Watch {
+    pid: Option<u32>,
+    /// Since when CPU has been over the threshold, while it stays over.
+    cpu_over_since: Option<Instant>,
+    rss_warned_at: Option<Instant>,
+    cpu_warned_at: Option<Instant>,
+}
+
+impl Watch {
+    /// The warnings `usage` calls for now, cooldowns applied.
+    fn check(
+        &mut self,
+        usage: &ResourceUsage,
+        thresholds: &Thresholds,
+        now: Instant,
+    ) -> Vec<ResourceWarning> {
+        if self.pid != Some(usage.pid) {
+            self.pid = Some(usage.pid);
+            self.cpu_over_since = None;
+        }
+        let cooldown = Duration::from_secs(thresholds.cooldown_secs);
+        let cooled =
+            |warned: Option<Instant>| warned.is_none_or(|at| now.duration_since(at) >= cooldown);
+        let mut warnings = Vec::new();
+
+        let rss_mb = usage.rss_bytes as f64 / (1024.0 * 1024.0);
+        if rss_mb > thresholds.rss_mb as f64 && cooled(self.rss_warned_at) {
+            self.rss_warned_at = Some(now);
+            warnings.push(ResourceWarning {
+                metric: Metric::RssMb,
+                value: rss_mb.round(),
+                threshold: thresholds.rss_mb as f64,
+                pid: usage.pid,
+                restarting: false,
+            });
+        }
+
+        if usage.cpu_percent <= thresholds.cpu_percent as f32 {
+            self.cpu_over_since = None;
+        } else {
+            let since = *self.cpu_over_since.get_or_insert(now);
+            if now.duration_since(since) >= Duration::from_secs(thresholds.cpu_sustain_secs)
+                && cooled(self.cpu_warned_at)
+            {
+                self.cpu_warned_at = Some(now);
+                warnings.push(ResourceWarning {
+                    metric: Metric::CpuPercent,
+                    value: f64::from(usage.cpu_percent).round(),
+                    threshold: f64::from(thresholds.cpu_percent),
+                    pid: usage.pid,
+                    restarting: false,
+                });
+            }
+        }
+        warnings
+    }
+}
+
+/// Sample the running backend every `WATCH_INTERVAL` and warn about its usage, until the
+/// app quits. Thresholds are read from the settings at each sample.
+pub(crate) fn start_watch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let supervisor = app.state::<Supervisor>();
+        let exited = supervisor.exited();
+        tokio::pin!(exited);
+        let mut watch = Watch::default();
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+                _ = &mut exited => return,
+            }
+            if supervisor.state() != SpawnState::Running || supervisor.is_suspended() {
+                watch.cpu_over_since = None;
+                continue;
+            }
+            let Some(pid) = crate::running_backend_pid(&app) else {
+                continue;
+            };
+            let handle = app.clone();
+            let sampled = tauri::async_runtime::spawn_blocking(move || {
+                sample(&handle.state::<ResourceMonitor>(), Some(pid))
+            })
+            .await;
+            let Ok(BackendResources::Running(usage)) = sampled else {
+                continue;
+            };
+
+            let thresholds = settings::current(&app).resource_thresholds();
+            for mut warning in watch.check(&usage, &thresholds, Instant::now()) {
+                let restart = warning.metric == Metric::RssMb
+                    && thresholds.restart_on_memory
+                    && crate::active_backend_jobs(&endpoint::transport(&app))
+                        .await
+                        .is_empty();
+                warning.restarting = restart;
+                log::warn!(
+                    "Backend pid {} is over its {:?} threshold: {} > {}{}",
+                    warning.pid,
+                    warning.metric,
+                    warning.value,
+                    warning.threshold,
+                    if restart { ", restarting it" } else { "" }
+                );
+                let _ = app.emit("backend-resource-warning", warning);
+                if restart {
+                    match crate::restart_backend(app.clone()).await {
+                        Ok(true) => {
+                            log::info!("Backend restarted after going over its memory threshold")
+                        }
+                        Ok(false) => {
+                            log::warn!("Backend did not come back after a memory restart")
+                        }
+                        Err(e) => log::warn!("Memory restart skipped: {}", e),
+                    }
+                }
+            }
+        }
+    });
+}
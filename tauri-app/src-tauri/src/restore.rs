@@ -0,0 +1,631 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `restore_data`: put a `backup_data` zip in place of the backend's data.
+//!
+//! The zip is checked before anything is touched: manifest and database present, written
+//! by this version of the app or an older one, only plain relative paths. Then the backend
+//! is stopped, the current data is moved aside into a `.bak` folder next to the data dir,
+//! the zip is extracted and the backend started on it. If it doesn't become ready the
+//! extracted files are removed, the `.bak` contents moved back and the backend started
+//! again on them.
+//!
+//! `restore.json` in the app data dir records a restore in progress, and which step it
+//! reached. Launching with it still there means the app died mid-restore: the previous
+//! data is put back from the `.bak` folder before the backend starts. After a restore
+//! that worked, the `.bak` folder stays until the next restore replaces it.
+//!
+//! A backup of part of the data, such as a pre-update one, lists what it holds in its
+//! manifest; only those entries are moved aside and replaced.
+
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backup::{self, Manifest};
+use crate::supervisor::{self, Startup, Supervisor};
+use crate::{datadir, endpoint, unzip, version};
+
+pub(crate) const MARKER_FILE: &str = "restore.json";
+/// The backend's scratch folder; never in a backup, so left as it is.
+const BACKEND_TEMP: &str = "temp";
+const PROGRESS_EVENT: &str = "restore-progress";
+/// Least time between two progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `restore.json`: where the previous data went, and how far the restore got.
+#[derive(Debug, Serialize, Deserialize)]
+struct Marker {
+    data_dir: PathBuf,
+    backup_dir: PathBuf,
+    step: Step,
+    /// The manifest's `replaces`: the top-level entries being replaced, `None` for all.
+    #[serde(default)]
+    replaces: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Step {
+    /// Entries are being moved into `backup_dir`; whatever is left in the data dir is
+    /// still the previous data.
+    MovingAside,
+    /// The data dir holds extracted files, complete or not.
+    Extracting,
+}
+
+/// What `restore_data` did.
+#[derive(Debug, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub(crate) enum RestoreOutcome {
+    /// The backend is running on the restored data.
+    Restored {
+        files: usize,
+        bytes: u64,
+        /// App version that wrote the backup.
+        backup_version: String,
+        /// The data it replaced, kept until the next restore.
+        previous_data: PathBuf,
+    },
+    /// The restore failed after the data was moved aside, and the previous data is back.
+    RolledBack {
+        reason: String,
+        /// The backend is running again on the previous data.
+        backend_ready: bool,
+    },
+}
+
+/// Payload of the `restore-progress` event.
+#[derive(Clone, Debug, Serialize)]
+struct RestoreProgress {
+    written_bytes: u64,
+    total_bytes: u64,
+    files_done: usize,
+    files_total: usize,
+}
+
+/// A checked zip, ready to extract.
+struct Plan {
+    archive: unzip::Archive,
+    /// File entries to extract, with where each goes under the data dir.
+    files: Vec<(unzip::Entry, PathBuf)>,
+    total_bytes: u64,
+    manifest: Manifest,
+}
+
+fn marker_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(MARKER_FILE))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// The `.bak` folder next to `data_dir`.
+pub(crate) fn backup_dir(data_dir: &Path) -> Result<PathBuf, String> {
+    let name = data_dir
+        .file_name()
+        .ok_or_else(|| format!("Can't put a folder next to {}", data_dir.display()))?;
+    let mut name = name.to_os_string();
+    name.push(".bak");
+    Ok(data_dir.with_file_name(name))
+}
+
+/// What a restore may replace: everything but the shell's own files, the backend's temp
+/// and the app's backups.
+fn is_data(name: &OsStr) -> bool {
+    name != BACKEND_TEMP && name != backup::BACKUPS_DIR && !datadir::is_shell_entry(name)
+}
+
+/// What the restore `marker` records replaces.
+fn is_replaced(marker: &Marker, name: &OsStr) -> bool {
+    is_data(name)
+        && marker
+            .replaces
+            .as_ref()
+            .is_none_or(|replaces| replaces.iter().any(|entry| name == entry.as_str()))
+}
+
+/// Write `marker` through a synced temp file, so it is either the old or the new one.
+fn write_marker(path: &Path, marker: &Marker) -> io::Result<()> {
+    let temp = path.with_file_name(format!(".{MARKER_FILE}.part"));
+    let json = serde_json::to_vec_pretty(marker).map_err(io::Error::other)?;
+    let mut file = File::create(&temp)?;
+    file.write_all(&json)?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
+
+/// Put the previous data back if the app died mid-restore. Call before the first backend
+/// launch.
+#[cfg_attr(debug_assertions, allow(dead_code))]
+pub(crate) fn recover(app: &AppHandle) {
+    if let Ok(path) = marker_path(app) {
+        recover_from(&path);
+    }
+}
+
+/// `recover` with the marker at `path`.
+#[cfg_attr(debug_assertions, allow(dead_code))]
+fn recover_from(path: &Path) {
+    let marker = match fs::read(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => {
+            log::error!("Failed to read {}: {}", path.display(), e);
+            return;
+        }
+    };
+    let marker: Marker = match serde_json::from_slice(&marker) {
+        Ok(marker) => marker,
+        Err(e) => {
+            log::error!(
+                "Unreadable {}, leaving it for a look: {}",
+                path.display(),
+                e
+            );
+            return;
+        }
+    };
+    log::warn!(
+        "A restore into {} did not finish ({:?}); putting back the data from {}",
+        marker.data_dir.display(),
+        marker.step,
+        marker.backup_dir.display()
+    );
+    match roll_back(&marker) {
+        Ok(()) => {
+            let _ = fs::remove_file(path);
+            log::info!("Previous data restored after an interrupted restore");
+        }
+        // The marker stays, so the next launch tries again.
+        Err(e) => log::error!("Failed to put back the previous data: {}", e),
+    }
+}
+
+/// Move the data out of `marker.data_dir` into `marker.backup_dir`.
+fn move_aside(marker: &Marker) -> io::Result<()> {
+    fs::create_dir_all(&marker.backup_dir)?;
+    if !marker.data_dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(&marker.data_dir)? {
+        let name = entry?.file_name();
+        if is_replaced(marker, &name) {
+            fs::rename(marker.data_dir.join(&name), marker.backup_dir.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Undo a restore up to `marker.step`: drop extracted files, then move the previous data
+/// back and remove the emptied `.bak` folder.
+fn roll_back(marker: &Marker) -> io::Result<()> {
+    if marker.step == Step::Extracting && marker.data_dir.exists() {
+        for entry in fs::read_dir(&marker.data_dir)? {
+            let name = entry?.file_name();
+            if !is_replaced(marker, &name) {
+                continue;
+            }
+            let path = marker.data_dir.join(&name);
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+    if !marker.backup_dir.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(&marker.data_dir)?;
+    for entry in fs::read_dir(&marker.backup_dir)? {
+        let name = entry?.file_name();
+        fs::rename(marker.backup_dir.join(&name), marker.data_dir.join(&name))?;
+    }
+    fs::remove_dir(&marker.backup_dir)
+}
+
+/// Where `name` goes under the data dir, if it is a plain relative path outside the
+/// shell's files and the backend's temp folder.
+fn entry_path(name: &str) -> Option<PathBuf> {
+    if name.contains('\\') {
+        return None;
+    }
+    let path = PathBuf::from(name.trim_end_matches('/'));
+    let plain = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    let first = path.components().next()?;
+    (plain && is_data(first.as_os_str())).then_some(path)
+}
+
+/// Open `archive` and check it is a backup this app can restore.
+fn plan(archive_path: &Path, app_version: &str) -> Result<Plan, String> {
+    let mut archive = unzip::open(archive_path)
+        .map_err(|e| format!("{} is not a readable backup: {e}", archive_path.display()))?;
+    let manifest = archive
+        .find(backup::MANIFEST_FILE)
+        .ok_or("Not a backup made by this app: it has no manifest")?;
+    let manifest: Manifest = archive
+        .read(&manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_slice(&json).map_err(|e| e.to_string()))
+        .map_err(|e| format!("The backup's manifest is unreadable: {e}"))?;
+    if archive.find(backup::DATABASE_FILE).is_none() {
+        return Err("The backup has no database".into());
+    }
+    if manifest.format > backup::FORMAT {
+        return Err(format!(
+            "The backup was made by a newer version of the app ({}); update it first",
+            manifest.app_version
+        ));
+    }
+    // A newer app may have migrated the database to a schema this one can't read.
+    match (
+        version::major_minor(&manifest.app_version),
+        version::major_minor(app_version),
+    ) {
+        (Some(made_by), Some(ours)) if made_by <= ours => {}
+        (Some(_), Some(_)) => {
+            return Err(format!(
+                "The backup was made by version {}, newer than this app ({app_version}); update it first",
+                manifest.app_version
+            ))
+        }
+        _ => {
+            return Err(format!(
+                "The backup's app version {:?} is not recognised",
+                manifest.app_version
+            ))
+        }
+    }
+
+    let mut files = Vec::new();
+    for entry in &archive.entries {
+        if entry.name == backup::MANIFEST_FILE || entry.name.starts_with(backup::SHELL_PREFIX) {
+            continue;
+        }
+        let path = entry_path(&entry.name)
+            .ok_or_else(|| format!("The backup holds an unsafe path: {:?}", entry.name))?;
+        // A partial backup must not leave files next to the data it keeps.
+        if let Some(replaces) = &manifest.replaces {
+            let first = path.components().next().map(|c| c.as_os_str());
+            if !first.is_some_and(|first| replaces.iter().any(|entry| first == entry.as_str())) {
+                return Err(format!(
+                    "The backup holds {:?}, outside what it replaces",
+                    entry.name
+                ));
+            }
+        }
+        if !entry.is_dir() {
+            files.push((entry.clone(), path));
+        }
+    }
+    let total_bytes = files.iter().map(|(entry, _)| entry.size).sum();
+    Ok(Plan {
+        archive,
+        files,
+        total_bytes,
+        manifest,
+    })
+}
+
+/// Extract the plan's files under `data_dir`, each synced before the next.
+fn extract(app: &AppHandle, plan: &mut Plan, data_dir: &Path) -> Result<(), String> {
+    let mut progress = RestoreProgress {
+        written_bytes: 0,
+        total_bytes: plan.total_bytes,
+        files_done: 0,
+        files_total: plan.files.len(),
+    };
+    let mut reported = Instant::now();
+    let _ = app.emit(PROGRESS_EVENT, progress.clone());
+    for (entry, relative) in &plan.files {
+        let path = data_dir.join(relative);
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                let mut out = BufWriter::new(File::create(&path)?);
+                plan.archive.extract(entry, &mut out)?;
+                out.into_inner().map_err(|e| e.into_error())?.sync_all()
+            });
+        written.map_err(|e| format!("Failed to restore {}: {e}", entry.name))?;
+        progress.written_bytes += entry.size;
+        progress.files_done += 1;
+        if progress.files_done == progress.files_total || reported.elapsed() >= PROGRESS_INTERVAL {
+            reported = Instant::now();
+            let _ = app.emit(PROGRESS_EVENT, progress.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Replace the backend's data with the backup at `archive`. Errors mean nothing was
+/// changed; a failure after the data was moved aside is rolled back and reported as
+/// `RolledBack`.
+pub(crate) async fn restore(app: &AppHandle, archive: PathBuf) -> Result<RestoreOutcome, String> {
+    if cfg!(debug_assertions) {
+        return Err("Dev mode: the backend's data dir is not managed by the app".into());
+    }
+    if endpoint::external_url(app).is_some() {
+        return Err("An external backend keeps its data itself".into());
+    }
+    if !crate::active_backend_jobs(&endpoint::transport(app))
+        .await
+        .is_empty()
+    {
+        return Err("Wait for the running jobs to finish before restoring".into());
+    }
+    let marker_file = marker_path(app)?;
+    if marker_file.exists() {
+        return Err("An earlier restore did not finish; restart the app first".into());
+    }
+    let data_dir = datadir::path(app)?;
+    let app_version = app.package_info().version.to_string();
+    let mut plan = {
+        let data_dir = data_dir.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let plan = plan(&archive, &app_version)?;
+            datadir::check_free_space(&data_dir, plan.total_bytes)?;
+            Ok::<_, String>(plan)
+        })
+        .await
+        .map_err(|e| format!("Checking the backup failed: {e}"))??
+    };
+
+    let supervisor = app.state::<Supervisor>();
+    let mut stop = supervisor.stop_signal();
+    let Some(_guard) = supervisor.try_lock_lifecycle() else {
+        return Err("The backend is starting or restarting, try again in a moment".into());
+    };
+    let mut marker = Marker {
+        backup_dir: backup_dir(&data_dir)?,
+        data_dir,
+        step: Step::MovingAside,
+        replaces: plan.manifest.replaces.clone(),
+    };
+    log::info!(
+        "Restoring a backup from version {} ({} files, {} bytes) into {}",
+        plan.manifest.app_version,
+        plan.files.len(),
+        plan.total_bytes,
+        marker.data_dir.display()
+    );
+    supervisor::stop_current(app).await;
+
+    let handle = app.clone();
+    let file = marker_file.clone();
+    let (extracted, marker, plan) = tauri::async_runtime::spawn_blocking(move || {
+        // Outer error: the data was never moved aside (or was moved back).
+        let extracted = prepare(&file, &marker).map(|()| {
+            marker.step = Step::Extracting;
+            write_marker(&file, &marker)
+                .map_err(|e| format!("Failed to record the restore: {e}"))?;
+            extract(&handle, &mut plan, &marker.data_dir)
+        });
+        (extracted, marker, plan)
+    })
+    .await
+    .map_err(|e| format!("Restoring failed: {e}"))?;
+
+    let reason = match extracted {
+        Err(e) => {
+            let _ = supervisor::launch_and_wait(app, &mut stop).await;
+            return Err(e);
+        }
+        Ok(Err(e)) => e,
+        Ok(Ok(())) => match supervisor::launch_and_wait(app, &mut stop).await {
+            Ok(Startup::Ready) => {
+                let _ = fs::remove_file(&marker_file);
+                log::info!(
+                    "Backup restored; previous data kept in {}",
+                    marker.backup_dir.display()
+                );
+                return Ok(RestoreOutcome::Restored {
+                    files: plan.files.len(),
+                    bytes: plan.total_bytes,
+                    backup_version: plan.manifest.app_version,
+                    previous_data: marker.backup_dir,
+                });
+            }
+            Ok(startup) => {
+                format!("The backend did not become ready on the restored data ({startup:?})")
+            }
+            Err(e) => format!("The backend did not start on the restored data: {e}"),
+        },
+    };
+
+    log::error!("{}; rolling back", reason);
+    supervisor::stop_current(app).await;
+    let rolled_back = tauri::async_runtime::spawn_blocking(move || {
+        roll_back(&marker)?;
+        fs::remove_file(&marker_file)
+    })
+    .await
+    .map_err(|e| format!("Rolling back failed: {e}"))?;
+    if let Err(e) = rolled_back {
+        // The marker stays and the next launch tries again.
+        return Err(format!(
+            "{reason}, and putting back the previous data failed: {e}. Restart the app to retry"
+        ));
+    }
+    let backend_ready = matches!(
+        supervisor::launch_and_wait(app, &mut stop).await,
+        Ok(Startup::Ready)
+    );
+    Ok(RestoreOutcome::RolledBack {
+        reason,
+        backend_ready,
+    })
+}
+
+/// Clear out an old `.bak` folder, record the restore, and move the current data aside.
+/// On failure whatever was moved is moved back and the marker removed; if that fails too
+/// the marker stays, for the next launch to finish the job.
+fn prepare(marker_path: &Path, marker: &Marker) -> Result<(), String> {
+    if marker.backup_dir.exists() {
+        fs::remove_dir_all(&marker.backup_dir).map_err(|e| {
+            format!(
+                "Failed to remove the previous {}: {e}",
+                marker.backup_dir.display()
+            )
+        })?;
+    }
+    write_marker(marker_path, marker).map_err(|e| format!("Failed to record the restore: {e}"))?;
+    if let Err(e) = move_aside(marker) {
+        let undone = roll_back(marker).and_then(|()| fs::remove_file(marker_path));
+        if let Err(undo) = undone {
+            log::error!("Failed to move the data back: {}", undo);
+        }
+        return Err(format!(
+            "Failed to move the current data to {}: {e}",
+            marker.backup_dir.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings;
+
+    /// An empty scratch folder under the system temp dir.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("restore-test-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    fn marker(root: &Path, step: Step) -> Marker {
+        let data_dir = root.join("data");
+        Marker {
+            backup_dir: backup_dir(&data_dir).unwrap(),
+            data_dir,
+            step,
+            replaces: None,
+        }
+    }
+
+    #[test]
+    fn entry_path_takes_plain_relative_paths() {
+        assert_eq!(
+            entry_path("data/invoices.db"),
+            Some(PathBuf::from("data/invoices.db"))
+        );
+        assert_eq!(entry_path("uploads/"), Some(PathBuf::from("uploads")));
+        assert_eq!(
+            entry_path("uploads/2024/scan.pdf"),
+            Some(PathBuf::from("uploads/2024/scan.pdf"))
+        );
+    }
+
+    #[test]
+    fn entry_path_refuses_escapes_and_shell_files() {
+        for name in [
+            "../outside",
+            "data/../../outside",
+            "/etc/passwd",
+            "data\\..\\..\\outside",
+            "data\\invoices.db",
+            "",
+            settings::SETTINGS_FILE_NAME,
+            "temp/upload.part",
+            "backups/old.zip",
+        ] {
+            assert_eq!(entry_path(name), None, "{name:?}");
+        }
+    }
+
+    /// A failed extraction leaves new files and the previous data side by side; rolling
+    /// back keeps only the previous data, and the shell's own files where they are.
+    #[test]
+    fn roll_back_restores_the_previous_data() {
+        let root = scratch("roll-back");
+        let marker = marker(&root, Step::Extracting);
+        write(&marker.data_dir.join("data/invoices.db"), "restored");
+        write(&marker.data_dir.join("uploads/new.pdf"), "restored");
+        write(&marker.data_dir.join(settings::SETTINGS_FILE_NAME), "{}");
+        write(&marker.backup_dir.join("data/invoices.db"), "previous");
+        write(&marker.backup_dir.join("uploads/old.pdf"), "previous");
+
+        roll_back(&marker).unwrap();
+
+        assert_eq!(read(&marker.data_dir.join("data/invoices.db")), "previous");
+        assert_eq!(read(&marker.data_dir.join("uploads/old.pdf")), "previous");
+        assert!(!marker.data_dir.join("uploads/new.pdf").exists());
+        assert_eq!(
+            read(&marker.data_dir.join(settings::SETTINGS_FILE_NAME)),
+            "{}"
+        );
+        assert!(!marker.backup_dir.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    /// Died while moving the data aside: part of it is in `.bak`, the rest still in place.
+    #[test]
+    fn recover_after_moving_aside() {
+        let root = scratch("recover-moving");
+        let marker = marker(&root, Step::MovingAside);
+        write(&marker.data_dir.join("uploads/scan.pdf"), "previous");
+        write(&marker.backup_dir.join("data/invoices.db"), "previous");
+        let marker_file = root.join(MARKER_FILE);
+        write_marker(&marker_file, &marker).unwrap();
+
+        recover_from(&marker_file);
+
+        assert_eq!(read(&marker.data_dir.join("data/invoices.db")), "previous");
+        assert_eq!(read(&marker.data_dir.join("uploads/scan.pdf")), "previous");
+        assert!(!marker.backup_dir.exists());
+        assert!(!marker_file.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    /// Died mid-extraction: the half-restored files go and the previous data comes back,
+    /// except what a partial backup doesn't replace.
+    #[test]
+    fn recover_after_extracting() {
+        let root = scratch("recover-extracting");
+        let mut marker = marker(&root, Step::Extracting);
+        marker.replaces = Some(vec!["data".into()]);
+        write(&marker.data_dir.join("data/invoices.db"), "partial");
+        write(&marker.data_dir.join("uploads/scan.pdf"), "kept");
+        write(&marker.backup_dir.join("data/invoices.db"), "previous");
+        let marker_file = root.join(MARKER_FILE);
+        write_marker(&marker_file, &marker).unwrap();
+
+        recover_from(&marker_file);
+
+        assert_eq!(read(&marker.data_dir.join("data/invoices.db")), "previous");
+        assert_eq!(read(&marker.data_dir.join("uploads/scan.pdf")), "kept");
+        assert!(!marker.backup_dir.exists());
+        assert!(!marker_file.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
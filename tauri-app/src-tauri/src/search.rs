@@ -0,0 +1,233 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Invoice search, with the criteria checked in the shell so a bad query names the field
+//! at fault instead of coming back as the backend's 422.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::concurrency::{self, Lane};
+use crate::invoices::DEFAULT_PAGE_SIZE;
+use crate::proxy::{self, TimeoutClass};
+use crate::{config, endpoint};
+
+const PATH: &str = "/api/v1/invoices/search";
+/// The backend's own limit on `limit`.
+const MAX_PAGE_SIZE: u32 = 500;
+/// The backend's column width for providers.
+const MAX_VENDOR_CHARS: usize = 255;
+
+/// Criteria of `search_invoices`; fields left out don't filter.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct InvoiceQuery {
+    /// Part of the provider's name, in any case.
+    pub vendor: Option<String>,
+    /// Bounds on the total with VAT, or without VAT when that is all an invoice has.
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    /// "YYYY-MM-DD", both ends included.
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    /// Counted from 0.
+    pub page: u32,
+    pub page_size: Option<u32>,
+}
+
+/// Why a search failed, tagged by `kind`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum SearchError {
+    /// The query itself is wrong; nothing was sent.
+    Invalid {
+        field: &'static str,
+        message: String,
+    },
+    /// The backend answered with an error status.
+    Rejected { status: u16, message: String },
+    /// No answer from the backend.
+    ConnectionLost { message: String },
+}
+
+fn invalid(field: &'static str, message: String) -> SearchError {
+    SearchError::Invalid { field, message }
+}
+
+/// One page of results.
+#[derive(Debug, Serialize)]
+pub(crate) struct SearchPage {
+    pub invoices: Vec<serde_json::Value>,
+    /// Invoices matching across all pages.
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+}
+
+/// `/api/v1/invoices/search` as the backend sends it.
+#[derive(Deserialize)]
+struct BackendPage {
+    items: Vec<serde_json::Value>,
+    total: u64,
+}
+
+impl InvoiceQuery {
+    /// Check the criteria and turn them into the backend's query parameters.
+    fn to_params(&self) -> Result<(u32, BTreeMap<String, String>), SearchError> {
+        let mut params = BTreeMap::new();
+
+        let vendor = self.vendor.as_deref().map(str::trim).unwrap_or_default();
+        if vendor.chars().count() > MAX_VENDOR_CHARS {
+            return Err(invalid(
+                "vendor",
+                format!("must be at most {MAX_VENDOR_CHARS} characters"),
+            ));
+        }
+        if !vendor.is_empty() {
+            params.insert("vendor".to_string(), vendor.to_string());
+        }
+
+        for (field, amount) in [
+            ("min_amount", self.min_amount),
+            ("max_amount", self.max_amount),
+        ] {
+            let Some(amount) = amount else { continue };
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(invalid(
+                    field,
+                    format!("must be a non-negative amount, got {amount}"),
+                ));
+            }
+            params.insert(field.to_string(), amount.to_string());
+        }
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(invalid(
+                    "min_amount",
+                    format!("must not exceed max_amount ({min} > {max})"),
+                ));
+            }
+        }
+
+        let mut dates = [None, None];
+        for (slot, (field, value)) in dates
+            .iter_mut()
+            .zip([("date_from", &self.date_from), ("date_to", &self.date_to)])
+        {
+            let Some(value) = value.as_deref().map(str::trim) else {
+                continue;
+            };
+            let date = parse_date(value).ok_or_else(|| {
+                invalid(
+                    field,
+                    format!("must be a date as YYYY-MM-DD, got {value:?}"),
+                )
+            })?;
+            params.insert(field.to_string(), value.to_string());
+            *slot = Some(date);
+        }
+        if let [Some(from), Some(to)] = dates {
+            if from > to {
+                return Err(invalid(
+                    "date_from",
+                    "must not be after date_to".to_string(),
+                ));
+            }
+        }
+
+        let page_size = self.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(invalid(
+                "page_size",
+                format!("must be between 1 and {MAX_PAGE_SIZE}, got {page_size}"),
+            ));
+        }
+        let skip = u64::from(self.page) * u64::from(page_size);
+        params.insert("skip".to_string(), skip.to_string());
+        params.insert("limit".to_string(), page_size.to_string());
+        Ok((page_size, params))
+    }
+}
+
+/// Year, month and day of a "YYYY-MM-DD" date that exists.
+fn parse_date(value: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = value.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    if parts.next().is_some() || !digits(year, 4) || !digits(month, 2) || !digits(day, 2) {
+        return None;
+    }
+    let (year, month, day) = (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days).contains(&day).then_some((year, month, day))
+}
+
+/// Run `query` against the backend.
+pub(crate) async fn search(
+    app: &AppHandle,
+    query: InvoiceQuery,
+) -> Result<SearchPage, SearchError> {
+    let (page_size, params) = query.to_params()?;
+    let path = proxy::with_query(PATH, &params).map_err(|message| invalid("query", message))?;
+    let timeout = config::request_timeout(app, TimeoutClass::Normal);
+    let resp = {
+        let _permit = concurrency::acquire(app, Lane::Requests).await;
+        endpoint::transport(app)
+            .get(&path, timeout)
+            .await
+            .map_err(|e| SearchError::ConnectionLost {
+                message: format!("Invoice search failed: {e}"),
+            })?
+    };
+    if resp.status == 404 {
+        return Err(SearchError::Rejected {
+            status: resp.status,
+            message: "This backend cannot search invoices; update it".to_string(),
+        });
+    }
+    if !resp.is_success() {
+        let detail = resp
+            .json::<serde_json::Value>()
+            .ok()
+            .and_then(|body| {
+                body.get("detail")
+                    .map(|d| d.as_str().map_or_else(|| d.to_string(), String::from))
+            })
+            .unwrap_or_else(|| resp.text());
+        return Err(SearchError::Rejected {
+            status: resp.status,
+            message: detail,
+        });
+    }
+    let found: BackendPage = resp.json().map_err(|e| SearchError::Rejected {
+        status: resp.status,
+        message: format!("Backend sent unreadable search results: {e}"),
+    })?;
+    Ok(SearchPage {
+        has_more: (u64::from(query.page) + 1) * u64::from(page_size) < found.total,
+        invoices: found.items,
+        total: found.total,
+        page: query.page,
+        page_size,
+    })
+}
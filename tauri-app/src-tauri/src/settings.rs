@@ -0,0 +1,261 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User settings, persisted as JSON in the app data dir.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::backendlog;
+use crate::concurrency;
+use crate::diskspace;
+use crate::loglevel::SavedLevel;
+use crate::port::PortRange;
+use crate::preupdate;
+use crate::priority::BackendPriority;
+use crate::proxy::TimeoutClass;
+use crate::resources;
+use crate::transport::TransportKind;
+
+pub(crate) const SETTINGS_FILE_NAME: &str = "settings.json";
+/// Settings that survive app restarts. Missing fields fall back to their defaults, so
+/// settings files written by older versions keep loading.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub backend_priority: BackendPriority,
+    /// How long to wait for a freshly spawned backend to answer `/health`.
+    pub readiness_timeout_secs: Option<u64>,
+    /// Longest delay between readiness polls; shorter delays are used at first.
+    pub readiness_poll_interval_ms: Option<u64>,
+    /// Timeout of each readiness `/health` request.
+    pub readiness_http_timeout_ms: Option<u64>,
+    /// Shut the backend down when the main window closes. When off, macOS keeps the
+    /// backend running for a window reopened from the dock.
+    pub quit_on_close: bool,
+    /// Appended to the backend's command line, e.g. `--workers 2`.
+    pub extra_backend_args: Vec<String>,
+    /// Names of extra environment variables to pass through to the backend.
+    pub forward_env: Vec<String>,
+    /// Hard memory cap for the backend in MB; `None` or 0 disables it. Not enforced on
+    /// macOS; see `memlimit`. Off unless the user sets it: on Linux it caps address space,
+    /// not resident memory, and torch maps far more than it touches.
+    pub memory_limit_mb: Option<u64>,
+    /// Troubleshooting: echo backend output into a console window (Windows only).
+    pub show_backend_console: bool,
+    /// Files in the backend temp dir older than this are removed on start and exit.
+    pub temp_max_age_hours: u64,
+    /// Purge the page renders and temp files on exit, as `purge_cache` would.
+    pub clear_caches_on_exit: bool,
+    /// Run the backend from a copy in the app cache dir. `None` decides automatically:
+    /// only when the bundle is read-only (AppImage) or translocated (macOS).
+    pub stage_backend: Option<bool>,
+    /// Preferred port for the backend, tried before `backend_port_range`. With neither set,
+    /// the OS picks a free port at each launch.
+    pub backend_port: Option<u16>,
+    /// Ports to fall back to, in order, when the preferred one is busy.
+    pub backend_port_range: Option<PortRange>,
+    /// Attach to this backend (e.g. an OCR server on the LAN) instead of spawning one.
+    pub backend_url: Option<String>,
+    /// How the shell talks to the backend it spawns; TCP remains the fallback.
+    pub backend_transport: TransportKind,
+    /// Uploads sent to the backend at once; further ones queue in the shell.
+    pub max_concurrent_uploads: u32,
+    /// Proxied requests and downloads sent to the backend at once.
+    pub max_concurrent_requests: u32,
+    /// Per-class limits of backend calls in seconds, e.g. `{"long_running": 900}`;
+    /// classes left out keep their defaults.
+    pub request_timeout_secs: BTreeMap<TimeoutClass, u64>,
+    /// Serve the spawned backend over HTTPS with a pinned self-signed certificate. Uses
+    /// TCP whatever `backend_transport` says.
+    pub loopback_tls: bool,
+    /// Size in MB at which `backend.log` is rotated.
+    #[serde(alias = "backend_log_max_mb")]
+    pub log_max_size_mb: u64,
+    /// Log files kept, the live one included.
+    pub log_max_files: u32,
+    /// Rotated log files older than this are deleted; `None` keeps them by count only.
+    pub log_max_age_days: Option<u64>,
+    /// Level chosen for troubleshooting; `None` is info. See `loglevel` for when it lapses.
+    pub log_level: Option<SavedLevel>,
+    /// Field names redacted in captured backend output, on top of the built-in ones.
+    pub log_redaction_fields: Vec<String>,
+    /// Regular expressions whose matches are redacted in captured backend output.
+    pub log_redaction_patterns: Vec<String>,
+    /// Troubleshooting only: keep invoice data in captured backend output. Set by hand in
+    /// the settings file; no command changes it.
+    pub debug_disable_log_redaction: bool,
+    /// Anonymous crash and startup telemetry; off until the user turns it on.
+    pub telemetry_enabled: bool,
+    /// Where telemetry batches are posted; nothing is sent without one.
+    pub telemetry_endpoint: Option<String>,
+    /// Random ID sent with telemetry, made on first opt-in.
+    pub telemetry_install_id: Option<String>,
+    /// Memory in MB above which `backend-resource-warning` is emitted.
+    pub resource_rss_warn_mb: u64,
+    /// CPU percentage that, held for `resource_cpu_warn_secs`, is warned about.
+    pub resource_cpu_warn_percent: u32,
+    pub resource_cpu_warn_secs: u64,
+    /// Least time between two warnings about the same metric.
+    pub resource_warn_cooldown_secs: u64,
+    /// Restart an idle backend that goes over `resource_rss_warn_mb`. Off by default.
+    pub restart_on_memory_warning: bool,
+    /// The user turned down importing the pre-app backend's data; it isn't offered again.
+    pub legacy_data_dismissed: bool,
+    /// Uploads are refused with less free space than this in `DATA_DIR`; 0 never refuses.
+    pub min_free_space_mb: u64,
+    /// Free space under which `low-disk-space` is emitted.
+    pub low_disk_space_warn_mb: u64,
+    /// `prepare_for_update` backups kept, newest first; 0 keeps them all.
+    pub pre_update_backups_kept: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            backend_priority: BackendPriority::default(),
+            readiness_timeout_secs: None,
+            readiness_poll_interval_ms: None,
+            readiness_http_timeout_ms: None,
+            quit_on_close: true,
+            extra_backend_args: Vec::new(),
+            forward_env: Vec::new(),
+            memory_limit_mb: None,
+            show_backend_console: false,
+            temp_max_age_hours: 24,
+            clear_caches_on_exit: false,
+            stage_backend: None,
+            backend_port: None,
+            backend_port_range: None,
+            backend_url: None,
+            backend_transport: TransportKind::default(),
+            max_concurrent_uploads: concurrency::DEFAULT_UPLOADS,
+            max_concurrent_requests: concurrency::DEFAULT_REQUESTS,
+            request_timeout_secs: BTreeMap::new(),
+            loopback_tls: false,
+            log_max_size_mb: backendlog::DEFAULT_MAX_MB,
+            log_max_files: backendlog::DEFAULT_MAX_FILES,
+            log_max_age_days: None,
+            log_level: None,
+            log_redaction_fields: Vec::new(),
+            log_redaction_patterns: Vec::new(),
+            debug_disable_log_redaction: false,
+            telemetry_enabled: false,
+            telemetry_endpoint: None,
+            telemetry_install_id: None,
+            resource_rss_warn_mb: resources::DEFAULT_RSS_MB,
+            resource_cpu_warn_percent: resources::DEFAULT_CPU_PERCENT,
+            resource_cpu_warn_secs: resources::DEFAULT_CPU_SUSTAIN_SECS,
+            resource_warn_cooldown_secs: resources::DEFAULT_COOLDOWN_SECS,
+            restart_on_memory_warning: false,
+            legacy_data_dismissed: false,
+            min_free_space_mb: diskspace::DEFAULT_FLOOR_MB,
+            low_disk_space_warn_mb: diskspace::DEFAULT_WARN_MB,
+            pre_update_backups_kept: preupdate::DEFAULT_KEPT,
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn log_retention(&self) -> backendlog::Retention {
+        backendlog::Retention {
+            max_size_mb: self.log_max_size_mb,
+            max_files: self.log_max_files,
+            max_age_days: self.log_max_age_days,
+        }
+    }
+
+    pub(crate) fn resource_thresholds(&self) -> resources::Thresholds {
+        resources::Thresholds {
+            rss_mb: self.resource_rss_warn_mb,
+            cpu_percent: self.resource_cpu_warn_percent,
+            cpu_sustain_secs: self.resource_cpu_warn_secs,
+            cooldown_secs: self.resource_warn_cooldown_secs,
+            restart_on_memory: self.restart_on_memory_warning,
+        }
+    }
+
+    pub(crate) fn disk_space_thresholds(&self) -> diskspace::Thresholds {
+        diskspace::Thresholds {
+            floor_mb: self.min_free_space_mb,
+            warn_mb: self.low_disk_space_warn_mb,
+        }
+    }
+}
+
+/// The loaded settings, managed by Tauri.
+pub(crate) struct AppSettings(pub(crate) Mutex<Settings>);
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SETTINGS_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Read the settings file, falling back to defaults if it is missing or unreadable.
+pub(crate) fn load(app: &AppHandle) -> Settings {
+    let Ok(path) = settings_path(app) else {
+        return Settings::default();
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        log::warn!(
+            "Ignoring unreadable settings file {}: {}",
+            path.display(),
+            e
+        );
+        Settings::default()
+    })
+}
+
+/// Apply `change` to the current settings and write them to disk. Written to a temp file
+/// first, so a crash never leaves half a settings file.
+pub(crate) fn update(app: &AppHandle, change: impl FnOnce(&mut Settings)) -> Result<(), String> {
+    let state = app.state::<AppSettings>();
+    let mut settings = state.0.lock().unwrap();
+    let mut updated = settings.clone();
+    change(&mut updated);
+
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(&updated).map_err(|e| e.to_string())?;
+    let temp = path.with_file_name(format!(".{SETTINGS_FILE_NAME}.part"));
+    std::fs::write(&temp, json)
+        .and_then(|()| std::fs::rename(&temp, &path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            format!("{}: {e}", path.display())
+        })?;
+    *settings = updated;
+    Ok(())
+}
+
+/// Go back to the default settings in memory, once the settings file has been deleted.
+pub(crate) fn reset(app: &AppHandle) {
+    *app.state::<AppSettings>().0.lock().unwrap() = Settings::default();
+}
+
+/// A copy of the current settings.
+pub(crate) fn current(app: &AppHandle) -> Settings {
+    app.state::<AppSettings>().0.lock().unwrap().clone()
+}
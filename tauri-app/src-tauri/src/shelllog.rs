@@ -0,0 +1,112 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! The shell's own log: `tracing` events and spans, as JSON lines in `shell.log`, and
+//! also printed to the console in debug builds.
+//!
+//! `log` macros still work: their records are forwarded into the same subscriber. Both
+//! follow `log::max_level()`, so `loglevel` keeps adjusting them at runtime.
+
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, Manager};
+use tracing::Level;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+const FILE_NAME: &str = "shell.log";
+
+/// Where the shell log goes, or why it goes nowhere, for the diagnostics report.
+static OUTCOME: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+
+/// Install the subscriber. Without a log dir the shell logs to the console only, in
+/// debug builds, and nowhere in release builds.
+///
+/// Fails if another logger was installed first; `log` macros then reach that one.
+pub(crate) fn init(app: &AppHandle) -> Result<(), String> {
+    let file = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| {
+            let path = dir.join(FILE_NAME);
+            fs::create_dir_all(&dir)
+                .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+                .map(|file| (file, path.clone()))
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        });
+    let (file, outcome) = match file {
+        Ok((file, path)) => (Some(file), Ok(path)),
+        Err(e) => (None, Err(e)),
+    };
+
+    let json = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(Mutex::new(file))
+            .with_filter(filter_fn(|meta| enabled(meta.level())))
+    });
+    #[cfg(debug_assertions)]
+    let console = Some(
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(filter_fn(|meta| enabled(meta.level()))),
+    );
+    #[cfg(not(debug_assertions))]
+    let console: Option<tracing_subscriber::layer::Identity> = None;
+
+    // Sets up the `log` bridge too.
+    if let Err(e) = tracing_subscriber::registry()
+        .with(json)
+        .with(console)
+        .try_init()
+    {
+        let error = format!("Shell logging is not set up: {e}");
+        let _ = OUTCOME.set(Err(error.clone()));
+        return Err(error);
+    }
+    if let Err(e) = &outcome {
+        log::warn!("Shell log file is not written: {}", e);
+    }
+    let _ = OUTCOME.set(outcome);
+    Ok(())
+}
+
+/// The shell log file, or why there is none.
+pub(crate) fn file() -> Result<PathBuf, String> {
+    OUTCOME
+        .get()
+        .cloned()
+        .unwrap_or_else(|| Err("Shell logging was never set up".to_string()))
+}
+
+/// Whether `level` passes the shell's current `log` filter.
+fn enabled(level: &Level) -> bool {
+    let max = match log::max_level() {
+        log::LevelFilter::Off => return false,
+        log::LevelFilter::Error => Level::ERROR,
+        log::LevelFilter::Warn => Level::WARN,
+        log::LevelFilter::Info => Level::INFO,
+        log::LevelFilter::Debug => Level::DEBUG,
+        log::LevelFilter::Trace => Level::TRACE,
+    };
+    *level <= max
+}
@@ -0,0 +1,215 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Copy of the bundled backend in a writable location.
+//!
+//! On an AppImage or a translocated macOS app, the resource dir is a read-only or
+//! randomized mount and the PyInstaller backend fails to extract itself next to the exe.
+//! The `backend/` resource folder is then synced to `<app_cache_dir>/backend/<version>/`
+//! and the backend is spawned from there.
+
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const STAGING_DIR_NAME: &str = "backend";
+const MANIFEST_NAME: &str = "manifest.json";
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Written last into a staged copy: the copy is complete and matches `fingerprint`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: String,
+    fingerprint: String,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    path: String,
+    len: u64,
+}
+
+/// FNV-1a; unlike `DefaultHasher` its output is stable across Rust versions, so a
+/// rebuilt app doesn't recopy an unchanged backend.
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Whether the backend should run from a staged copy: the `stage_backend` setting if set,
+/// otherwise only when the resource dir is known to be read-only or randomized.
+pub(crate) fn enabled(app: &AppHandle, setting: Option<bool>) -> bool {
+    setting.unwrap_or_else(|| {
+        if std::env::var_os("APPIMAGE").is_some() {
+            return true;
+        }
+        app.path()
+            .resource_dir()
+            .is_ok_and(|dir| dir.to_string_lossy().contains("/AppTranslocation/"))
+    })
+}
+
+fn staging_root(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join(STAGING_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app cache dir: {e}"))
+}
+
+fn version_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(staging_root(app)?.join(app.package_info().version.to_string()))
+}
+
+/// The staged copy for this app version, if a complete one exists.
+pub(crate) fn current_dir(app: &AppHandle) -> Option<PathBuf> {
+    let dir = version_dir(app).ok()?;
+    dir.join(MANIFEST_NAME).is_file().then_some(dir)
+}
+
+/// Files under `root` (relative path, metadata), without following symlinks.
+fn list_files(root: &Path, dir: &Path, out: &mut Vec<(PathBuf, std::fs::Metadata)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            list_files(root, &path, out);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push((relative.to_path_buf(), meta));
+        }
+    }
+}
+
+/// Fingerprint of the bundled backend over each file's path, size and mtime. Hashing the
+/// contents would mean reading the whole multi-hundred-MB bundle on every launch.
+fn fingerprint(files: &[(PathBuf, std::fs::Metadata)]) -> String {
+    let mut hasher = Fnv1a(0xcbf2_9ce4_8422_2325);
+    for (path, meta) in files {
+        hasher.write(path.to_string_lossy().as_bytes());
+        hasher.write(&meta.len().to_le_bytes());
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        hasher.write(&mtime.to_le_bytes());
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let contents = std::fs::read_to_string(dir.join(MANIFEST_NAME)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn copy_entry(from: &Path, to: &Path, meta: &std::fs::Metadata) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    #[cfg(unix)]
+    if meta.file_type().is_symlink() {
+        // macOS frameworks inside the bundle rely on relative symlinks.
+        return std::os::unix::fs::symlink(std::fs::read_link(from)?, to);
+    }
+    #[cfg(not(unix))]
+    let _ = meta;
+    // fs::copy keeps the permission bits, including the executable bit.
+    std::fs::copy(from, to).map(|_| ())
+}
+
+/// Make sure the staged copy matches the bundled backend in `bundled_dir`, copying it if
+/// not, and remove copies left by other app versions. Returns the staged directory.
+pub(crate) fn sync(app: &AppHandle, bundled_dir: &Path) -> Result<PathBuf, String> {
+    let dest = version_dir(app)?;
+    let mut files = Vec::new();
+    list_files(bundled_dir, bundled_dir, &mut files);
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let fingerprint = fingerprint(&files);
+
+    if read_manifest(&dest).is_some_and(|m| m.fingerprint == fingerprint) {
+        prune(app, &dest);
+        return Ok(dest);
+    }
+
+    log::info!(
+        "Staging backend ({} files) into {}",
+        files.len(),
+        dest.display()
+    );
+    let mut partial = dest.clone().into_os_string();
+    partial.push(PARTIAL_SUFFIX);
+    let partial = PathBuf::from(partial);
+    let _ = std::fs::remove_dir_all(&partial);
+
+    for (relative, meta) in &files {
+        copy_entry(&bundled_dir.join(relative), &partial.join(relative), meta)
+            .map_err(|e| format!("Failed to stage {}: {e}", relative.display()))?;
+    }
+    let manifest = Manifest {
+        version: app.package_info().version.to_string(),
+        fingerprint,
+        files: files
+            .iter()
+            .map(|(path, meta)| ManifestEntry {
+                path: path.to_string_lossy().into_owned(),
+                len: meta.len(),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(partial.join(MANIFEST_NAME), json)
+        .map_err(|e| format!("Failed to write staging manifest: {e}"))?;
+
+    let _ = std::fs::remove_dir_all(&dest);
+    std::fs::rename(&partial, &dest)
+        .map_err(|e| format!("Failed to move staged backend into place: {e}"))?;
+    prune(app, &dest);
+    Ok(dest)
+}
+
+/// Remove staged copies other than `keep`.
+fn prune(app: &AppHandle, keep: &Path) {
+    let Ok(root) = staging_root(app) else { return };
+    let Ok(entries) = std::fs::read_dir(&root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path != keep && path.is_dir() {
+            log::info!("Removing stale staged backend {}", path.display());
+            if let Err(e) = std::fs::remove_dir_all(&path) {
+                log::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! How long backend launches take, phase by phase, kept across app restarts.
+//!
+//! Each launch records when the process was spawned, the port opened, `/health` first
+//! answered 200, `/ready` passed and `backend-ready` went out, all relative to the start
+//! of the launch. The last `HISTORY_LEN` runs are saved to `startup_metrics.json`.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::unix_millis;
+
+pub(crate) const FILE_NAME: &str = "startup_metrics.json";
+const HISTORY_LEN: usize = 20;
+
+/// Points of a launch that get a timestamp.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Milestone {
+    ProcessLaunched,
+    PortOpen,
+    FirstHealthy,
+    Ready,
+    ReadyEmitted,
+}
+
+/// How a launch ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Outcome {
+    Ready,
+    /// The process could not be spawned.
+    SpawnFailed,
+    /// Spawned, but it never became ready or exited first.
+    NotReady,
+    /// Its API version is not one this app accepts.
+    Incompatible,
+    /// Stopped or quit before it finished.
+    Cancelled,
+}
+
+/// One launch. Milestones are milliseconds after `started_at_ms`, `None` if not reached.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct StartupRun {
+    pub started_at_ms: u64,
+    /// Attached to a backend someone else runs, so nothing was spawned.
+    pub external: bool,
+    pub outcome: Option<Outcome>,
+    pub process_launched_ms: Option<u64>,
+    pub port_open_ms: Option<u64>,
+    pub first_healthy_ms: Option<u64>,
+    pub ready_ms: Option<u64>,
+    pub ready_emitted_ms: Option<u64>,
+}
+
+/// Payload of `get_startup_metrics`, and the contents of the metrics file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct StartupHistory {
+    /// Oldest first; the last one is the latest finished launch.
+    pub runs: Vec<StartupRun>,
+}
+
+/// The launch in progress and the saved history, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct StartupMetrics(Mutex<Metrics>);
+
+#[derive(Default)]
+struct Metrics {
+    current: Option<(Instant, StartupRun)>,
+    history: StartupHistory,
+}
+
+fn file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Read the saved history at launch; a missing or unreadable file starts a new one.
+pub(crate) fn load(app: &AppHandle) {
+    let Some(contents) = file_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+    else {
+        return;
+    };
+    match serde_json::from_str(&contents) {
+        Ok(history) => app.state::<StartupMetrics>().0.lock().unwrap().history = history,
+        Err(e) => log::warn!("Ignoring unreadable startup metrics: {}", e),
+    }
+}
+
+/// Start timing a launch, dropping one that never finished.
+pub(crate) fn begin(app: &AppHandle, external: bool) {
+    let run = StartupRun {
+        started_at_ms: unix_millis(SystemTime::now()),
+        external,
+        ..StartupRun::default()
+    };
+    app.state::<StartupMetrics>().0.lock().unwrap().current = Some((Instant::now(), run));
+}
+
+/// Record `milestone` for the launch in progress, unless it was already reached.
+pub(crate) fn mark(app: &AppHandle, milestone: Milestone) {
+    let state = app.state::<StartupMetrics>();
+    let mut metrics = state.0.lock().unwrap();
+    let Some((started, run)) = metrics.current.as_mut() else {
+        return;
+    };
+    let slot = match milestone {
+        Milestone::ProcessLaunched => &mut run.process_launched_ms,
+        Milestone::PortOpen => &mut run.port_open_ms,
+        Milestone::FirstHealthy => &mut run.first_healthy_ms,
+        Milestone::Ready => &mut run.ready_ms,
+        Milestone::ReadyEmitted => &mut run.ready_emitted_ms,
+    };
+    slot.get_or_insert(started.elapsed().as_millis() as u64);
+}
+
+/// End the launch in progress with `outcome` and save it to the history.
+pub(crate) fn finish(app: &AppHandle, outcome: Outcome) {
+    let history = {
+        let state = app.state::<StartupMetrics>();
+        let mut metrics = state.0.lock().unwrap();
+        let Some((_, mut run)) = metrics.current.take() else {
+            return;
+        };
+        run.outcome = Some(outcome);
+        let runs = &mut metrics.history.runs;
+        runs.push(run);
+        if runs.len() > HISTORY_LEN {
+            runs.drain(..runs.len() - HISTORY_LEN);
+        }
+        metrics.history.clone()
+    };
+    let result = file_path(app).and_then(|path| {
+        let json = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save startup metrics: {}", e);
+    }
+}
+
+pub(crate) fn history(app: &AppHandle) -> StartupHistory {
+    app.state::<StartupMetrics>()
+        .0
+        .lock()
+        .unwrap()
+        .history
+        .clone()
+}
+
+/// The latest finished launch.
+pub(crate) fn latest(app: &AppHandle) -> Option<StartupRun> {
+    history(app).runs.pop()
+}
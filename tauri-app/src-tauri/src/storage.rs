@@ -0,0 +1,178 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `get_storage_usage`: what the app's data takes up on disk, by category.
+//!
+//! `DATA_DIR` is sorted by the backend's layout, and the log and temp dirs are added when
+//! they are elsewhere. Links are never followed, so nothing outside these roots is
+//! counted. The walk can take a while on a spinning disk, so its result is kept for
+//! `CACHE_TTL`; free space is measured anew each time.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{backup, datadir, diskspace, purge, tempdir, unix_millis};
+
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Payload of `get_storage_usage`, in bytes.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct StorageUsage {
+    pub data_dir: PathBuf,
+    /// The SQLite database and its journals.
+    pub database_bytes: u64,
+    /// Uploaded invoices and the documents kept for them.
+    pub originals_bytes: u64,
+    /// Page renders and downloaded OCR models, which are fetched or rebuilt when missing.
+    pub cache_bytes: u64,
+    /// The shell's and the backend's logs, rotated copies included.
+    pub log_bytes: u64,
+    /// Intermediates of jobs, in the backend's temp folder and the shell's temp dir.
+    pub temp_bytes: u64,
+    pub other_bytes: u64,
+    pub total_bytes: u64,
+    /// On the volume holding `data_dir`; `None` when it couldn't be measured in time.
+    pub free_bytes: Option<u64>,
+    /// When the walk behind these sizes was done.
+    pub measured_ms: u64,
+}
+
+#[derive(Clone, Copy)]
+enum Category {
+    Database,
+    Originals,
+    Cache,
+    Logs,
+    Temp,
+    Other,
+}
+
+/// The last walk, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct StorageCache(Mutex<Option<(Instant, StorageUsage)>>);
+
+impl StorageUsage {
+    fn add(&mut self, category: Category, bytes: u64) {
+        *match category {
+            Category::Database => &mut self.database_bytes,
+            Category::Originals => &mut self.originals_bytes,
+            Category::Cache => &mut self.cache_bytes,
+            Category::Logs => &mut self.log_bytes,
+            Category::Temp => &mut self.temp_bytes,
+            Category::Other => &mut self.other_bytes,
+        } += bytes;
+        self.total_bytes += bytes;
+    }
+}
+
+/// Where each top-level entry of `DATA_DIR` goes, after the backend's `config.py`.
+fn category(name: &OsStr) -> Category {
+    match name.to_str() {
+        Some("data") => Category::Database,
+        Some("uploads" | "documents") => Category::Originals,
+        Some("models") => Category::Cache,
+        Some("logs") => Category::Logs,
+        Some(tempdir::TEMP_DIR_NAME) => Category::Temp,
+        _ => Category::Other,
+    }
+}
+
+/// Usage of the app's data, from the last minute's walk unless `refresh`.
+pub(crate) async fn usage(app: &AppHandle, refresh: bool) -> Result<StorageUsage, String> {
+    let cached = app
+        .state::<StorageCache>()
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .filter(|(at, _)| !refresh && at.elapsed() < CACHE_TTL);
+    let mut usage = match cached {
+        Some((_, usage)) => usage,
+        None => {
+            let data_dir = datadir::path(app)?;
+            let log_dir = app.path().app_log_dir().ok();
+            let temp_dir = tempdir::path(app).ok();
+            let usage =
+                tauri::async_runtime::spawn_blocking(move || measure(data_dir, log_dir, temp_dir))
+                    .await
+                    .map_err(|e| format!("Storage usage scan failed: {e}"))?;
+            *app.state::<StorageCache>().0.lock().unwrap() = Some((Instant::now(), usage.clone()));
+            usage
+        }
+    };
+    usage.free_bytes = diskspace::probe(app).await?.free_bytes;
+    Ok(usage)
+}
+
+/// Drop the last walk, after deleting enough to make it misleading.
+pub(crate) fn forget(app: &AppHandle) {
+    *app.state::<StorageCache>().0.lock().unwrap() = None;
+}
+
+fn measure(data_dir: PathBuf, log_dir: Option<PathBuf>, temp_dir: Option<PathBuf>) -> StorageUsage {
+    let mut usage = StorageUsage {
+        measured_ms: unix_millis(SystemTime::now()),
+        ..StorageUsage::default()
+    };
+    if let Ok(entries) = fs::read_dir(&data_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if name == backup::BACKEND_TEMP {
+                // The backend's renders sit among its job files.
+                walk(&entry.path(), &mut usage, &|name| {
+                    if purge::is_render(name) {
+                        Category::Cache
+                    } else {
+                        Category::Temp
+                    }
+                });
+            } else {
+                let category = category(&name);
+                walk(&entry.path(), &mut usage, &|_| category);
+            }
+        }
+    }
+    // Counted above when they are in the data dir.
+    for (dir, category) in [(log_dir, Category::Logs), (temp_dir, Category::Temp)] {
+        if let Some(dir) = dir.filter(|dir| !dir.starts_with(&data_dir)) {
+            walk(&dir, &mut usage, &|_| category);
+        }
+    }
+    usage.data_dir = data_dir;
+    usage
+}
+
+/// Add the file at `path`, or the files below it, to `usage` under what `category` makes
+/// of each file name. Links are left out, whatever they point to.
+fn walk(path: &Path, usage: &mut StorageUsage, category: &dyn Fn(&OsStr) -> Category) {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if meta.is_file() {
+        let name = path.file_name().unwrap_or_default();
+        usage.add(category(name), meta.len());
+    } else if meta.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            walk(&entry.path(), usage, category);
+        }
+    }
+}
@@ -0,0 +1,1102 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backend lifecycle coordination: start, stop, restart and recovery after a crash.
+//!
+//! Every path that spawns or stops the backend holds the lifecycle lock, so they can
+//! never race on `BackendProcess`. Stops cancel in-flight waits through the stop signal.
+
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::TerminatedPayload;
+use tokio::sync::watch;
+
+use crate::backendlog;
+use crate::config::{self, AppConfig};
+use crate::datalock;
+use crate::endpoint::{self, Connection};
+use crate::events;
+use crate::health::{BackendHealth, HealthCheck, HealthLatency};
+use crate::jobevents;
+use crate::monitor::BackendCrash;
+use crate::proxy::TimeoutClass;
+use crate::startupmetrics::{self, Milestone, Outcome};
+use crate::suspend;
+use crate::telemetry;
+use crate::transport::{self, TransportKind};
+use crate::version::{self, VersionMismatch};
+use crate::wake;
+use crate::{
+    health, launch_backend, shutdown_backend, unix_millis, wait_for_backend_ready, BackendProcess,
+    SHUTDOWN_GRACE_PERIOD,
+};
+
+const INITIAL_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+
+/// When the backend crashes this often, it is considered broken and no longer restarted.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CrashLoopPolicy {
+    pub max_crashes: usize,
+    pub window: Duration,
+}
+
+impl CrashLoopPolicy {
+    pub(crate) const DEFAULT: Self = Self {
+        max_crashes: 5,
+        window: Duration::from_secs(120),
+    };
+}
+
+/// How often the watchdog pings a running backend and how many misses trigger a restart.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WatchdogConfig {
+    pub interval: Duration,
+    pub max_failures: u32,
+}
+
+impl WatchdogConfig {
+    pub(crate) const DEFAULT: Self = Self {
+        interval: Duration::from_secs(15),
+        max_failures: 4,
+    };
+}
+
+/// Payload of the `backend-health-changed` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct HealthChanged {
+    pub connection: Connection,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// The poll that brought the change.
+    pub check: HealthCheck,
+}
+
+/// Payload of the `backend-ready` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BackendReady {
+    /// Where the backend listens; the port is picked at launch, never assume 8000.
+    pub url: String,
+    /// Spawn-to-ready time, when the event ends a launch rather than e.g. a resume.
+    pub startup_ms: Option<u64>,
+    /// What `/health` reported, so the UI learns the backend version without asking.
+    pub health: Option<BackendHealth>,
+    /// How the shell reaches the backend. The webview always uses `url` over TCP, which the
+    /// backend keeps open next to a socket.
+    pub transport: TransportKind,
+}
+
+impl BackendReady {
+    pub(crate) fn new(
+        app: &AppHandle,
+        startup_ms: Option<u64>,
+        health: Option<BackendHealth>,
+    ) -> Self {
+        Self {
+            url: endpoint::base_url(app),
+            startup_ms,
+            health,
+            transport: endpoint::transport(app).kind(),
+        }
+    }
+}
+
+/// Payload of the `backend-fatal` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BackendFatal {
+    pub reason: String,
+    pub crashes: Vec<BackendCrash>,
+}
+
+/// Crashes inside the policy window, and the fatal verdict once the policy tripped.
+#[derive(Debug)]
+struct CrashHistory {
+    policy: CrashLoopPolicy,
+    recent: VecDeque<(Instant, BackendCrash)>,
+    fatal: Option<BackendFatal>,
+}
+
+impl CrashHistory {
+    fn new(policy: CrashLoopPolicy) -> Self {
+        Self {
+            policy,
+            recent: VecDeque::new(),
+            fatal: None,
+        }
+    }
+
+    /// Record a crash at `now`, returning the fatal verdict if the policy just tripped.
+    fn record(&mut self, crash: BackendCrash, now: Instant) -> Option<BackendFatal> {
+        while let Some((at, _)) = self.recent.front() {
+            if now.duration_since(*at) > self.policy.window {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.recent.push_back((now, crash));
+
+        if self.fatal.is_none() && self.recent.len() >= self.policy.max_crashes {
+            let fatal = BackendFatal {
+                reason: format!(
+                    "Backend crashed {} times within {}s; automatic restarts stopped",
+                    self.recent.len(),
+                    self.policy.window.as_secs()
+                ),
+                crashes: self.recent.iter().map(|(_, c)| c.clone()).collect(),
+            };
+            self.fatal = Some(fatal.clone());
+            return Some(fatal);
+        }
+        None
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+        self.fatal = None;
+    }
+}
+
+/// Restart counters reported to the frontend.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RestartStats {
+    pub count: u32,
+    pub last_restart_at: Option<SystemTime>,
+}
+
+/// Where the managed backend is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SpawnState {
+    NotStarted,
+    Starting,
+    Running,
+    Stopped,
+}
+
+impl SpawnState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::NotStarted,
+            1 => Self::Starting,
+            2 => Self::Running,
+            _ => Self::Stopped,
+        }
+    }
+}
+
+/// What the frontend shows about the backend. Every change is emitted as `backend-status`,
+/// so the UI can fetch it once with `get_backend_status` and then follow events.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum BackendStatus {
+    NotStarted,
+    /// `attempt` counts launches this session, restarts included.
+    Starting {
+        attempt: u32,
+    },
+    Ready {
+        since_ms: u64,
+    },
+    /// Running but failing health checks.
+    Unresponsive,
+    /// Failing health checks right after the machine woke from sleep; not restarted yet.
+    Recovering,
+    Crashed {
+        code: Option<i32>,
+    },
+    Stopped,
+    /// Crashed too often; no longer restarted until the failure state is reset.
+    Fatal,
+}
+
+/// Payload of the `backend-status` event: the status and how the backend is reached.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct StatusReport {
+    #[serde(flatten)]
+    pub status: BackendStatus,
+    pub connection: Connection,
+}
+
+/// Shared lifecycle state: one start/stop/restart at a time, no restarts once the app is
+/// exiting or the backend has been declared broken.
+pub(crate) struct Supervisor {
+    lifecycle: tokio::sync::Mutex<()>,
+    /// A `SpawnState` as `u8`, so claiming a start is a single compare-and-swap.
+    state: AtomicU8,
+    stop_tx: watch::Sender<u64>,
+    /// Set once the app is quitting; long-running tasks watch it to bail out promptly.
+    exiting: watch::Sender<bool>,
+    /// Frozen by `suspend_backend`; health checks would only time out.
+    suspended: AtomicBool,
+    /// Set by `pause_health_monitor`. The watchdog shares the monitor's poll, so this also
+    /// stops unresponsive-backend restarts.
+    monitor_paused: AtomicBool,
+    monitor_interval: watch::Sender<Duration>,
+    stats: Mutex<RestartStats>,
+    spawned_at: Mutex<Option<SystemTime>>,
+    last_health_ok: Mutex<Option<bool>>,
+    last_startup: Mutex<Option<Duration>>,
+    /// API version reported by the running backend during the handshake.
+    backend_version: Mutex<Option<String>>,
+    last_exit: Mutex<Option<TerminatedPayload>>,
+    /// A watch channel so `wait_for_backend` callers can await the next change.
+    status: watch::Sender<BackendStatus>,
+    crashes: Mutex<CrashHistory>,
+}
+
+/// Held for the duration of a start, stop or restart.
+pub(crate) type LifecycleGuard<'a> = tokio::sync::MutexGuard<'a, ()>;
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::with_policy(CrashLoopPolicy::DEFAULT)
+    }
+}
+
+impl Supervisor {
+    pub(crate) fn with_policy(policy: CrashLoopPolicy) -> Self {
+        Self {
+            lifecycle: tokio::sync::Mutex::new(()),
+            state: AtomicU8::new(SpawnState::NotStarted as u8),
+            stop_tx: watch::Sender::new(0),
+            exiting: watch::Sender::new(false),
+            suspended: AtomicBool::new(false),
+            monitor_paused: AtomicBool::new(false),
+            monitor_interval: watch::Sender::new(WatchdogConfig::DEFAULT.interval),
+            stats: Mutex::new(RestartStats::default()),
+            spawned_at: Mutex::new(None),
+            last_health_ok: Mutex::new(None),
+            last_startup: Mutex::new(None),
+            backend_version: Mutex::new(None),
+            last_exit: Mutex::new(None),
+            status: watch::Sender::new(BackendStatus::NotStarted),
+            crashes: Mutex::new(CrashHistory::new(policy)),
+        }
+    }
+
+    /// Claim the lifecycle lock, or `None` if a start, stop or restart is already running.
+    pub(crate) fn try_lock_lifecycle(&self) -> Option<LifecycleGuard<'_>> {
+        self.lifecycle.try_lock().ok()
+    }
+
+    /// Wait for the lifecycle lock. Pair with [`Self::request_stop`] to preempt a restart.
+    pub(crate) async fn lock_lifecycle(&self) -> LifecycleGuard<'_> {
+        self.lifecycle.lock().await
+    }
+
+    pub(crate) fn state(&self) -> SpawnState {
+        SpawnState::from_u8(self.state.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn set_state(&self, state: SpawnState) {
+        self.state.store(state as u8, Ordering::SeqCst);
+    }
+
+    /// Move from `NotStarted` or `Stopped` to `Starting`. Fails with the current state if a
+    /// spawn is already underway or the backend is running.
+    pub(crate) fn try_begin_start(&self) -> Result<(), SpawnState> {
+        let mut current = self.state.load(Ordering::SeqCst);
+        loop {
+            match SpawnState::from_u8(current) {
+                SpawnState::NotStarted | SpawnState::Stopped => {}
+                state => return Err(state),
+            }
+            match self.state.compare_exchange(
+                current,
+                SpawnState::Starting as u8,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return Ok(()),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Cancel readiness waits and restart backoffs that are currently in flight.
+    pub(crate) fn request_stop(&self) {
+        self.stop_tx.send_modify(|generation| *generation += 1);
+    }
+
+    /// A receiver whose `changed()` resolves on the next [`Self::request_stop`].
+    pub(crate) fn stop_signal(&self) -> watch::Receiver<u64> {
+        self.stop_tx.subscribe()
+    }
+
+    pub(crate) fn record_restart(&self) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.count += 1;
+        stats.last_restart_at = Some(SystemTime::now());
+    }
+
+    pub(crate) fn stats(&self) -> RestartStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Record when the current backend process was spawned; `None` once it is gone.
+    pub(crate) fn set_spawned_at(&self, at: Option<SystemTime>) {
+        *self.spawned_at.lock().unwrap() = at;
+    }
+
+    pub(crate) fn spawned_at(&self) -> Option<SystemTime> {
+        *self.spawned_at.lock().unwrap()
+    }
+
+    pub(crate) fn record_health(&self, healthy: bool) {
+        *self.last_health_ok.lock().unwrap() = Some(healthy);
+    }
+
+    /// Result of the most recent health check, `None` before the first one.
+    pub(crate) fn last_health_ok(&self) -> Option<bool> {
+        *self.last_health_ok.lock().unwrap()
+    }
+
+    /// Record how long the latest launch took from spawn to ready.
+    pub(crate) fn record_startup(&self, duration: Duration) {
+        *self.last_startup.lock().unwrap() = Some(duration);
+    }
+
+    pub(crate) fn last_startup(&self) -> Option<Duration> {
+        *self.last_startup.lock().unwrap()
+    }
+
+    pub(crate) fn record_backend_version(&self, version: Option<String>) {
+        *self.backend_version.lock().unwrap() = version;
+    }
+
+    pub(crate) fn backend_version(&self) -> Option<String> {
+        self.backend_version.lock().unwrap().clone()
+    }
+
+    pub(crate) fn status(&self) -> BackendStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Wait until the backend is ready or declared broken, at most `timeout`, and return
+    /// the status at that point. Any number of callers can wait at once.
+    pub(crate) async fn wait_until_settled(&self, timeout: Duration) -> BackendStatus {
+        let mut status = self.status.subscribe();
+        let settled =
+            status.wait_for(|s| matches!(s, BackendStatus::Ready { .. } | BackendStatus::Fatal));
+        let settled = match tokio::time::timeout(timeout, settled).await {
+            Ok(Ok(s)) => Some(s.clone()),
+            _ => None,
+        };
+        settled.unwrap_or_else(|| self.status())
+    }
+
+    /// Remember how the most recent backend process ended, expected or not.
+    pub(crate) fn record_exit(&self, status: TerminatedPayload) {
+        *self.last_exit.lock().unwrap() = Some(status);
+    }
+
+    pub(crate) fn last_exit(&self) -> Option<TerminatedPayload> {
+        self.last_exit.lock().unwrap().clone()
+    }
+
+    /// Mark the app as exiting so no further restarts are attempted.
+    pub(crate) fn set_exiting(&self) {
+        self.exiting.send_replace(true);
+    }
+
+    pub(crate) fn is_exiting(&self) -> bool {
+        *self.exiting.borrow()
+    }
+
+    /// Resolves once the app starts quitting.
+    pub(crate) fn exited(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut exiting = self.exiting.subscribe();
+        async move {
+            let _ = exiting.wait_for(|exiting| *exiting).await;
+        }
+    }
+
+    pub(crate) fn set_suspended(&self, suspended: bool) {
+        self.suspended.store(suspended, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.suspended.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_monitor_paused(&self, paused: bool) {
+        self.monitor_paused.store(paused, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_monitor_paused(&self) -> bool {
+        self.monitor_paused.load(Ordering::SeqCst)
+    }
+
+    /// Change the health poll interval; a monitor waiting on the old one wakes up right away.
+    pub(crate) fn set_monitor_interval(&self, interval: Duration) {
+        self.monitor_interval.send_replace(interval);
+    }
+
+    /// Record an unexpected exit. Returns the fatal verdict if this crash tripped the policy.
+    pub(crate) fn record_crash(&self, crash: BackendCrash) -> Option<BackendFatal> {
+        self.crashes.lock().unwrap().record(crash, Instant::now())
+    }
+
+    pub(crate) fn is_fatal(&self) -> bool {
+        self.crashes.lock().unwrap().fatal.is_some()
+    }
+
+    /// Forget past crashes so automatic restarts are allowed again.
+    pub(crate) fn reset_failures(&self) {
+        self.crashes.lock().unwrap().reset();
+    }
+}
+
+/// Delay before restart `attempt` (0-based): 1s, 2s, 4s, ... with ±10% jitter, capped at
+/// 30s.
+fn restart_delay(attempt: u32) -> Duration {
+    config::jitter(backoff(attempt)).min(MAX_RESTART_DELAY)
+}
+
+/// `restart_delay` before jitter.
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_RESTART_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_RESTART_DELAY)
+}
+
+/// Payload of the `backend-error` event, tagged by `kind` so the UI can offer the right fix.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum StartupError {
+    /// Another process already listens on the backend port.
+    PortInUse {
+        port: u16,
+        occupant: Option<String>,
+        message: String,
+    },
+    /// Spawning failed or the backend never became ready.
+    Failed { message: String },
+    /// macOS refused to run the bundled backend. `code` is stable, for support links.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Gatekeeper { code: &'static str, message: String },
+    /// Another instance of the app holds the data dir; see `data-dir-locked`.
+    DataDirLocked { pid: Option<u32>, message: String },
+}
+
+impl std::fmt::Display for StartupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PortInUse { message, .. }
+            | Self::Failed { message }
+            | Self::Gatekeeper { message, .. }
+            | Self::DataDirLocked { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for StartupError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+/// What `backend-error` carries: the error, and what the backend printed before it.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct BackendError {
+    #[serde(flatten)]
+    pub error: StartupError,
+    /// The last lines of the backend's output, redacted and capped in size; empty when
+    /// nothing was spawned.
+    pub recent_output: Vec<String>,
+    /// Set when the process had already exited.
+    pub exit_code: Option<i32>,
+}
+
+impl BackendError {
+    /// `error` with no backend output, for failures before a process existed.
+    pub(crate) fn bare(error: StartupError) -> Self {
+        Self {
+            error,
+            recent_output: Vec::new(),
+            exit_code: None,
+        }
+    }
+
+    /// `error` with the output of the backend whose exit status is published on `exit`.
+    fn with_output(
+        app: &AppHandle,
+        error: StartupError,
+        exit: &watch::Receiver<Option<TerminatedPayload>>,
+    ) -> Self {
+        Self {
+            error,
+            recent_output: backendlog::recent_output(app),
+            exit_code: exit.borrow().as_ref().and_then(|status| status.code),
+        }
+    }
+}
+
+/// Outcome of launching the backend and waiting for it to answer.
+#[derive(Debug)]
+pub(crate) enum Startup {
+    Ready,
+    /// Never answered in time; the process was stopped.
+    NotReady,
+    Cancelled,
+    /// Another entry point already started the backend; nothing was spawned.
+    AlreadyStarted,
+    /// The backend answered with an incompatible API version and was stopped.
+    Incompatible,
+}
+
+/// Launch the backend and wait until it is ready, emitting `backend-ready` or `backend-error`
+/// (`backend-version-mismatch` if it speaks an incompatible API).
+///
+/// The caller must hold the lifecycle lock. `stop` should be subscribed before the caller
+/// took the lock, so a stop requested in between still cancels the wait.
+#[tracing::instrument(
+    name = "backend_launch",
+    skip_all,
+    fields(attempt, external, elapsed_ms)
+)]
+pub(crate) async fn launch_and_wait(
+    app: &AppHandle,
+    stop: &mut watch::Receiver<u64>,
+) -> Result<Startup, String> {
+    let supervisor = app.state::<Supervisor>();
+    if let Err(state) = supervisor.try_begin_start() {
+        log::info!("Backend spawn skipped, already {:?}", state);
+        return Ok(Startup::AlreadyStarted);
+    }
+    let attempt = supervisor.stats().count + 1;
+    let span = tracing::Span::current();
+    span.record("attempt", attempt);
+    set_status(app, BackendStatus::Starting { attempt });
+    let launched = Instant::now();
+    let external = endpoint::external_url(app);
+    startupmetrics::begin(app, external.is_some());
+    span.record("external", external.is_some());
+    let exit = match external {
+        // Someone else runs this backend: nothing to spawn, go straight to polling it.
+        Some(url) => {
+            log::info!("Attaching to external backend at {}", url);
+            Ok(None)
+        }
+        None => launch_backend(app).await.map(Some),
+    };
+    let exit = match exit {
+        Ok(exit) => exit,
+        Err(e) => {
+            log::error!("Failed to spawn backend: {}", e);
+            startupmetrics::finish(app, Outcome::SpawnFailed);
+            supervisor.set_state(SpawnState::Stopped);
+            set_status(app, BackendStatus::Stopped);
+            let message = e.to_string();
+            events::emit(app, "backend-error", BackendError::bare(e));
+            return Err(message);
+        }
+    };
+    if supervisor.is_exiting() {
+        // Spawned while the exit path was already tearing down: nobody else will stop it.
+        log::info!("Backend start cancelled: app is quitting");
+        startupmetrics::finish(app, Outcome::Cancelled);
+        stop_current(app).await;
+        return Ok(Startup::Cancelled);
+    }
+    if exit.is_some() {
+        log::info!("Backend process spawned, waiting for ready...");
+    }
+
+    let readiness = app.state::<AppConfig>().readiness;
+    tokio::select! {
+        result = wait_for_backend_ready(app, exit.as_ref(), &readiness) => match result {
+            Ok((_, health)) => {
+                let backend_version = version::backend_version(&endpoint::transport(app)).await.unwrap_or_else(|e| {
+                    log::warn!("Backend version check failed: {}", e);
+                    None
+                });
+                supervisor.record_backend_version(backend_version.clone());
+                match &backend_version {
+                    Some(v) if !version::is_compatible(v) => {
+                        log::error!(
+                            "Backend API version {} is not compatible with {}",
+                            v,
+                            version::expected()
+                        );
+                        let mismatch = VersionMismatch {
+                            expected: version::expected(),
+                            backend: v.clone(),
+                        };
+                        events::emit(app, "backend-version-mismatch", mismatch);
+                        startupmetrics::finish(app, Outcome::Incompatible);
+                        stop_current(app).await;
+                        return Ok(Startup::Incompatible);
+                    }
+                    Some(_) => {}
+                    None => log::warn!("Backend did not report its version, skipping the handshake"),
+                }
+                let startup = launched.elapsed();
+                let startup_ms = startup.as_millis() as u64;
+                span.record("elapsed_ms", startup_ms);
+                tracing::info!(attempt, elapsed_ms = startup_ms, "Backend is ready");
+                supervisor.record_health(true);
+                supervisor.record_startup(startup);
+                telemetry::record(app, telemetry::EventKind::BackendReady { startup_ms, attempt });
+                supervisor.set_state(SpawnState::Running);
+                set_status(app, ready_now());
+                let ready =
+                    BackendReady::new(app, Some(startup_ms), Some(health));
+                events::emit(app, "backend-ready", ready);
+                startupmetrics::mark(app, Milestone::ReadyEmitted);
+                startupmetrics::finish(app, Outcome::Ready);
+                Ok(Startup::Ready)
+            }
+            Err(_) if supervisor.is_exiting() => {
+                log::info!("Backend readiness wait cancelled: app is quitting");
+                startupmetrics::finish(app, Outcome::Cancelled);
+                Ok(Startup::Cancelled)
+            }
+            Err(e) => {
+                log::error!("Backend failed to start: {}", e);
+                startupmetrics::finish(app, Outcome::NotReady);
+                supervisor.record_health(false);
+                let error = match &exit {
+                    Some(exit) => BackendError::with_output(app, e, exit),
+                    None => BackendError::bare(e),
+                };
+                // Left `Starting`, nothing would ever retry or stop it.
+                stop_current(app).await;
+                events::emit(app, "backend-error", error);
+                Ok(Startup::NotReady)
+            }
+        },
+        _ = stop.changed() => {
+            log::info!("Backend readiness wait cancelled");
+            startupmetrics::finish(app, Outcome::Cancelled);
+            Ok(Startup::Cancelled)
+        }
+    }
+}
+
+/// Take the backend out of `BackendProcess` and shut it down. The caller must hold the
+/// lifecycle lock.
+pub(crate) async fn stop_current(app: &AppHandle) {
+    let child = app.state::<BackendProcess>().0.lock().unwrap().take();
+    if let Some(child) = child {
+        log::info!("Stopping backend process (pid {})...", child.pid());
+        resume_if_suspended(app, child.pid());
+        shutdown_backend(child, SHUTDOWN_GRACE_PERIOD).await;
+    }
+    let supervisor = app.state::<Supervisor>();
+    supervisor.set_spawned_at(None);
+    supervisor.record_backend_version(None);
+    transport::set_socket(app, None);
+    jobevents::unsubscribe_all(app);
+    datalock::release(app);
+    supervisor.set_state(SpawnState::Stopped);
+    set_status(app, BackendStatus::Stopped);
+}
+
+/// Update the backend status, emitting `backend-status` if it changed.
+pub(crate) fn set_status(app: &AppHandle, status: BackendStatus) {
+    let supervisor = app.state::<Supervisor>();
+    let changed = supervisor.status.send_if_modified(|current| {
+        if *current == status {
+            return false;
+        }
+        *current = status.clone();
+        true
+    });
+    if changed {
+        let _ = app.emit("backend-status", status_report(app, status));
+    }
+}
+
+pub(crate) fn status_report(app: &AppHandle, status: BackendStatus) -> StatusReport {
+    StatusReport {
+        status,
+        connection: endpoint::connection(app),
+    }
+}
+
+/// `Ready` as of now.
+pub(crate) fn ready_now() -> BackendStatus {
+    BackendStatus::Ready {
+        since_ms: unix_millis(SystemTime::now()),
+    }
+}
+
+/// Thaw a suspended backend so it can react to the staged shutdown.
+pub(crate) fn resume_if_suspended(app: &AppHandle, pid: u32) {
+    let supervisor = app.state::<Supervisor>();
+    if supervisor.is_suspended() {
+        if let Err(e) = suspend::resume(pid) {
+            log::warn!("{}", e);
+        }
+        supervisor.set_suspended(false);
+    }
+}
+
+/// Respawn the backend after an unexpected exit, backing off until it becomes ready again.
+///
+/// A crash while a start holds the lifecycle lock, e.g. during the first launch, queues the
+/// restart behind it. It is dropped if that start got the backend up, or was a stop.
+pub(crate) fn restart_after_crash(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let supervisor = app.state::<Supervisor>();
+        let mut stop = supervisor.stop_signal();
+        let _guard = match supervisor.try_lock_lifecycle() {
+            Some(guard) => guard,
+            None => {
+                log::info!("Backend start or restart in progress, restarting after it");
+                let guard = supervisor.lock_lifecycle().await;
+                let superseded = matches!(
+                    supervisor.state(),
+                    SpawnState::Starting | SpawnState::Running
+                );
+                if superseded
+                    || stop.has_changed().unwrap_or(true)
+                    || supervisor.is_exiting()
+                    || supervisor.is_fatal()
+                {
+                    log::info!("Backend restart no longer needed");
+                    return;
+                }
+                guard
+            }
+        };
+
+        for attempt in 0.. {
+            if restart_attempt(&app, &mut stop, attempt).await.is_break() {
+                return;
+            }
+        }
+    });
+}
+
+/// One pass of crash recovery: back off, then launch and wait. Breaks once there is
+/// nothing left to retry.
+#[tracing::instrument(
+    name = "backend_restart",
+    skip_all,
+    fields(attempt = attempt + 1, delay_ms)
+)]
+async fn restart_attempt(
+    app: &AppHandle,
+    stop: &mut watch::Receiver<u64>,
+    attempt: u32,
+) -> ControlFlow<()> {
+    let supervisor = app.state::<Supervisor>();
+    let delay = restart_delay(attempt);
+    tracing::Span::current().record("delay_ms", delay.as_millis() as u64);
+    tracing::info!("Restarting backend in {:?}", delay);
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => {}
+        _ = stop.changed() => {
+            log::info!("Backend restart cancelled");
+            return ControlFlow::Break(());
+        }
+    }
+    if supervisor.is_exiting() || supervisor.is_fatal() {
+        return ControlFlow::Break(());
+    }
+
+    supervisor.record_restart();
+    match launch_and_wait(app, stop).await {
+        Ok(Startup::Ready) => {
+            tracing::info!(
+                restarts = supervisor.stats().count,
+                "Backend recovered after {} attempt(s)",
+                attempt + 1
+            );
+            ControlFlow::Break(())
+        }
+        Ok(Startup::Cancelled | Startup::AlreadyStarted | Startup::Incompatible) => {
+            ControlFlow::Break(())
+        }
+        Ok(Startup::NotReady) | Err(_) => ControlFlow::Continue(()),
+    }
+}
+
+/// Poll the backend's health while it is running, emit `backend-health-changed` when it
+/// turns healthy or unhealthy, and restart it once it stops answering.
+///
+/// The watchdog only acts while the supervisor reports `Running`, and restarts under the
+/// lifecycle lock, so it never competes with a deliberate stop/restart or crash recovery.
+/// A sleep that overran by far means the machine was suspended: failures are forgotten and,
+/// for `wake::GRACE`, reported as `backend-recovering` instead of counted.
+pub(crate) fn start_watchdog(app: AppHandle, config: WatchdogConfig) {
+    tauri::async_runtime::spawn(async move {
+        let supervisor = app.state::<Supervisor>();
+        supervisor.set_monitor_interval(config.interval);
+        let mut interval_rx = supervisor.monitor_interval.subscribe();
+        let mut failures = 0;
+        let exited = supervisor.exited();
+        tokio::pin!(exited);
+
+        loop {
+            let interval = *interval_rx.borrow_and_update();
+            let mark = wake::ClockMark::now();
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = interval_rx.changed() => continue,
+                _ = &mut exited => {
+                    log::info!("Backend watchdog cancelled: app is quitting");
+                    return;
+                }
+            }
+            wake::after_sleep(&app, &mark, interval, &mut failures);
+            if supervisor.state() != SpawnState::Running
+                || supervisor.is_suspended()
+                || supervisor.is_monitor_paused()
+            {
+                failures = 0;
+                continue;
+            }
+
+            let transport = endpoint::transport(&app);
+            let timeout = config::request_timeout(&app, TimeoutClass::Fast);
+            let polled = Instant::now();
+            let check = tokio::select! {
+                check = health::poll(&transport, timeout) => check,
+                _ = &mut exited => {
+                    log::info!("Backend watchdog cancelled: app is quitting");
+                    return;
+                }
+            };
+            let healthy = check.is_healthy();
+            app.state::<HealthLatency>()
+                .record(polled.elapsed(), healthy, false);
+            let was_recovering = supervisor.status() == BackendStatus::Recovering;
+            if !healthy && wake::recovering(&app) {
+                log::info!("Backend health check failed while the system recovers from sleep");
+                if !was_recovering {
+                    set_status(&app, BackendStatus::Recovering);
+                    let recovering = HealthChanged {
+                        connection: endpoint::connection(&app),
+                        healthy,
+                        consecutive_failures: 0,
+                        last_error: check.error(),
+                        check,
+                    };
+                    events::emit(&app, "backend-recovering", recovering);
+                }
+                continue;
+            }
+            let was_healthy = supervisor.last_health_ok();
+            supervisor.record_health(healthy);
+            if healthy {
+                failures = 0;
+            } else if check.warrants_restart() {
+                failures += 1;
+            }
+            if was_healthy != Some(healthy) || was_recovering {
+                set_status(
+                    &app,
+                    if healthy {
+                        ready_now()
+                    } else {
+                        BackendStatus::Unresponsive
+                    },
+                );
+                let changed = HealthChanged {
+                    connection: endpoint::connection(&app),
+                    healthy,
+                    consecutive_failures: failures,
+                    last_error: check.error(),
+                    check: check.clone(),
+                };
+                let _ = app.emit("backend-health-changed", changed);
+            }
+            if healthy {
+                continue;
+            }
+            if !check.warrants_restart() {
+                log::warn!(
+                    "{}; the backend is up, not restarting it",
+                    check.error().unwrap_or_default()
+                );
+                continue;
+            }
+            log::warn!(
+                "Backend health check failed ({}/{})",
+                failures,
+                config.max_failures
+            );
+            if failures < config.max_failures {
+                continue;
+            }
+
+            failures = 0;
+            let mut stop = supervisor.stop_signal();
+            let Some(_guard) = supervisor.try_lock_lifecycle() else {
+                continue;
+            };
+            if supervisor.state() != SpawnState::Running || supervisor.is_suspended() {
+                continue;
+            }
+            if endpoint::external_url(&app).is_some() {
+                log::error!("External backend is unresponsive, reconnecting");
+            } else {
+                log::error!("Backend is unresponsive, restarting it");
+            }
+            events::emit(&app, "backend-unresponsive", ());
+            stop_current(&app).await;
+            supervisor.record_restart();
+            let _ = launch_and_wait(&app, &mut stop).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: CrashLoopPolicy = CrashLoopPolicy {
+        max_crashes: 3,
+        window: Duration::from_secs(60),
+    };
+
+    fn crash(code: i32) -> BackendCrash {
+        BackendCrash {
+            code: Some(code),
+            signal: None,
+            stderr_tail: vec![format!("exit {code}")],
+            recent_output: Vec::new(),
+            memory_limit_mb: None,
+            memory_limit_hit: false,
+        }
+    }
+
+    #[test]
+    fn crashes_inside_the_window_are_fatal_once() {
+        let mut history = CrashHistory::new(POLICY);
+        let start = Instant::now();
+        assert!(history.record(crash(1), start).is_none());
+        assert!(history
+            .record(crash(2), start + Duration::from_secs(20))
+            .is_none());
+        let fatal = history
+            .record(crash(3), start + Duration::from_secs(40))
+            .expect("third crash within 60s trips the policy");
+        let codes: Vec<_> = fatal.crashes.iter().map(|c| c.code).collect();
+        assert_eq!(codes, [Some(1), Some(2), Some(3)]);
+        assert!(
+            fatal.reason.contains("3 times within 60s"),
+            "{}",
+            fatal.reason
+        );
+        // Already fatal: later crashes don't emit the event again.
+        assert!(history
+            .record(crash(4), start + Duration::from_secs(41))
+            .is_none());
+        assert!(history.fatal.is_some());
+    }
+
+    #[test]
+    fn crashes_spread_outside_the_window_are_not_fatal() {
+        let mut history = CrashHistory::new(POLICY);
+        let start = Instant::now();
+        for i in 0..10 {
+            let at = start + Duration::from_secs(35 * i);
+            assert!(history.record(crash(i as i32), at).is_none(), "crash {i}");
+        }
+        assert!(history.fatal.is_none());
+        assert_eq!(history.recent.len(), 2);
+    }
+
+    #[test]
+    fn history_starts_over_after_a_healthy_run() {
+        let mut history = CrashHistory::new(POLICY);
+        let start = Instant::now();
+        history.record(crash(1), start);
+        history.record(crash(2), start + Duration::from_secs(1));
+        // Up for longer than the window: the earlier crashes no longer count.
+        let later = start + Duration::from_secs(1) + POLICY.window + Duration::from_secs(1);
+        assert!(history.record(crash(3), later).is_none());
+        assert!(history
+            .record(crash(4), later + Duration::from_secs(1))
+            .is_none());
+        assert!(history
+            .record(crash(5), later + Duration::from_secs(2))
+            .is_some());
+    }
+
+    #[test]
+    fn reset_allows_restarts_again() {
+        let mut history = CrashHistory::new(POLICY);
+        let start = Instant::now();
+        for i in 0..3 {
+            history.record(crash(i), start + Duration::from_secs(i as u64));
+        }
+        assert!(history.fatal.is_some());
+        history.reset();
+        assert!(history.fatal.is_none());
+        assert!(history.recent.is_empty());
+        let at = start + Duration::from_secs(5);
+        assert!(history.record(crash(9), at).is_none());
+    }
+
+    #[test]
+    fn restart_delay_doubles_up_to_the_cap() {
+        let secs: Vec<u64> = (0..8).map(|attempt| backoff(attempt).as_secs()).collect();
+        assert_eq!(secs, [1, 2, 4, 8, 16, 30, 30, 30]);
+        assert_eq!(backoff(u32::MAX), MAX_RESTART_DELAY);
+    }
+
+    #[test]
+    fn restart_delay_jitter_stays_within_ten_percent_and_the_cap() {
+        for attempt in [0, 1, 3, 5, 40, u32::MAX] {
+            let base = backoff(attempt);
+            for _ in 0..200 {
+                let delay = restart_delay(attempt);
+                assert!(delay >= base * 9 / 10, "attempt {attempt}: {delay:?}");
+                assert!(delay <= base * 11 / 10, "attempt {attempt}: {delay:?}");
+                assert!(delay <= MAX_RESTART_DELAY, "attempt {attempt}: {delay:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn only_one_start_is_claimed() {
+        let supervisor = Supervisor::default();
+        assert_eq!(supervisor.try_begin_start(), Ok(()));
+        assert_eq!(supervisor.try_begin_start(), Err(SpawnState::Starting));
+        supervisor.set_state(SpawnState::Running);
+        assert_eq!(supervisor.try_begin_start(), Err(SpawnState::Running));
+        supervisor.set_state(SpawnState::Stopped);
+        assert_eq!(supervisor.try_begin_start(), Ok(()));
+    }
+
+    #[test]
+    fn concurrent_starts_claim_once() {
+        let supervisor = Supervisor::default();
+        let claimed = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| supervisor.try_begin_start().is_ok()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|&won| won)
+                .count()
+        });
+        assert_eq!(claimed, 1);
+        assert_eq!(supervisor.state(), SpawnState::Starting);
+    }
+}
@@ -0,0 +1,603 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Support bundle: one zip with what we ask users for when something goes wrong.
+//!
+//! It holds the tail of the backend log, a diagnostics report and the settings with
+//! secrets redacted. Invoices, uploads and the database are never read. Entries are
+//! deflated as they are written, and the zip goes to a temp file next to the
+//! destination that is renamed into place once complete.
+//!
+//! `export_logs` writes the same kind of zip with every file of the app log dir instead,
+//! and `backup` uses the writer for data backups.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::{args, backendlog, settings, tls, unix_millis};
+
+/// Backend log lines included.
+const LOG_LINES: usize = 2000;
+
+/// What `create_support_bundle` wrote.
+#[derive(Debug, Serialize)]
+pub(crate) struct SupportBundle {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// What `export_logs` wrote.
+#[derive(Debug, Serialize)]
+pub(crate) struct LogExport {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Files in the zip, in the order they were added.
+    pub files: Vec<ExportedFile>,
+    /// Files that could not be read, with why.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ExportedFile {
+    /// Path inside the log dir, with `/` separators.
+    pub name: String,
+    pub size: u64,
+}
+
+/// Write the bundle to `destination`, or into it under a timestamped name when it is a
+/// directory. `diagnostics` is stored as `diagnostics.json`.
+pub(crate) fn create(
+    app: &AppHandle,
+    destination: &Path,
+    diagnostics: &Value,
+) -> Result<SupportBundle, String> {
+    let now = SystemTime::now();
+    let path = if destination.is_dir() {
+        let (year, month, day, hour, minute, second) = civil(now);
+        destination.join(format!(
+            "invoicator-support-{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}.zip"
+        ))
+    } else {
+        destination.to_path_buf()
+    };
+    let log = backendlog::tail(app, LOG_LINES, None, None);
+    let settings = redacted_settings(app);
+
+    let bytes = write_atomically(&path, now, false, |zip| {
+        zip.entry("backend.log", |out| match &log {
+            Ok(tail) => tail
+                .records
+                .iter()
+                .try_for_each(|record| writeln!(out, "{}", backendlog::format_line(record))),
+            Err(e) => writeln!(out, "Backend log unavailable: {e}"),
+        })?;
+        zip.entry("diagnostics.json", |out| {
+            serde_json::to_writer_pretty(&mut *out, diagnostics).map_err(io::Error::other)
+        })?;
+        zip.entry("settings.json", |out| {
+            serde_json::to_writer_pretty(&mut *out, &settings).map_err(io::Error::other)
+        })
+    })?;
+    log::info!("Wrote support bundle {} ({} bytes)", path.display(), bytes);
+    Ok(SupportBundle { path, bytes })
+}
+
+/// Name the save dialog suggests for `export_logs`, e.g. "invoice-processor-logs-2025-01-31.zip".
+pub(crate) fn default_log_export_name(time: SystemTime) -> String {
+    let (year, month, day, ..) = civil(time);
+    format!("invoice-processor-logs-{year:04}-{month:02}-{day:02}.zip")
+}
+
+/// Name a data backup gets in a chosen folder, e.g. "invoice-processor-backup-2025-01-31.zip".
+pub(crate) fn default_backup_name(time: SystemTime) -> String {
+    let (year, month, day, ..) = civil(time);
+    format!("invoice-processor-backup-{year:04}-{month:02}-{day:02}.zip")
+}
+
+/// Name of a `prepare_for_update` backup, e.g. "pre-update-1.4.0-2025-01-31.zip".
+pub(crate) fn pre_update_backup_name(version: &str, time: SystemTime) -> String {
+    let (year, month, day, ..) = civil(time);
+    format!("pre-update-{version}-{year:04}-{month:02}-{day:02}.zip")
+}
+
+/// Name the save dialog suggests for `export_database`, e.g. "invoice-processor-2025-01-31.db".
+pub(crate) fn default_database_export_name(time: SystemTime) -> String {
+    let (year, month, day, ..) = civil(time);
+    format!("invoice-processor-{year:04}-{month:02}-{day:02}.db")
+}
+
+/// Zip every file in the app log dir to `destination`, or into it when it is a folder.
+///
+/// Files still being written are snapshotted: each entry holds the file up to the length
+/// it had when opened, so lines appended meanwhile can't leave the entry inconsistent.
+pub(crate) fn export_logs(app: &AppHandle, destination: &Path) -> Result<LogExport, String> {
+    let now = SystemTime::now();
+    let path = if destination.is_dir() {
+        destination.join(default_log_export_name(now))
+    } else {
+        destination.to_path_buf()
+    };
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log dir: {e}"))?;
+    backendlog::flush(app);
+    let mut sources = Vec::new();
+    collect_files(&dir, &dir, &mut sources)
+        .map_err(|e| format!("Failed to list {}: {e}", dir.display()))?;
+    // The zip being written may itself be inside the log dir.
+    let temp = temp_path(&path)?;
+    sources.retain(|(_, source)| *source != path && *source != temp);
+    sources.sort();
+
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let bytes = write_atomically(&path, now, false, |zip| {
+        for (name, source) in &sources {
+            let snapshot = File::open(source).and_then(|file| {
+                let len = file.metadata()?.len();
+                Ok((file, len))
+            });
+            let (file, len) = match snapshot {
+                Ok(opened) => opened,
+                Err(e) => {
+                    log::warn!("Not exporting {}: {}", source.display(), e);
+                    skipped.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+            zip.entry(name, |out| io::copy(&mut file.take(len), out).map(|_| ()))?;
+            files.push(ExportedFile {
+                name: name.clone(),
+                size: len,
+            });
+        }
+        Ok(())
+    })?;
+    log::info!(
+        "Exported {} log files to {} ({} bytes)",
+        files.len(),
+        path.display(),
+        bytes
+    );
+    Ok(LogExport {
+        path,
+        bytes,
+        files,
+        skipped,
+    })
+}
+
+/// Files under `dir`, as their `/`-separated path relative to `root` and their full path.
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, out)?;
+        } else {
+            let name = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            out.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Where the zip for `path` is written before it is renamed into place: the same
+/// directory, so the rename never crosses file systems.
+pub(crate) fn temp_path(path: &Path) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("Not a file path: {}", path.display()))?;
+    Ok(path.with_file_name(format!(".{file_name}.part")))
+}
+
+/// Write a zip to `path` through a temp file, and return its size. `zip64` lifts the
+/// 4 GiB limit, for readers that support it.
+pub(crate) fn write_atomically(
+    path: &Path,
+    time: SystemTime,
+    zip64: bool,
+    fill: impl FnOnce(&mut Zip) -> io::Result<()>,
+) -> Result<u64, String> {
+    let temp = temp_path(path)?;
+    let written = write_zip(&temp, time, zip64, fill);
+    match written.and_then(|()| fs::rename(&temp, path)) {
+        Ok(()) => Ok(fs::metadata(path).map(|m| m.len()).unwrap_or_default()),
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            Err(format!("Failed to write {}: {e}", path.display()))
+        }
+    }
+}
+
+/// The settings in effect, without credentials in the backend URL or secret-looking
+/// backend arguments and fields.
+fn redacted_settings(app: &AppHandle) -> Value {
+    let settings = settings::current(app);
+    let args = args::redacted(&settings.extra_backend_args);
+    let mut value = serde_json::to_value(&settings).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.insert("extra_backend_args".to_string(), Value::String(args));
+        if let Some(url) = settings.backend_url {
+            object.insert(
+                "backend_url".to_string(),
+                Value::String(without_credentials(&url)),
+            );
+        }
+    }
+    redact_secret_fields(&mut value);
+    value
+}
+
+/// `url` without user info and query string, which is where credentials would be.
+pub(crate) fn without_credentials(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.set_query(None);
+            parsed.to_string()
+        }
+        Err(_) => "***".to_string(),
+    }
+}
+
+/// Replace the value of every field whose name looks like a secret with `***`.
+pub(crate) fn redact_secret_fields(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (name, field) in object.iter_mut() {
+                let name = name.to_ascii_lowercase();
+                if args::SECRET_HINTS.iter().any(|hint| name.contains(hint)) {
+                    *field = Value::String("***".to_string());
+                } else {
+                    redact_secret_fields(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secret_fields),
+        _ => {}
+    }
+}
+
+/// `time` in UTC as RFC 3339, e.g. "2025-01-31T08:05:09Z".
+pub(crate) fn utc_timestamp(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil(time);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// UTC year, month, day, hour, minute and second of `time`.
+fn civil(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let secs = unix_millis(time) / 1000;
+    let (year, month, day) = tls::civil_from_days((secs / 86_400) as i64);
+    let rem = (secs % 86_400) as u32;
+    (year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Create `path` and let `fill` add entries, then write the central directory.
+fn write_zip(
+    path: &Path,
+    time: SystemTime,
+    zip64: bool,
+    fill: impl FnOnce(&mut Zip) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut zip = Zip {
+        out: BufWriter::new(File::create(path)?),
+        entries: Vec::new(),
+        dos_time: dos_time(time),
+        zip64,
+    };
+    fill(&mut zip)?;
+    zip.finish()
+}
+
+/// Seconds/2, minutes and hours, then day, month and years since 1980, as zip stores them.
+fn dos_time(time: SystemTime) -> (u16, u16) {
+    let (year, month, day, hour, minute, second) = civil(time);
+    let clock = (hour << 11 | minute << 5 | (second / 2)) as u16;
+    let date = ((year.clamp(1980, 2107) - 1980) as u32) << 9 | month << 5 | day;
+    (clock, date as u16)
+}
+
+/// A minimal zip writer: deflated entries with data descriptors, so sizes and checksum
+/// follow the data instead of needing it up front. Bundles stay far below 4 GiB; data
+/// backups may not, and are written as zip64 throughout.
+pub(crate) struct Zip {
+    out: BufWriter<File>,
+    entries: Vec<Entry>,
+    dos_time: (u16, u16),
+    zip64: bool,
+}
+
+struct Entry {
+    name: String,
+    offset: u64,
+    crc: u32,
+    compressed: u64,
+    size: u64,
+}
+
+/// General purpose flags: sizes in a data descriptor, UTF-8 names.
+const FLAGS: u16 = 0x0008 | 0x0800;
+const DEFLATE: u16 = 8;
+const VERSION: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+const ZIP64_EXTRA: u16 = 0x0001;
+/// Stands for a size or offset found in the zip64 extra field instead.
+const IN_ZIP64: u32 = u32::MAX;
+
+impl Zip {
+    /// Add `name`, with whatever `write` writes as its contents.
+    pub(crate) fn entry(
+        &mut self,
+        name: &str,
+        write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let offset = self.position()?;
+        let (clock, date) = self.dos_time;
+        let version = self.version();
+        let out = &mut self.out;
+        out.write_all(&0x0403_4b50u32.to_le_bytes())?;
+        for field in [version, FLAGS, DEFLATE, clock, date] {
+            out.write_all(&field.to_le_bytes())?;
+        }
+        // Checksum and sizes, which follow in the data descriptor.
+        out.write_all(&[0; 4])?;
+        let unknown = if self.zip64 { IN_ZIP64 } else { 0 };
+        out.write_all(&unknown.to_le_bytes())?;
+        out.write_all(&unknown.to_le_bytes())?;
+        out.write_all(&(name.len() as u16).to_le_bytes())?;
+        let extra_len: u16 = if self.zip64 { 20 } else { 0 };
+        out.write_all(&extra_len.to_le_bytes())?;
+        out.write_all(name.as_bytes())?;
+        if self.zip64 {
+            // Sizes left at zero; the data descriptor has them.
+            out.write_all(&ZIP64_EXTRA.to_le_bytes())?;
+            out.write_all(&16u16.to_le_bytes())?;
+            out.write_all(&[0; 16])?;
+        }
+
+        let start = self.position()?;
+        let (crc, size) = {
+            let mut contents = Contents {
+                crc: Crc::new(),
+                size: 0,
+                inner: DeflateEncoder::new(&mut self.out, Compression::default()),
+            };
+            write(&mut contents)?;
+            contents.inner.finish()?;
+            (contents.crc, contents.size)
+        };
+        let compressed = self.position()? - start;
+
+        let entry = Entry {
+            name: name.to_string(),
+            offset,
+            crc: crc.sum(),
+            compressed,
+            size,
+        };
+        let out = &mut self.out;
+        out.write_all(&0x0807_4b50u32.to_le_bytes())?;
+        out.write_all(&entry.crc.to_le_bytes())?;
+        if self.zip64 {
+            out.write_all(&entry.compressed.to_le_bytes())?;
+            out.write_all(&entry.size.to_le_bytes())?;
+        } else {
+            out.write_all(&small(entry.compressed)?.to_le_bytes())?;
+            out.write_all(&small(entry.size)?.to_le_bytes())?;
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and flush.
+    fn finish(mut self) -> io::Result<()> {
+        let start = self.position()?;
+        let (clock, date) = self.dos_time;
+        let version = self.version();
+        let out = &mut self.out;
+        for entry in &self.entries {
+            out.write_all(&0x0201_4b50u32.to_le_bytes())?;
+            for field in [version, version, FLAGS, DEFLATE, clock, date] {
+                out.write_all(&field.to_le_bytes())?;
+            }
+            out.write_all(&entry.crc.to_le_bytes())?;
+            if self.zip64 {
+                out.write_all(&IN_ZIP64.to_le_bytes())?;
+                out.write_all(&IN_ZIP64.to_le_bytes())?;
+            } else {
+                out.write_all(&small(entry.compressed)?.to_le_bytes())?;
+                out.write_all(&small(entry.size)?.to_le_bytes())?;
+            }
+            // Name and extra field lengths, then no comment, disk number or attributes.
+            out.write_all(&(entry.name.len() as u16).to_le_bytes())?;
+            let extra_len: u16 = if self.zip64 { 28 } else { 0 };
+            out.write_all(&extra_len.to_le_bytes())?;
+            out.write_all(&[0; 10])?;
+            if self.zip64 {
+                out.write_all(&IN_ZIP64.to_le_bytes())?;
+            } else {
+                out.write_all(&small(entry.offset)?.to_le_bytes())?;
+            }
+            out.write_all(entry.name.as_bytes())?;
+            if self.zip64 {
+                out.write_all(&ZIP64_EXTRA.to_le_bytes())?;
+                out.write_all(&24u16.to_le_bytes())?;
+                for field in [entry.size, entry.compressed, entry.offset] {
+                    out.write_all(&field.to_le_bytes())?;
+                }
+            }
+        }
+        let end = self.position()?;
+        let size = end - start;
+        let count = self.entries.len() as u64;
+        let out = &mut self.out;
+        if self.zip64 {
+            // Zip64 end of central directory record, then the locator pointing at it.
+            out.write_all(&0x0606_4b50u32.to_le_bytes())?;
+            out.write_all(&44u64.to_le_bytes())?;
+            out.write_all(&VERSION_ZIP64.to_le_bytes())?;
+            out.write_all(&VERSION_ZIP64.to_le_bytes())?;
+            out.write_all(&[0; 8])?;
+            for field in [count, count, size, start] {
+                out.write_all(&field.to_le_bytes())?;
+            }
+            out.write_all(&0x0706_4b50u32.to_le_bytes())?;
+            out.write_all(&0u32.to_le_bytes())?;
+            out.write_all(&end.to_le_bytes())?;
+            out.write_all(&1u32.to_le_bytes())?;
+        }
+        let (count, size, start) = if self.zip64 {
+            (u16::MAX, IN_ZIP64, IN_ZIP64)
+        } else {
+            let count = u16::try_from(count)
+                .map_err(|_| io::Error::other("zip has more than 65535 entries"))?;
+            (count, small(size)?, small(start)?)
+        };
+        out.write_all(&0x0605_4b50u32.to_le_bytes())?;
+        for field in [0, 0, count, count] {
+            out.write_all(&u16::to_le_bytes(field))?;
+        }
+        out.write_all(&size.to_le_bytes())?;
+        out.write_all(&start.to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        let file = self.out.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()
+    }
+
+    fn version(&self) -> u16 {
+        if self.zip64 {
+            VERSION_ZIP64
+        } else {
+            VERSION
+        }
+    }
+
+    fn position(&mut self) -> io::Result<u64> {
+        self.out.stream_position()
+    }
+}
+
+/// `value` as a 32-bit zip field, for archives without zip64.
+fn small(value: u64) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::other("zip exceeds 4 GiB"))
+}
+
+/// Deflates an entry's contents and keeps their checksum and length.
+struct Contents<W: Write> {
+    crc: Crc,
+    /// Counted here: `Crc::amount` wraps at 4 GiB.
+    size: u64,
+    inner: DeflateEncoder<W>,
+}
+
+impl<W: Write> Write for Contents<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unzip;
+
+    /// Bytes that barely compress, so the entry is larger in the zip than on disk.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(zip64: bool) {
+        let path =
+            std::env::temp_dir().join(format!("support-test-{}-{zip64}.zip", std::process::id()));
+        let entries: Vec<(&str, Vec<u8>)> = vec![
+            ("report.json", b"{\"ok\":true}".repeat(500)),
+            ("empty.txt", Vec::new()),
+            ("logs/facture-été-日本.log", noise(200 * 1024)),
+        ];
+        write_atomically(&path, SystemTime::now(), zip64, |zip| {
+            for (name, contents) in &entries {
+                zip.entry(name, |out| out.write_all(contents))?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert!(!temp_path(&path).unwrap().exists());
+
+        let mut archive = unzip::open(&path).unwrap();
+        let names: Vec<_> = archive.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, entries.iter().map(|(n, _)| *n).collect::<Vec<_>>());
+        for (name, contents) in &entries {
+            let entry = archive.find(name).unwrap();
+            assert_eq!(entry.size, contents.len() as u64, "{name}");
+            assert_eq!(&archive.read(&entry).unwrap(), contents, "{name}");
+        }
+        assert!(archive.find("missing").is_none());
+
+        // The checksum in the central directory is the one extract checks against.
+        let mut bytes = fs::read(&path).unwrap();
+        let central = bytes
+            .windows(4)
+            .position(|w| w == 0x0201_4b50u32.to_le_bytes())
+            .unwrap();
+        bytes[central + 16] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        let mut archive = unzip::open(&path).unwrap();
+        let entry = archive.find("report.json").unwrap();
+        let error = archive.read(&entry).unwrap_err();
+        assert!(error.to_string().contains("checksum"), "{error}");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn zip_round_trips_through_unzip() {
+        round_trip(false);
+    }
+
+    #[test]
+    fn zip64_round_trips_through_unzip() {
+        round_trip(true);
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Freeze and thaw the backend (and the workers it forked) for battery saving.
+
+use crate::proctree::Descendants;
+
+/// Stop `pid` and its descendants from being scheduled until [`resume`] is called.
+pub(crate) fn suspend(pid: u32) -> Result<(), String> {
+    // Workers first, so none of them notices its parent going away.
+    for worker in Descendants::of(pid).pids() {
+        if let Err(e) = suspend_one(worker) {
+            log::warn!("Failed to suspend backend worker {}: {}", worker, e);
+        }
+    }
+    suspend_one(pid).map_err(|e| format!("Failed to suspend backend (pid {pid}): {e}"))
+}
+
+/// Let `pid` and its descendants run again.
+pub(crate) fn resume(pid: u32) -> Result<(), String> {
+    let result = resume_one(pid).map_err(|e| format!("Failed to resume backend (pid {pid}): {e}"));
+    for worker in Descendants::of(pid).pids() {
+        if let Err(e) = resume_one(worker) {
+            log::warn!("Failed to resume backend worker {}: {}", worker, e);
+        }
+    }
+    result
+}
+
+#[cfg(unix)]
+fn suspend_one(pid: u32) -> std::io::Result<()> {
+    signal(pid, libc::SIGSTOP)
+}
+
+#[cfg(unix)]
+fn resume_one(pid: u32) -> std::io::Result<()> {
+    signal(pid, libc::SIGCONT)
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, signal: libc::c_int) -> std::io::Result<()> {
+    // SAFETY: kill(2) has no memory-safety preconditions.
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+mod nt {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SUSPEND_RESUME};
+
+    // Undocumented but stable since NT; the documented alternative is suspending
+    // every thread one by one, which races with threads being created.
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSuspendProcess(process: HANDLE) -> i32;
+        fn NtResumeProcess(process: HANDLE) -> i32;
+    }
+
+    pub(super) fn suspend_one(pid: u32) -> std::io::Result<()> {
+        // SAFETY: `process` is a valid handle with PROCESS_SUSPEND_RESUME access.
+        with_process(pid, |process| unsafe { NtSuspendProcess(process) })
+    }
+
+    pub(super) fn resume_one(pid: u32) -> std::io::Result<()> {
+        // SAFETY: `process` is a valid handle with PROCESS_SUSPEND_RESUME access.
+        with_process(pid, |process| unsafe { NtResumeProcess(process) })
+    }
+
+    fn with_process(pid: u32, call: impl FnOnce(HANDLE) -> i32) -> std::io::Result<()> {
+        // SAFETY: OpenProcess has no pointer arguments; a null result is handled below.
+        let process = unsafe { OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid) };
+        if process.is_null() {
+            return Err(std::io::Error::last_os_error());
+        }
+        let status = call(process);
+        // SAFETY: we opened `process` above and close it exactly once.
+        unsafe { CloseHandle(process) };
+        if status < 0 {
+            return Err(std::io::Error::other(format!("NTSTATUS {status:#010x}")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+use nt::{resume_one, suspend_one};
@@ -0,0 +1,257 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Opt-in anonymous usage events, so we learn how often the backend crashes in the wild.
+//!
+//! Nothing is recorded until the user turns `telemetry_enabled` on. Events are a fixed set
+//! of counters and timings, never invoice data; they wait in a bounded queue file and are
+//! posted in batches to `telemetry_endpoint` together with a random install ID.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::supervisor::Supervisor;
+use crate::{auth, settings, unix_millis};
+
+pub(crate) const QUEUE_FILE: &str = "telemetry_queue.json";
+/// Oldest events are dropped beyond this.
+const MAX_QUEUED: usize = 500;
+const BATCH_SIZE: usize = 100;
+const SEND_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Failed sends double the wait up to this.
+const MAX_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+const INSTALL_ID_BYTES: usize = 16;
+
+/// What happened. Keep every variant free of file names, paths and invoice content.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum EventKind {
+    AppStart {
+        app_version: String,
+        os: String,
+        arch: String,
+    },
+    BackendReady {
+        startup_ms: u64,
+        /// 1 for the first launch, more after restarts.
+        attempt: u32,
+    },
+    BackendCrash {
+        code: Option<i32>,
+        signal: Option<i32>,
+        memory_limit_hit: bool,
+        restart_count: u32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Event {
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// The body of one POST.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Batch {
+    pub install_id: String,
+    pub events: Vec<Event>,
+}
+
+/// Payload of `get_telemetry_preview`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct TelemetryPreview {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub queued: usize,
+    /// Exactly what the next send would post; `None` with nothing queued.
+    pub next_batch: Option<Batch>,
+}
+
+/// Events not sent yet, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct Telemetry(Mutex<VecDeque<Event>>);
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(QUEUE_FILE))
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+fn save(app: &AppHandle, queue: &VecDeque<Event>) {
+    let result = queue_path(app).and_then(|path| {
+        let json = serde_json::to_string(queue).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("{}: {e}", path.display()))
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save telemetry queue: {}", e);
+    }
+}
+
+/// Read the events a previous run left unsent. With telemetry off, delete them instead.
+pub(crate) fn load(app: &AppHandle) {
+    let Ok(path) = queue_path(app) else { return };
+    if !settings::current(app).telemetry_enabled {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<VecDeque<Event>>(&contents) {
+        Ok(mut events) => {
+            events.drain(..events.len().saturating_sub(MAX_QUEUED));
+            *app.state::<Telemetry>().0.lock().unwrap() = events;
+        }
+        Err(e) => log::warn!("Ignoring unreadable telemetry queue: {}", e),
+    }
+}
+
+/// Queue `kind` if the user opted in.
+pub(crate) fn record(app: &AppHandle, kind: EventKind) {
+    if !settings::current(app).telemetry_enabled {
+        return;
+    }
+    let state = app.state::<Telemetry>();
+    let mut queue = state.0.lock().unwrap();
+    queue.push_back(Event {
+        at_ms: unix_millis(SystemTime::now()),
+        kind,
+    });
+    if queue.len() > MAX_QUEUED {
+        queue.pop_front();
+    }
+    save(app, &queue);
+}
+
+/// Turn telemetry on or off and save the choice. The install ID is made on first opt-in;
+/// opting out drops whatever is still queued.
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let install_id = match settings::current(app).telemetry_install_id {
+        Some(id) => id,
+        None => auth::random_hex(INSTALL_ID_BYTES)?,
+    };
+    settings::update(app, |s| {
+        s.telemetry_enabled = enabled;
+        s.telemetry_install_id = Some(install_id);
+    })?;
+    if !enabled {
+        app.state::<Telemetry>().0.lock().unwrap().clear();
+        if let Ok(path) = queue_path(app) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    log::info!("Telemetry {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+fn next_batch(app: &AppHandle) -> Option<Batch> {
+    let install_id = settings::current(app).telemetry_install_id?;
+    let state = app.state::<Telemetry>();
+    let queue = state.0.lock().unwrap();
+    if queue.is_empty() {
+        return None;
+    }
+    Some(Batch {
+        install_id,
+        events: queue.iter().take(BATCH_SIZE).cloned().collect(),
+    })
+}
+
+pub(crate) fn preview(app: &AppHandle) -> TelemetryPreview {
+    let settings = settings::current(app);
+    TelemetryPreview {
+        enabled: settings.telemetry_enabled,
+        endpoint: settings.telemetry_endpoint,
+        queued: app.state::<Telemetry>().0.lock().unwrap().len(),
+        next_batch: next_batch(app),
+    }
+}
+
+/// Post the next batch and drop it from the queue. Returns how many events went out.
+async fn send(app: &AppHandle) -> Result<usize, String> {
+    let settings = settings::current(app);
+    let Some(endpoint) = settings
+        .telemetry_endpoint
+        .filter(|_| settings.telemetry_enabled)
+    else {
+        return Ok(0);
+    };
+    let url = reqwest::Url::parse(&endpoint)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "https" | "http"))
+        .ok_or_else(|| format!("Invalid telemetry endpoint {endpoint}"))?;
+    let Some(batch) = next_batch(app) else {
+        return Ok(0);
+    };
+    // Not the backend client: this leaves the machine, so system proxies apply.
+    let client = reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))?;
+    let resp = client
+        .post(url)
+        .json(&batch)
+        .send()
+        .await
+        .map_err(|e| format!("Telemetry upload failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Telemetry endpoint answered {}", resp.status()));
+    }
+
+    let state = app.state::<Telemetry>();
+    let mut queue = state.0.lock().unwrap();
+    // Events may have been dropped from the front meanwhile; only remove what is still there.
+    for event in &batch.events {
+        if queue.front() == Some(event) {
+            queue.pop_front();
+        }
+    }
+    save(app, &queue);
+    Ok(batch.events.len())
+}
+
+/// Send queued events every `SEND_INTERVAL`, backing off while sends fail.
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let exited = app.state::<Supervisor>().exited();
+        tokio::pin!(exited);
+        let mut wait = SEND_INTERVAL;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = &mut exited => return,
+            }
+            wait = match send(&app).await {
+                Ok(sent) => {
+                    if sent > 0 {
+                        log::debug!("Sent {} telemetry events", sent);
+                    }
+                    SEND_INTERVAL
+                }
+                Err(e) => {
+                    let next = (wait * 2).min(MAX_BACKOFF);
+                    log::debug!("{}, retrying in {} min", e, next.as_secs() / 60);
+                    next
+                }
+            };
+        }
+    });
+}
@@ -0,0 +1,114 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Temp directory handed to the backend for OCR intermediates, swept on start and exit.
+//!
+//! The backend never cleans up after itself, so without this the system temp dir (on
+//! Windows the user's %TEMP%) fills up over weeks.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+pub(crate) const TEMP_DIR_NAME: &str = "tmp";
+/// Files touched this recently may belong to a job in progress and are never swept.
+pub(crate) const ACTIVE_FILE_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// `<app_data_dir>/tmp`, created if missing.
+pub(crate) fn path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join(TEMP_DIR_NAME);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    Ok(dir)
+}
+
+/// Delete files older than `max_age` (but never younger than five minutes) and the
+/// directories left empty. Returns the number of files and bytes removed.
+pub(crate) fn sweep(dir: &Path, max_age: Duration) -> (u64, u64) {
+    let max_age = max_age.max(ACTIVE_FILE_AGE);
+    let now = SystemTime::now();
+    let mut removed = (0, 0);
+    sweep_dir(dir, now, max_age, &mut removed);
+    removed
+}
+
+fn sweep_dir(dir: &Path, now: SystemTime, max_age: Duration, removed: &mut (u64, u64)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // symlink_metadata: never follow links out of the temp dir.
+        let Ok(meta) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            sweep_dir(&path, now, max_age, removed);
+            // Only succeeds once the directory is empty.
+            let _ = std::fs::remove_dir(&path);
+            continue;
+        }
+        let age = meta
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age >= max_age && std::fs::remove_file(&path).is_ok() {
+            removed.0 += 1;
+            removed.1 += meta.len();
+        }
+    }
+}
+
+/// Sweep the backend temp dir, logging what was removed.
+pub(crate) fn sweep_logged(app: &AppHandle, max_age: Duration) {
+    let dir = match path(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    };
+    let (files, bytes) = sweep(&dir, max_age);
+    if files > 0 {
+        log::info!(
+            "Removed {} stale temp file(s) ({} bytes) from {}",
+            files,
+            bytes,
+            dir.display()
+        );
+    }
+}
+
+/// Total size of the files under `dir`, without following symlinks.
+pub(crate) fn usage(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = std::fs::symlink_metadata(entry.path()).ok()?;
+            Some(if meta.is_dir() {
+                usage(&entry.path())
+            } else {
+                meta.len()
+            })
+        })
+        .sum()
+}
@@ -0,0 +1,328 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opt-in HTTPS between the shell and the backend it spawns.
+//!
+//! Loopback traffic can be captured by other local processes. With `loopback_tls` on, a
+//! self-signed certificate for 127.0.0.1, ::1 and localhost is kept in the app data dir,
+//! uvicorn serves it through `SSL_CERTFILE`/`SSL_KEYFILE`, and the shell's client trusts
+//! that certificate and nothing else.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+use tauri::{AppHandle, Manager};
+
+pub(crate) const CERT_FILE_ENV: &str = "SSL_CERTFILE";
+pub(crate) const KEY_FILE_ENV: &str = "SSL_KEYFILE";
+pub(crate) const CERT_FILE_NAME: &str = "loopback-cert.pem";
+pub(crate) const KEY_FILE_NAME: &str = "loopback-key.pem";
+const VALIDITY: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+/// Replace the certificate this long before it expires, so a long session never sees it lapse.
+const RENEW_BEFORE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const COMMON_NAME: &str = "Invoicator loopback";
+
+/// PEM of the certificate the current backend serves; `None` unless TLS is on.
+static PINNED: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// Certificate and key handed to the backend.
+pub(crate) struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// The certificate the shell's client trusts, if the backend serves HTTPS.
+pub(crate) fn pinned() -> Option<Vec<u8>> {
+    PINNED.lock().unwrap().clone()
+}
+
+/// `https` while a pinned certificate is in use, else `http`.
+pub(crate) fn scheme() -> &'static str {
+    if PINNED.lock().unwrap().is_some() {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+/// Where the certificate and key live.
+pub(crate) fn files(app: &AppHandle) -> Result<TlsFiles, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    Ok(TlsFiles {
+        cert: dir.join(CERT_FILE_NAME),
+        key: dir.join(KEY_FILE_NAME),
+    })
+}
+
+/// Get the certificate ready for the backend about to be spawned, regenerating it when
+/// missing or close to expiry, and pin it. With `enabled` off, unpin and return `false`.
+pub(crate) fn prepare(app: &AppHandle, enabled: bool) -> Result<bool, String> {
+    if !enabled {
+        *PINNED.lock().unwrap() = None;
+        return Ok(false);
+    }
+    let files = files(app)?;
+    if !is_fresh(&files) {
+        log::info!(
+            "Generating loopback TLS certificate {}",
+            files.cert.display()
+        );
+        if let Some(dir) = files.cert.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {e}"))?;
+        }
+        generate(&files)?;
+    }
+    let pem = std::fs::read(&files.cert)
+        .map_err(|e| format!("Failed to read {}: {e}", files.cert.display()))?;
+    *PINNED.lock().unwrap() = Some(pem);
+    Ok(true)
+}
+
+/// Both files exist and the certificate (which only we write) is not due for renewal.
+fn is_fresh(files: &TlsFiles) -> bool {
+    if !files.key.is_file() {
+        return false;
+    }
+    let written = std::fs::metadata(&files.cert).and_then(|meta| meta.modified());
+    match written.ok().and_then(|at| at.elapsed().ok()) {
+        Some(age) => age + RENEW_BEFORE < VALIDITY,
+        None => false,
+    }
+}
+
+fn generate(files: &TlsFiles) -> Result<(), String> {
+    let rng = SystemRandom::new();
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+        .map_err(|_| "Failed to generate TLS key".to_string())?;
+    let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+        .map_err(|e| format!("Failed to load generated TLS key: {e}"))?;
+    let cert = self_signed(&key, &rng)?;
+    write_private(&files.key, pem("PRIVATE KEY", pkcs8.as_ref()).as_bytes())?;
+    std::fs::write(&files.cert, pem("CERTIFICATE", &cert))
+        .map_err(|e| format!("Failed to write {}: {e}", files.cert.display()))
+}
+
+/// A DER X.509 v3 certificate for the loopback names, signed by `key` itself.
+///
+/// Not a CA, and only for server auth: webpki refuses a CA certificate as the server's.
+fn self_signed(key: &EcdsaKeyPair, rng: &SystemRandom) -> Result<Vec<u8>, String> {
+    // 1.2.840.10045.4.3.2
+    const ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+    // 1.2.840.10045.2.1 and 1.2.840.10045.3.1.7
+    const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+    const PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+    const COMMON_NAME_OID: &[u8] = &[0x55, 0x04, 0x03];
+    const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+    const BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+    const EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+    const SERVER_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01];
+
+    let mut serial = [0u8; 16];
+    rng.fill(&mut serial)
+        .map_err(|_| "Failed to generate certificate serial".to_string())?;
+    // Positive, and no leading zero byte to strip.
+    serial[0] = (serial[0] & 0x7f) | 0x40;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs();
+    // Backdated a day, in case the clock is adjusted.
+    let not_before = time(now.saturating_sub(24 * 60 * 60));
+    let not_after = time(now + VALIDITY.as_secs());
+
+    let algorithm = der(0x30, &der(0x06, ECDSA_WITH_SHA256));
+    let name = der(
+        0x30,
+        &der(
+            0x31,
+            &der(
+                0x30,
+                &[
+                    der(0x06, COMMON_NAME_OID),
+                    der(0x0c, COMMON_NAME.as_bytes()),
+                ]
+                .concat(),
+            ),
+        ),
+    );
+    let public_key = der(
+        0x30,
+        &[
+            der(
+                0x30,
+                &[der(0x06, EC_PUBLIC_KEY), der(0x06, PRIME256V1)].concat(),
+            ),
+            bit_string(key.public_key().as_ref()),
+        ]
+        .concat(),
+    );
+    let alt_names = der(
+        0x30,
+        &[
+            der(0x87, &[127, 0, 0, 1]),
+            der(0x87, &std::net::Ipv6Addr::LOCALHOST.octets()),
+            der(0x82, b"localhost"),
+        ]
+        .concat(),
+    );
+    let extensions = der(
+        0xa3,
+        &der(
+            0x30,
+            &[
+                extension(SUBJECT_ALT_NAME, false, &alt_names),
+                // cA defaults to false, so the value is an empty sequence.
+                extension(BASIC_CONSTRAINTS, true, &der(0x30, &[])),
+                extension(
+                    EXTENDED_KEY_USAGE,
+                    false,
+                    &der(0x30, &der(0x06, SERVER_AUTH)),
+                ),
+            ]
+            .concat(),
+        ),
+    );
+    let tbs = der(
+        0x30,
+        &[
+            // [0] version: v3
+            der(0xa0, &der(0x02, &[2])),
+            der(0x02, &serial),
+            algorithm.clone(),
+            name.clone(),
+            der(0x30, &[not_before, not_after].concat()),
+            name,
+            public_key,
+            extensions,
+        ]
+        .concat(),
+    );
+    let signature = key
+        .sign(rng, &tbs)
+        .map_err(|_| "Failed to sign TLS certificate".to_string())?;
+    Ok(der(
+        0x30,
+        &[tbs, algorithm, bit_string(signature.as_ref())].concat(),
+    ))
+}
+
+/// A DER element: tag, definite length, content.
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// A BIT STRING of whole bytes.
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    der(0x03, &[&[0u8][..], bytes].concat())
+}
+
+fn extension(oid: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+    let mut content = der(0x06, oid);
+    if critical {
+        content.extend(der(0x01, &[0xff]));
+    }
+    content.extend(der(0x04, value));
+    der(0x30, &content)
+}
+
+/// `secs` since the epoch as UTCTime, or GeneralizedTime from 2050 on as X.509 requires.
+fn time(secs: u64) -> Vec<u8> {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    let clock = format!(
+        "{month:02}{day:02}{:02}{:02}{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    );
+    if year < 2050 {
+        der(0x17, format!("{:02}{clock}", year % 100).as_bytes())
+    } else {
+        der(0x18, format!("{year:04}{clock}").as_bytes())
+    }
+}
+
+/// Year, month and day of the `days`th day since 1970-01-01 (Howard Hinnant's algorithm).
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 of a calendar date, the inverse of `civil_from_days`.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn pem(label: &str, der: &[u8]) -> String {
+    let encoded = BASE64_STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {label}-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(&String::from_utf8_lossy(line));
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {label}-----\n"));
+    out
+}
+
+/// Write `contents` readable by the current user only.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let _ = std::fs::remove_file(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
@@ -0,0 +1,589 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! How the shell talks HTTP to the backend: over TCP, or over a Unix domain socket
+//! (macOS, Linux).
+//!
+//! Callers only see status codes and bodies, so health checks and readiness polling work
+//! the same on every transport.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::supervisor::StartupError;
+use crate::{auth, tls};
+
+/// File name of the backend's socket in the app data dir.
+pub(crate) const SOCKET_FILE_NAME: &str = "backend.sock";
+/// `sun_path` holds 104 bytes on macOS (108 on Linux), terminating NUL included.
+#[cfg(unix)]
+const MAX_SOCKET_PATH_LEN: usize = 103;
+
+/// Env var telling the backend which socket to listen on, besides TCP.
+pub(crate) const LOCAL_ENDPOINT_ENV: &str = "UDS_PATH";
+
+/// Connecting to a local backend is instant when it is up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// Longer than the watchdog's interval, so its checks keep one connection alive.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+/// Offered on buffered requests; see `BackendTransport::send`.
+const ACCEPT_ENCODING: &str = "gzip, br";
+
+/// Keyed by the pinned certificate, which changes when loopback TLS is switched on or off.
+static TCP_CLIENT: Mutex<Option<(Option<Vec<u8>>, reqwest::Client)>> = Mutex::new(None);
+#[cfg(unix)]
+static UNIX_CLIENT: Mutex<Option<(PathBuf, reqwest::Client)>> = Mutex::new(None);
+
+/// Which transport the user asked for; `Tcp` is always available as the fallback.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TransportKind {
+    /// Also what a `named_pipe` setting from an older version means now.
+    #[default]
+    #[serde(alias = "named_pipe")]
+    Tcp,
+    /// A Unix domain socket in the app data dir (macOS and Linux).
+    UnixSocket,
+}
+
+/// Socket the current backend listens on, `None` when the shell uses TCP.
+#[derive(Default)]
+pub(crate) struct BackendSocket(pub(crate) Mutex<Option<PathBuf>>);
+
+/// Where a request to the backend goes.
+#[derive(Clone, Debug)]
+pub(crate) enum BackendTransport {
+    /// HTTP to `base`, e.g. "http://127.0.0.1:8000".
+    Tcp(String),
+    /// HTTP over the Unix domain socket at this path.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// One HTTP request to the backend.
+pub(crate) struct Request<'a> {
+    pub method: reqwest::Method,
+    /// Path and query, e.g. "/api/v1/invoices?limit=10".
+    pub path: &'a str,
+    pub body: Option<Body>,
+    pub timeout: Duration,
+    /// Fail rather than read a longer response body; `None` reads it all.
+    pub max_response_bytes: Option<usize>,
+}
+
+impl<'a> Request<'a> {
+    pub(crate) fn new(method: reqwest::Method, path: &'a str, timeout: Duration) -> Self {
+        Self {
+            method,
+            path,
+            body: None,
+            timeout,
+            max_response_bytes: None,
+        }
+    }
+}
+
+/// A request body and its content type.
+pub(crate) struct Body {
+    pub content_type: String,
+    /// Sent as `Content-Length`; streamed bodies have no length of their own.
+    pub length: u64,
+    pub data: reqwest::Body,
+}
+
+impl Body {
+    pub(crate) fn json(bytes: Vec<u8>) -> Self {
+        Self {
+            content_type: "application/json".to_string(),
+            length: bytes.len() as u64,
+            data: bytes.into(),
+        }
+    }
+}
+
+/// A backend response, read in full and decompressed.
+#[derive(Clone)]
+pub(crate) struct Response {
+    pub status: u16,
+    /// Lowercase names, in the order the backend sent them. `content-encoding` and
+    /// `content-length` are dropped once the body is decoded.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Size of the body as it came over the connection, before decompression.
+    pub wire_bytes: usize,
+}
+
+impl Response {
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub(crate) fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_slice(&self.body).map_err(|e| e.to_string())
+    }
+}
+
+/// A backend response whose body has not been read yet.
+pub(crate) struct StreamingResponse {
+    pub status: u16,
+    /// Lowercase names, in the order the backend sent them.
+    pub headers: Vec<(String, String)>,
+    body: reqwest::Response,
+}
+
+impl StreamingResponse {
+    /// The next chunk of the body, `None` at its end.
+    pub(crate) async fn chunk(&mut self) -> Result<Option<Bytes>, TransportError> {
+        Ok(self.body.chunk().await?)
+    }
+
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// `Content-Length`, when the backend sent one.
+    pub(crate) fn content_length(&self) -> Option<u64> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .and_then(|(_, value)| value.parse().ok())
+    }
+}
+
+/// Why a request got no response.
+#[derive(Debug)]
+pub(crate) struct TransportError {
+    pub timed_out: bool,
+    /// The response body was longer than `Request::max_response_bytes`.
+    pub too_large: bool,
+    /// The connection was refused or reset, as it is for a moment while the backend
+    /// restarts.
+    pub connection_failed: bool,
+    /// The backend's host name did not resolve.
+    pub dns_failed: bool,
+    message: String,
+}
+
+impl TransportError {
+    fn too_large(limit: usize) -> Self {
+        Self {
+            too_large: true,
+            ..Self::from(format!("response body is larger than {limit} bytes"))
+        }
+    }
+}
+
+/// Whether `e` comes from a refused, reset or aborted connection.
+fn is_connection_failure(e: &reqwest::Error) -> bool {
+    use std::error::Error;
+    use std::io::ErrorKind;
+
+    if e.is_connect() {
+        return true;
+    }
+    let mut source = e.source();
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Whether `e` comes from failing to resolve the host name.
+fn is_dns_failure(e: &reqwest::Error) -> bool {
+    use std::error::Error;
+
+    let mut source = e.source();
+    while let Some(err) = source {
+        // hyper's connector wraps resolver errors in one that displays as just this.
+        if err.to_string() == "dns error" {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(e: reqwest::Error) -> Self {
+        Self {
+            timed_out: e.is_timeout(),
+            too_large: false,
+            connection_failed: is_connection_failure(&e),
+            dns_failed: is_dns_failure(&e),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<String> for TransportError {
+    fn from(message: String) -> Self {
+        Self {
+            timed_out: false,
+            too_large: false,
+            connection_failed: false,
+            dns_failed: false,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for BackendTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(base) => f.write_str(base),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl BackendTransport {
+    pub(crate) async fn get(
+        &self,
+        path: &str,
+        timeout: Duration,
+    ) -> Result<Response, TransportError> {
+        self.send(Request::new(reqwest::Method::GET, path, timeout))
+            .await
+    }
+
+    pub(crate) async fn post(
+        &self,
+        path: &str,
+        timeout: Duration,
+    ) -> Result<Response, TransportError> {
+        self.send(Request::new(reqwest::Method::POST, path, timeout))
+            .await
+    }
+
+    /// Send `request` with the auth token of the current backend and read the response.
+    ///
+    /// Offers gzip and brotli: the body is buffered anyway, so decoding it costs little.
+    /// `max_response_bytes` applies to the body both before and after decoding.
+    pub(crate) async fn send(&self, request: Request<'_>) -> Result<Response, TransportError> {
+        let limit = request.max_response_bytes;
+        let mut resp = self.open_with(request, Some(ACCEPT_ENCODING)).await?;
+        let mut raw = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            append_limited(&mut raw, &chunk, limit)?;
+        }
+        let wire_bytes = raw.len();
+        let encoding = resp
+            .headers
+            .iter()
+            .find(|(name, _)| name == "content-encoding")
+            .map(|(_, value)| value.trim().to_ascii_lowercase());
+        let (headers, body) = match encoding.as_deref() {
+            None | Some("identity") => (resp.headers, raw),
+            Some(encoding) => {
+                let body = decode(encoding, &raw, limit)?;
+                let headers = resp
+                    .headers
+                    .into_iter()
+                    .filter(|(name, _)| name != "content-encoding" && name != "content-length")
+                    .collect();
+                (headers, body)
+            }
+        };
+        Ok(Response {
+            status: resp.status,
+            headers,
+            body,
+            wire_bytes,
+        })
+    }
+
+    /// Send `request` with the auth token and return once the response headers arrive;
+    /// the body is read with `StreamingResponse::chunk`.
+    ///
+    /// `request.timeout` bounds the whole exchange, body included.
+    ///
+    /// Asks for the body uncompressed, so chunks can be written out as they arrive.
+    pub(crate) async fn open(
+        &self,
+        request: Request<'_>,
+    ) -> Result<StreamingResponse, TransportError> {
+        self.open_with(request, None).await
+    }
+
+    async fn open_with(
+        &self,
+        request: Request<'_>,
+        accept_encoding: Option<&str>,
+    ) -> Result<StreamingResponse, TransportError> {
+        let (client, url) = match self {
+            Self::Tcp(base) => (client()?, format!("{base}{}", request.path)),
+            // The host is ignored once the client is bound to a socket.
+            #[cfg(unix)]
+            Self::Unix(socket) => (
+                unix_client(socket)?,
+                format!("http://localhost{}", request.path),
+            ),
+        };
+        let mut builder = client.request(request.method, url).timeout(request.timeout);
+        if let Some(token) = auth::token() {
+            builder = builder.bearer_auth(token);
+        }
+        if let Some(encodings) = accept_encoding {
+            builder = builder.header(reqwest::header::ACCEPT_ENCODING, encodings);
+        }
+        if let Some(body) = request.body {
+            builder = builder
+                .header(reqwest::header::CONTENT_TYPE, body.content_type)
+                .header(reqwest::header::CONTENT_LENGTH, body.length)
+                .body(body.data);
+        }
+        let resp = builder.send().await?;
+        Ok(StreamingResponse {
+            status: resp.status().as_u16(),
+            headers: header_pairs(resp.headers()),
+            body: resp,
+        })
+    }
+
+    /// Whether the backend accepts connections within `timeout`, without speaking HTTP.
+    pub(crate) async fn accepts_connections(&self, timeout: Duration) -> bool {
+        match self {
+            Self::Tcp(base) => {
+                let Some(addr) = crate::endpoint::socket_addr(base) else {
+                    // Let the HTTP poll report what is wrong with the URL.
+                    return true;
+                };
+                let connect = tokio::net::TcpStream::connect(addr);
+                matches!(tokio::time::timeout(timeout, connect).await, Ok(Ok(_)))
+            }
+            #[cfg(unix)]
+            Self::Unix(socket) => {
+                let connect = tokio::net::UnixStream::connect(socket);
+                matches!(tokio::time::timeout(timeout, connect).await, Ok(Ok(_)))
+            }
+        }
+    }
+
+    pub(crate) fn kind(&self) -> TransportKind {
+        match self {
+            Self::Tcp(_) => TransportKind::Tcp,
+            #[cfg(unix)]
+            Self::Unix(_) => TransportKind::UnixSocket,
+        }
+    }
+
+    /// Whether this is a socket the backend never created. Always false for TCP.
+    pub(crate) fn endpoint_missing(&self) -> bool {
+        match self {
+            Self::Tcp(_) => false,
+            #[cfg(unix)]
+            Self::Unix(socket) => !socket.exists(),
+        }
+    }
+
+    /// The socket file behind this transport, if any.
+    pub(crate) fn socket_path(&self) -> Option<&Path> {
+        match self {
+            Self::Tcp(_) => None,
+            #[cfg(unix)]
+            Self::Unix(socket) => Some(socket),
+        }
+    }
+}
+
+/// Response headers as lowercase name/value pairs; values that aren't text are dropped.
+fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// Add `chunk` to `body`, failing once it would exceed `limit`.
+fn append_limited(
+    body: &mut Vec<u8>,
+    chunk: &[u8],
+    limit: Option<usize>,
+) -> Result<(), TransportError> {
+    if let Some(limit) = limit {
+        if body.len() + chunk.len() > limit {
+            return Err(TransportError::too_large(limit));
+        }
+    }
+    body.extend_from_slice(chunk);
+    Ok(())
+}
+
+/// Decompress a body sent with `Content-Encoding: encoding`, at most `limit` bytes of it.
+fn decode(encoding: &str, raw: &[u8], limit: Option<usize>) -> Result<Vec<u8>, TransportError> {
+    let decoder: Box<dyn Read + '_> = match encoding {
+        "gzip" | "x-gzip" => Box::new(flate2::read::GzDecoder::new(raw)),
+        "deflate" => Box::new(flate2::read::ZlibDecoder::new(raw)),
+        "br" => Box::new(brotli::Decompressor::new(raw, 4096)),
+        other => return Err(format!("Backend sent an unsupported encoding {other:?}").into()),
+    };
+    // One byte past the limit tells a body that fits exactly from one that doesn't.
+    let cap = limit.map_or(u64::MAX, |limit| limit as u64 + 1);
+    let mut body = Vec::new();
+    decoder
+        .take(cap)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to decode {encoding} response: {e}"))?;
+    if let Some(limit) = limit.filter(|limit| body.len() > *limit) {
+        return Err(TransportError::too_large(limit));
+    }
+    Ok(body)
+}
+
+/// Settings every client that talks to the backend shares. It is on loopback, so system
+/// proxies never apply; timeouts beyond connecting are set per request.
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .no_proxy()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+}
+
+fn build(builder: reqwest::ClientBuilder) -> Result<reqwest::Client, String> {
+    builder
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))
+}
+
+/// The client shared by every TCP request, so connections are pooled across calls.
+///
+/// It sends no credentials by itself; `send` adds the auth token per request. Only for
+/// the backend: traffic to anywhere else needs its own client that honors the proxy.
+///
+/// With loopback TLS on, it trusts the backend's certificate and no other.
+pub(crate) fn client() -> Result<reqwest::Client, String> {
+    let pinned = tls::pinned();
+    let mut cached = TCP_CLIENT.lock().unwrap();
+    if let Some((cert, client)) = cached.as_ref() {
+        if *cert == pinned {
+            return Ok(client.clone());
+        }
+    }
+    let mut builder = client_builder();
+    if let Some(pem) = &pinned {
+        let cert = reqwest::Certificate::from_pem(pem)
+            .map_err(|e| format!("Invalid loopback TLS certificate: {e}"))?;
+        builder = builder
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(cert);
+    }
+    let client = build(builder)?;
+    *cached = Some((pinned, client.clone()));
+    Ok(client)
+}
+
+/// A client bound to `socket`, the Unix counterpart of `client()`.
+#[cfg(unix)]
+fn unix_client(socket: &Path) -> Result<reqwest::Client, String> {
+    let mut cached = UNIX_CLIENT.lock().unwrap();
+    if let Some((path, client)) = cached.as_ref() {
+        if path == socket {
+            return Ok(client.clone());
+        }
+    }
+    let client = build(client_builder().unix_socket(socket))?;
+    *cached = Some((socket.to_path_buf(), client.clone()));
+    Ok(client)
+}
+
+/// The socket for the next backend, if `kind` asks for one and it can be used here.
+///
+/// Falls back to TCP (`Ok(None)`) when the transport is unsupported on this platform or
+/// the socket path is too long. A socket file left by a crashed backend is removed; one
+/// that still accepts connections belongs to someone else and fails the launch.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub(crate) fn prepare_socket(
+    app: &AppHandle,
+    kind: TransportKind,
+) -> Result<Option<PathBuf>, StartupError> {
+    match kind {
+        TransportKind::Tcp => Ok(None),
+        #[cfg(unix)]
+        TransportKind::UnixSocket => prepare_unix_socket(app),
+        #[cfg(not(unix))]
+        TransportKind::UnixSocket => {
+            log::warn!("Unix sockets are not available on this platform, using TCP");
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn prepare_unix_socket(app: &AppHandle) -> Result<Option<PathBuf>, StartupError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    let path = dir.join(SOCKET_FILE_NAME);
+    if path.as_os_str().len() > MAX_SOCKET_PATH_LEN {
+        log::warn!(
+            "Socket path {} is too long for a Unix socket, using TCP",
+            path.display()
+        );
+        return Ok(None);
+    }
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            return Err(format!("socket {} is in use by another process", path.display()).into());
+        }
+        log::info!("Removing stale backend socket {}", path.display());
+        remove_socket(&path);
+    }
+    Ok(Some(path))
+}
+
+/// Delete a socket file once nothing listens on it any more.
+pub(crate) fn remove_socket(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to remove backend socket {}: {}", path.display(), e),
+    }
+}
+
+pub(crate) fn current_socket(app: &AppHandle) -> Option<PathBuf> {
+    app.state::<BackendSocket>().0.lock().unwrap().clone()
+}
+
+pub(crate) fn set_socket(app: &AppHandle, socket: Option<PathBuf>) {
+    *app.state::<BackendSocket>().0.lock().unwrap() = socket;
+}
@@ -0,0 +1,347 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A minimal zip reader, for restoring the backups `support` writes.
+//!
+//! Entries are found through the central directory, zip64 included, and may be stored or
+//! deflated; encrypted entries are refused. Each entry's checksum and size are checked as
+//! it is read.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
+
+const END_SIG: u32 = 0x0605_4b50;
+const END64_SIG: u32 = 0x0606_4b50;
+const LOCATOR_SIG: u32 = 0x0706_4b50;
+const CENTRAL_SIG: u32 = 0x0201_4b50;
+const LOCAL_SIG: u32 = 0x0403_4b50;
+const END_LEN: u64 = 22;
+const LOCATOR_LEN: u64 = 20;
+/// The end record's comment is at most this long.
+const MAX_COMMENT: u64 = u16::MAX as u64;
+/// Larger central directories are refused rather than read into memory.
+const MAX_DIRECTORY_BYTES: u64 = 64 * 1024 * 1024;
+const STORED: u16 = 0;
+const DEFLATE: u16 = 8;
+const ENCRYPTED: u16 = 0x0001;
+const ZIP64_EXTRA: u16 = 0x0001;
+
+/// An open zip and what its central directory lists.
+pub(crate) struct Archive {
+    file: File,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    /// As stored, `/`-separated; not checked for `..` or absolute paths.
+    pub name: String,
+    /// Uncompressed size, as the central directory records it.
+    pub size: u64,
+    method: u16,
+    crc: u32,
+    compressed: u64,
+    offset: u64,
+}
+
+impl Entry {
+    pub(crate) fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Little-endian fields read off a byte slice.
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(invalid("truncated zip record"));
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+fn read_at(file: &mut File, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut buf)?;
+    if (buf.len() as u64) < len {
+        return Err(invalid("zip is truncated"));
+    }
+    Ok(buf)
+}
+
+/// Open `path` and read its central directory.
+pub(crate) fn open(path: &Path) -> io::Result<Archive> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < END_LEN {
+        return Err(invalid("not a zip file"));
+    }
+    let tail_len = len.min(END_LEN + MAX_COMMENT);
+    let tail = read_at(&mut file, len - tail_len, tail_len)?;
+    let end_at = (0..=tail.len() - END_LEN as usize)
+        .rev()
+        .find(|&i| tail[i..i + 4] == END_SIG.to_le_bytes())
+        .ok_or_else(|| invalid("not a zip file"))?;
+    let end_offset = len - tail_len + end_at as u64;
+
+    let mut end = Fields(&tail[end_at + 4..]);
+    let (disk, _, _, mut count) = (end.u16()?, end.u16()?, end.u16()?, end.u16()? as u64);
+    let (mut size, mut start) = (end.u32()? as u64, end.u32()? as u64);
+    if disk != 0 {
+        return Err(invalid("multi-part zips are not supported"));
+    }
+    if count == u16::MAX as u64 || size == u32::MAX as u64 || start == u32::MAX as u64 {
+        (count, size, start) = zip64_end(&mut file, end_offset)?;
+    }
+    if size > MAX_DIRECTORY_BYTES || start.saturating_add(size) > end_offset {
+        return Err(invalid("zip central directory is out of bounds"));
+    }
+
+    let directory = read_at(&mut file, start, size)?;
+    let mut fields = Fields(&directory);
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        entries.push(central_entry(&mut fields)?);
+    }
+    Ok(Archive { file, entries })
+}
+
+/// Entry count, size and offset of the central directory from the zip64 end record.
+fn zip64_end(file: &mut File, end_offset: u64) -> io::Result<(u64, u64, u64)> {
+    let locator_at = end_offset
+        .checked_sub(LOCATOR_LEN)
+        .ok_or_else(|| invalid("zip64 locator missing"))?;
+    let locator = read_at(file, locator_at, LOCATOR_LEN)?;
+    let mut locator = Fields(&locator);
+    if locator.u32()? != LOCATOR_SIG {
+        return Err(invalid("zip64 locator missing"));
+    }
+    let (_, record_at) = (locator.u32()?, locator.u64()?);
+    let record = read_at(file, record_at, 56)?;
+    let mut record = Fields(&record);
+    if record.u32()? != END64_SIG {
+        return Err(invalid("zip64 end record missing"));
+    }
+    // Record size, versions, disk numbers and entries on this disk.
+    record.bytes(8 + 4 + 8 + 8)?;
+    Ok((record.u64()?, record.u64()?, record.u64()?))
+}
+
+fn central_entry(fields: &mut Fields) -> io::Result<Entry> {
+    if fields.u32()? != CENTRAL_SIG {
+        return Err(invalid("corrupt zip central directory"));
+    }
+    // Versions made by and needed.
+    fields.bytes(4)?;
+    let flags = fields.u16()?;
+    let method = fields.u16()?;
+    // Modification time and date.
+    fields.bytes(4)?;
+    let crc = fields.u32()?;
+    let mut compressed = fields.u32()? as u64;
+    let mut size = fields.u32()? as u64;
+    let (name_len, extra_len, comment_len) = (fields.u16()?, fields.u16()?, fields.u16()?);
+    // Disk number and attributes.
+    fields.bytes(8)?;
+    let mut offset = fields.u32()? as u64;
+    let name = String::from_utf8_lossy(fields.bytes(name_len as usize)?).into_owned();
+    let mut extra = Fields(fields.bytes(extra_len as usize)?);
+    fields.bytes(comment_len as usize)?;
+
+    if flags & ENCRYPTED != 0 {
+        return Err(invalid(format!("{name} is encrypted")));
+    }
+    // Only the fields saturated in the record are in the zip64 extra, in this order.
+    while !extra.0.is_empty() {
+        let (id, len) = (extra.u16()?, extra.u16()?);
+        let mut data = Fields(extra.bytes(len as usize)?);
+        if id != ZIP64_EXTRA {
+            continue;
+        }
+        for field in [&mut size, &mut compressed, &mut offset] {
+            if *field == u32::MAX as u64 {
+                *field = data.u64()?;
+            }
+        }
+    }
+    Ok(Entry {
+        name,
+        size,
+        method,
+        crc,
+        compressed,
+        offset,
+    })
+}
+
+impl Archive {
+    /// Write the contents of `entry` to `out`, failing if they don't match its checksum.
+    pub(crate) fn extract(&mut self, entry: &Entry, out: &mut dyn Write) -> io::Result<()> {
+        let header = read_at(&mut self.file, entry.offset, 30)?;
+        let mut header = Fields(&header);
+        if header.u32()? != LOCAL_SIG {
+            return Err(invalid(format!("{}: corrupt local header", entry.name)));
+        }
+        header.bytes(22)?;
+        let (name_len, extra_len) = (header.u16()? as u64, header.u16()? as u64);
+        self.file
+            .seek(SeekFrom::Start(entry.offset + 30 + name_len + extra_len))?;
+        let data = (&mut self.file).take(entry.compressed);
+        let reader: Box<dyn Read + '_> = match entry.method {
+            STORED => Box::new(data),
+            DEFLATE => Box::new(DeflateDecoder::new(data)),
+            method => {
+                return Err(invalid(format!(
+                    "{}: compression method {method} is not supported",
+                    entry.name
+                )))
+            }
+        };
+        // A byte past the recorded size is enough to refuse an entry, however far a crafted
+        // deflate stream would expand; the restore's free space check relies on the sizes.
+        let mut reader = reader.take(entry.size.saturating_add(1));
+        let mut crc = Crc::new();
+        let mut size = 0u64;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            size += n as u64;
+            if size > entry.size {
+                return Err(invalid(format!(
+                    "{}: larger than the size the zip records",
+                    entry.name
+                )));
+            }
+            crc.update(&buf[..n]);
+            out.write_all(&buf[..n])?;
+        }
+        if size != entry.size || crc.sum() != entry.crc {
+            return Err(invalid(format!(
+                "{}: contents don't match the checksum",
+                entry.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// The contents of `entry`, for small ones such as a manifest.
+    pub(crate) fn read(&mut self, entry: &Entry) -> io::Result<Vec<u8>> {
+        let mut contents = Vec::with_capacity(entry.size.min(1024 * 1024) as usize);
+        self.extract(entry, &mut contents)?;
+        Ok(contents)
+    }
+
+    pub(crate) fn find(&self, name: &str) -> Option<Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+
+    /// A one-entry zip of `contents`, deflated, whose records claim `size` bytes.
+    fn write_zip(path: &Path, contents: &[u8], size: u32) {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(contents).unwrap();
+        let data = encoder.finish().unwrap();
+        let mut crc = Crc::new();
+        crc.update(contents);
+        let name = b"data/invoices.db";
+        let fields = |out: &mut Vec<u8>| {
+            for field in [DEFLATE, 0, 0] {
+                out.extend(field.to_le_bytes());
+            }
+            for field in [crc.sum(), data.len() as u32, size] {
+                out.extend(field.to_le_bytes());
+            }
+            out.extend((name.len() as u16).to_le_bytes());
+            out.extend(0u16.to_le_bytes());
+        };
+
+        let mut zip = LOCAL_SIG.to_le_bytes().to_vec();
+        zip.extend([20, 0, 0, 0]);
+        fields(&mut zip);
+        zip.extend(name);
+        zip.extend(&data);
+        let directory = zip.len() as u32;
+        zip.extend(CENTRAL_SIG.to_le_bytes());
+        zip.extend([20, 0, 20, 0, 0, 0]);
+        fields(&mut zip);
+        // No comment, disk number or attributes; the local header is at 0.
+        zip.extend([0; 14]);
+        zip.extend(name);
+        let directory_len = zip.len() as u32 - directory;
+        zip.extend(END_SIG.to_le_bytes());
+        zip.extend([0, 0, 0, 0, 1, 0, 1, 0]);
+        zip.extend(directory_len.to_le_bytes());
+        zip.extend(directory.to_le_bytes());
+        zip.extend([0, 0]);
+        std::fs::write(path, zip).unwrap();
+    }
+
+    /// An entry that inflates past its recorded size is refused by the first byte over,
+    /// and no more than the recorded size reaches the output.
+    #[test]
+    fn extract_stops_at_the_recorded_size() {
+        let path = std::env::temp_dir().join(format!("unzip-test-{}.zip", std::process::id()));
+        let contents = vec![0; 4 * 1024 * 1024];
+        write_zip(&path, &contents, 1024);
+        let mut archive = open(&path).unwrap();
+        let entry = archive.entries[0].clone();
+        let mut out = Vec::new();
+        let error = archive.extract(&entry, &mut out).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("larger than"), "{error}");
+        assert!(out.len() <= 1024, "{} bytes written", out.len());
+
+        write_zip(&path, &contents, contents.len() as u32);
+        let mut archive = open(&path).unwrap();
+        let entry = archive.entries[0].clone();
+        assert_eq!(archive.read(&entry).unwrap(), contents);
+        std::fs::remove_file(path).unwrap();
+    }
+}
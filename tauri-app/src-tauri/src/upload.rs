@@ -0,0 +1,320 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! `upload_invoice`: stream a file from disk to the backend's `/analyze` endpoint.
+//!
+//! The webview would hold the whole file in memory and report no reliable progress; here
+//! it is read in chunks, framed as multipart/form-data and sent as it is read.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::AsyncReadExt;
+
+use crate::auth;
+use crate::cancel::Cancellations;
+use crate::concurrency::{self, Lane};
+use crate::proxy::{self, TimeoutClass};
+use crate::transport::{Body, Request};
+use crate::unix_millis;
+use crate::{config, diskspace, endpoint, invoices};
+
+const UPLOAD_PATH: &str = "/api/v1/analyze";
+/// The backend's own limit (`MAX_FILE_SIZE_BYTES`); checked here to fail before sending.
+const MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+/// `/analyze` runs OCR before it answers.
+const UPLOAD_TIMEOUT_CLASS: TimeoutClass = TimeoutClass::LongRunning;
+
+/// Extensions `/analyze` accepts, with the content type sent for each.
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("webp", "image/webp"),
+];
+
+/// Payload of the `upload-progress` event.
+#[derive(Clone, Debug, Serialize)]
+struct UploadProgress {
+    upload_id: String,
+    path: PathBuf,
+    sent_bytes: u64,
+    total_bytes: u64,
+}
+
+/// Why an upload failed, tagged by `kind`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum UploadError {
+    /// Refused before anything was sent: wrong extension, too large, or an `upload_id`
+    /// already in use.
+    InvalidFile {
+        message: String,
+    },
+    /// The file could not be opened or read.
+    Unreadable {
+        message: String,
+    },
+    /// The backend answered with an error status.
+    Rejected {
+        status: u16,
+        message: String,
+    },
+    /// The connection failed before the backend answered.
+    ConnectionLost {
+        message: String,
+    },
+    Cancelled {
+        message: String,
+    },
+    /// Refused because the data dir's volume has less free space than `min_free_space_mb`.
+    LowDiskSpace {
+        free_bytes: u64,
+        floor_bytes: u64,
+        message: String,
+    },
+}
+
+/// Upload the file at `path` and return the backend's JSON answer.
+///
+/// `upload_id` lets the caller cancel with `cancel_backend_request` before the first
+/// progress event; one is generated otherwise. Progress is emitted every `PROGRESS_INTERVAL` and once at the end.
+pub(crate) async fn upload(
+    app: &AppHandle,
+    path: PathBuf,
+    upload_id: Option<String>,
+) -> Result<serde_json::Value, UploadError> {
+    let content_type = content_type(&path)?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| unreadable(&path, e))?;
+    let total_bytes = file
+        .metadata()
+        .await
+        .map_err(|e| unreadable(&path, e))?
+        .len();
+    if total_bytes > MAX_FILE_BYTES {
+        return Err(UploadError::InvalidFile {
+            message: format!(
+                "{} is larger than the {} MB limit",
+                path.display(),
+                MAX_FILE_BYTES / (1024 * 1024)
+            ),
+        });
+    }
+
+    let id = upload_id
+        .or_else(|| auth::random_hex(8).ok())
+        .unwrap_or_else(|| unix_millis(SystemTime::now()).to_string());
+    let calls = app.state::<Cancellations>();
+    let registration = calls
+        .register(&id)
+        .map_err(|message| UploadError::InvalidFile { message })?;
+
+    // Random, so it can't occur in the file by accident.
+    let boundary = format!(
+        "invoicator-{}",
+        auth::random_hex(16).unwrap_or_else(|_| id.clone())
+    );
+    let head = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+         Content-Type: {content_type}\r\n\r\n",
+        quote_file_name(&file_name)
+    );
+    let tail = format!("\r\n--{boundary}--\r\n");
+    let length = head.len() as u64 + total_bytes + tail.len() as u64;
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let read_error: Arc<Mutex<Option<String>>> = Arc::default();
+    let body = file_stream(file, sent.clone(), read_error.clone());
+    let body = stream::once(async move { Ok(head.into_bytes()) })
+        .chain(body)
+        .chain(stream::once(async move { Ok(tail.into_bytes()) }));
+
+    let progress = |sent_bytes| UploadProgress {
+        upload_id: id.clone(),
+        path: path.clone(),
+        sent_bytes,
+        total_bytes,
+    };
+    // Held until the backend has answered.
+    let _permit = tokio::select! {
+        permit = concurrency::acquire(app, Lane::Uploads) => permit,
+        _ = registration.cancelled() => {
+            log::info!("Queued upload of {} cancelled", path.display());
+            return Err(UploadError::Cancelled {
+                message: format!("Upload of {} was cancelled", path.display()),
+            });
+        }
+    };
+    // Checked once queued uploads ahead of this one have been stored; unknown space
+    // (an unreachable share) lets it through.
+    if let Ok(space) = diskspace::probe(app).await {
+        if space.below_floor() {
+            let free_bytes = space.free_bytes.unwrap_or_default();
+            return Err(UploadError::LowDiskSpace {
+                free_bytes,
+                floor_bytes: space.floor_bytes,
+                message: format!(
+                    "Only {} MB free in {}; uploads need at least {} MB",
+                    free_bytes / (1024 * 1024),
+                    space.path.display(),
+                    space.floor_bytes / (1024 * 1024)
+                ),
+            });
+        }
+    }
+    let transport = endpoint::transport(app);
+    let timeout = config::request_timeout(app, UPLOAD_TIMEOUT_CLASS);
+    let request = transport.send(Request {
+        method: reqwest::Method::POST,
+        path: UPLOAD_PATH,
+        body: Some(Body {
+            content_type: format!("multipart/form-data; boundary={boundary}"),
+            length,
+            data: reqwest::Body::wrap_stream(body),
+        }),
+        timeout,
+        max_response_bytes: None,
+    });
+    tokio::pin!(request);
+    let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+    let result = loop {
+        tokio::select! {
+            result = &mut request => break result,
+            _ = registration.cancelled() => {
+                log::info!("Upload of {} cancelled", path.display());
+                return Err(UploadError::Cancelled {
+                    message: format!("Upload of {} was cancelled", path.display()),
+                });
+            }
+            _ = ticker.tick() => {
+                let _ = app.emit("upload-progress", progress(sent.load(Ordering::Relaxed)));
+            }
+        }
+    };
+    let _ = app.emit("upload-progress", progress(sent.load(Ordering::Relaxed)));
+
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(e) => {
+            return Err(match read_error.lock().unwrap().take() {
+                Some(message) => UploadError::Unreadable { message },
+                None if e.timed_out => UploadError::ConnectionLost {
+                    message: format!(
+                        "Upload of {} timed out after {} ({} timeout)",
+                        path.display(),
+                        proxy::describe(timeout),
+                        UPLOAD_TIMEOUT_CLASS.name()
+                    ),
+                },
+                None => UploadError::ConnectionLost {
+                    message: format!("Connection lost while uploading {}: {e}", path.display()),
+                },
+            });
+        }
+    };
+    if !resp.is_success() {
+        let detail = resp
+            .json::<serde_json::Value>()
+            .ok()
+            .and_then(|body| {
+                body.get("detail")
+                    .and_then(|d| d.as_str())
+                    .map(String::from)
+            })
+            .unwrap_or_else(|| resp.text());
+        return Err(UploadError::Rejected {
+            status: resp.status,
+            message: detail,
+        });
+    }
+    invoices::invalidate(app);
+    resp.json().map_err(|e| UploadError::Rejected {
+        status: resp.status,
+        message: format!("Backend sent an unreadable answer: {e}"),
+    })
+}
+
+/// The file's chunks, counting bytes read into `sent` and keeping any read error.
+fn file_stream(
+    file: tokio::fs::File,
+    sent: Arc<AtomicU64>,
+    read_error: Arc<Mutex<Option<String>>>,
+) -> impl futures_util::Stream<Item = Result<Vec<u8>, std::io::Error>> {
+    stream::unfold(Some(file), move |file| {
+        let sent = sent.clone();
+        let read_error = read_error.clone();
+        async move {
+            let mut file = file?;
+            let mut chunk = vec![0u8; CHUNK_SIZE];
+            match file.read(&mut chunk).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    chunk.truncate(n);
+                    sent.fetch_add(n as u64, Ordering::Relaxed);
+                    Some((Ok(chunk), Some(file)))
+                }
+                Err(e) => {
+                    *read_error.lock().unwrap() = Some(format!("Failed to read file: {e}"));
+                    Some((Err(e), None))
+                }
+            }
+        }
+    })
+}
+
+fn content_type(path: &Path) -> Result<&'static str, UploadError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    CONTENT_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, content_type)| *content_type)
+        .ok_or_else(|| UploadError::InvalidFile {
+            message: format!(
+                "Unsupported file type {:?}. Allowed: PDF, JPG, JPEG, PNG, BMP, TIFF, WEBP",
+                extension
+            ),
+        })
+}
+
+fn unreadable(path: &Path, e: std::io::Error) -> UploadError {
+    UploadError::Unreadable {
+        message: format!("Failed to read {}: {e}", path.display()),
+    }
+}
+
+/// A file name safe inside a quoted multipart header, escaped the way browsers do.
+fn quote_file_name(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
@@ -0,0 +1,108 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Check that the backend on our port speaks the API this shell was built against.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::transport::BackendTransport;
+
+/// Backend API version this shell was built against. A backend is compatible when it has
+/// the same major version and at least this minor version (same minor below 1.0).
+pub(crate) const EXPECTED_BACKEND_API: (u64, u64) = (1, 0);
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Payload of the `backend-version-mismatch` event.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct VersionMismatch {
+    pub expected: String,
+    pub backend: String,
+}
+
+/// What `/version` (or the API root, on backends without it) reports.
+#[derive(Debug, Default, Deserialize)]
+struct VersionBody {
+    #[serde(default)]
+    api_version: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// `EXPECTED_BACKEND_API` as a requirement string, e.g. "^1.0".
+pub(crate) fn expected() -> String {
+    let (major, minor) = EXPECTED_BACKEND_API;
+    format!("^{major}.{minor}")
+}
+
+/// `(major, minor)` of a version such as "1.2.3" or "v1.2".
+pub(crate) fn major_minor(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(minor) => minor
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?,
+        None => 0,
+    };
+    Some((major, minor))
+}
+
+/// Whether a backend reporting `version` is compatible with `EXPECTED_BACKEND_API`.
+pub(crate) fn is_compatible(version: &str) -> bool {
+    let Some((major, minor)) = major_minor(version) else {
+        return false;
+    };
+    let (expected_major, expected_minor) = EXPECTED_BACKEND_API;
+    if expected_major == 0 {
+        major == 0 && minor == expected_minor
+    } else {
+        major == expected_major && minor >= expected_minor
+    }
+}
+
+async fn get_version(
+    transport: &BackendTransport,
+    path: &str,
+) -> Result<Option<VersionBody>, String> {
+    let resp = transport
+        .get(path, VERSION_TIMEOUT)
+        .await
+        .map_err(|e| format!("Backend not reachable: {e}"))?;
+    if resp.status == 404 {
+        return Ok(None);
+    }
+    if !resp.is_success() {
+        return Err(format!("{path} returned HTTP {}", resp.status));
+    }
+    resp.json()
+        .map(Some)
+        .map_err(|e| format!("Unreadable {path} response: {e}"))
+}
+
+/// The API version the backend reports, `None` if it doesn't say.
+pub(crate) async fn backend_version(
+    transport: &BackendTransport,
+) -> Result<Option<String>, String> {
+    let body = match get_version(transport, "/version").await? {
+        Some(body) => body,
+        // Backends without `/version` still report their version at the API root.
+        None => get_version(transport, "/").await?.unwrap_or_default(),
+    };
+    Ok(body.api_version.or(body.version))
+}
@@ -0,0 +1,184 @@
+// Copyright 2026 Floriane TUERNAL SABOTINOV
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Notice that the machine just woke from sleep, so health checks failing while the network
+//! and the backend come back are not mistaken for a dead backend.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+/// How long after a wake-up failed health checks count as "recovering from sleep".
+pub(crate) const GRACE: Duration = Duration::from_secs(30);
+/// How much longer than asked a sleep may take before we assume the machine was suspended.
+const JUMP_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// When the last wake-up was noticed, managed by Tauri.
+#[derive(Default)]
+pub(crate) struct Wake(Mutex<Option<Instant>>);
+
+impl Wake {
+    /// Check a sleep from `before` to `after` that was meant to last `expected`. If it
+    /// spanned a suspend, start the grace window and forget `failures`: they piled up while
+    /// the backend was frozen along with the machine. Returns whether it did.
+    fn after_sleep(
+        &self,
+        before: &ClockMark,
+        after: &ClockMark,
+        expected: Duration,
+        failures: &mut u32,
+    ) -> bool {
+        if !before.spanned_suspend(after, expected) {
+            return false;
+        }
+        log::info!(
+            "System resumed from sleep, giving the backend {}s to recover",
+            GRACE.as_secs()
+        );
+        *self.0.lock().unwrap() = Some(after.monotonic);
+        *failures = 0;
+        true
+    }
+
+    /// Whether `now` is within `GRACE` of the last wake-up.
+    fn recovering_at(&self, now: Instant) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .is_some_and(|at| now.saturating_duration_since(at) < GRACE)
+    }
+}
+
+/// Both clocks read before a sleep, to compare against once it is over.
+pub(crate) struct ClockMark {
+    monotonic: Instant,
+    wall: SystemTime,
+}
+
+impl ClockMark {
+    pub(crate) fn now() -> Self {
+        Self {
+            monotonic: Instant::now(),
+            wall: SystemTime::now(),
+        }
+    }
+
+    /// Whether the sleep from this mark to `later`, meant to last `expected`, spanned a
+    /// system suspend.
+    fn spanned_suspend(&self, later: &ClockMark, expected: Duration) -> bool {
+        let wall = later.wall.duration_since(self.wall).ok();
+        let monotonic = later.monotonic.saturating_duration_since(self.monotonic);
+        spanned_suspend(expected, monotonic, wall)
+    }
+}
+
+/// Whether a sleep meant to last `expected` took so much longer by either clock that the
+/// machine must have been suspended in between.
+///
+/// The monotonic clock stops during suspend on Linux and macOS but not on Windows; the wall
+/// clock always runs on, but may be set back, which leaves `wall` as `None`.
+fn spanned_suspend(expected: Duration, monotonic: Duration, wall: Option<Duration>) -> bool {
+    let observed = wall.map_or(monotonic, |wall| wall.max(monotonic));
+    observed > expected + JUMP_THRESHOLD
+}
+
+/// [`Wake::after_sleep`] for a sleep that started at `before` and is over now.
+pub(crate) fn after_sleep(
+    app: &AppHandle,
+    before: &ClockMark,
+    expected: Duration,
+    failures: &mut u32,
+) -> bool {
+    app.state::<Wake>()
+        .after_sleep(before, &ClockMark::now(), expected, failures)
+}
+
+/// Whether we are within `GRACE` of the last wake-up.
+pub(crate) fn recovering(app: &AppHandle) -> bool {
+    app.state::<Wake>().recovering_at(Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    /// A mark `monotonic` and `wall` after `start` by each clock.
+    fn later(start: &ClockMark, monotonic: Duration, wall: Duration) -> ClockMark {
+        ClockMark {
+            monotonic: start.monotonic + monotonic,
+            wall: start.wall + wall,
+        }
+    }
+
+    #[test]
+    fn on_time_sleep_keeps_the_failure_count() {
+        let wake = Wake::default();
+        let before = ClockMark::now();
+        let after = later(&before, INTERVAL, INTERVAL + Duration::from_secs(1));
+        let mut failures = 3;
+
+        assert!(!wake.after_sleep(&before, &after, INTERVAL, &mut failures));
+        assert_eq!(failures, 3);
+        assert!(!wake.recovering_at(after.monotonic));
+    }
+
+    #[test]
+    fn monotonic_jump_is_a_resume() {
+        let wake = Wake::default();
+        let before = ClockMark::now();
+        let hour = Duration::from_secs(3600);
+        let after = later(&before, hour, hour);
+        let mut failures = 3;
+
+        assert!(wake.after_sleep(&before, &after, INTERVAL, &mut failures));
+        assert_eq!(failures, 0);
+    }
+
+    /// Windows keeps the monotonic clock stopped through suspend; only the wall clock jumps.
+    #[test]
+    fn wall_clock_jump_alone_is_a_resume() {
+        let wake = Wake::default();
+        let before = ClockMark::now();
+        let after = later(&before, INTERVAL, Duration::from_secs(3600));
+        let mut failures = 2;
+
+        assert!(wake.after_sleep(&before, &after, INTERVAL, &mut failures));
+        assert_eq!(failures, 0);
+    }
+
+    #[test]
+    fn wall_clock_set_back_is_not_a_resume() {
+        let before = ClockMark::now();
+        let after = ClockMark {
+            monotonic: before.monotonic + INTERVAL,
+            wall: before.wall - Duration::from_secs(3600),
+        };
+        assert!(!before.spanned_suspend(&after, INTERVAL));
+    }
+
+    #[test]
+    fn grace_window_ends_after_grace() {
+        let wake = Wake::default();
+        let before = ClockMark::now();
+        let hour = Duration::from_secs(3600);
+        let after = later(&before, hour, hour);
+        wake.after_sleep(&before, &after, INTERVAL, &mut 0);
+
+        assert!(wake.recovering_at(after.monotonic));
+        assert!(wake.recovering_at(after.monotonic + GRACE - Duration::from_secs(1)));
+        assert!(!wake.recovering_at(after.monotonic + GRACE));
+    }
+}
@@ -17,25 +17,53 @@
  * Handles error handling, logging, and response typing
  */
 
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { ApiException } from './types';
 
+// Tauri production mode: no Vite proxy, connect directly to the backend,
+// whose port is picked at launch and may change when it restarts
+const IS_TAURI_PRODUCTION = !!(window as any).__TAURI_INTERNALS__ && !import.meta.env.DEV;
+
+// Per-launch token the backend requires; rotates whenever the backend restarts
+let apiToken: string | null = null;
+let authReady: Promise<void> = Promise.resolve();
+
+function refreshAuth(): Promise<void> {
+  authReady = invoke<string | null>('get_backend_auth').then(
+    (token) => {
+      apiToken = token;
+    },
+    () => {
+      apiToken = null;
+    }
+  );
+  return authReady;
+}
+
 // Base URL detection:
 // 1. Explicit env var takes precedence
-// 2. In Tauri production mode (no dev server proxy), connect directly to backend
+// 2. In Tauri production mode, ask the app (and follow backend-ready events)
 // 3. Empty string = use Vite dev proxy
 function detectBaseUrl(): string {
   const explicit = import.meta.env.VITE_API_BASE_URL;
   if (explicit) return explicit;
 
-  // Tauri production mode: no Vite proxy, connect directly to backend
-  if ((window as any).__TAURI_INTERNALS__ && !import.meta.env.DEV) {
-    return 'http://127.0.0.1:8000';
+  if (IS_TAURI_PRODUCTION) {
+    invoke<string>('get_backend_url').then((url) => {
+      apiBaseUrl = url;
+    });
+    refreshAuth();
+    listen<{ url: string }>('backend-ready', (event) => {
+      apiBaseUrl = event.payload.url;
+      refreshAuth();
+    });
   }
 
   return '';
 }
 
-const API_BASE_URL = detectBaseUrl();
+let apiBaseUrl = detectBaseUrl();
 const API_PREFIX = '/api/v1';
 
 // Enable debug logging in development
@@ -56,7 +84,26 @@ function logError(message: string, error: unknown): void {
  */
 export function buildUrl(endpoint: string): string {
   const path = endpoint.startsWith('/') ? endpoint : `/${endpoint}`;
-  return `${API_BASE_URL}${API_PREFIX}${path}`;
+  return `${apiBaseUrl}${API_PREFIX}${path}`;
+}
+
+/**
+ * Build URL for a resource loaded directly by the webview (img, iframe),
+ * which cannot send headers, so the token goes in the query string
+ */
+export function buildResourceUrl(endpoint: string): string {
+  const url = buildUrl(endpoint);
+  if (!apiToken) return url;
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}token=${encodeURIComponent(apiToken)}`;
+}
+
+/**
+ * Authorization header for the current backend, if it requires one
+ */
+async function authHeaders(): Promise<Record<string, string>> {
+  await authReady;
+  return apiToken ? { Authorization: `Bearer ${apiToken}` } : {};
 }
 
 /**
@@ -80,8 +127,14 @@ async function parseError(response: Response): Promise<ApiException> {
     // Use default error message
   }
 
-  // User-friendly error messages for common cases
-  switch (response.status) {
+  return friendlyError(response.status, detail);
+}
+
+/**
+ * ApiException with user-friendly messages for common cases
+ */
+function friendlyError(status: number, detail: string): ApiException {
+  switch (status) {
     case 400:
       if (detail.includes('already exists')) {
         detail = 'A file with this name already exists';
@@ -102,7 +155,913 @@ async function parseError(response: Response): Promise<ApiException> {
       break;
   }
 
-  return new ApiException(response.status, detail);
+  return new ApiException(status, detail);
+}
+
+interface ProxyResponse {
+  status: number;
+  headers: Record<string, string>;
+  body: unknown;
+  retryable: boolean;
+  cached: boolean;
+}
+
+interface ProxyError {
+  message: string;
+  retryable: boolean;
+  cancelled: boolean;
+}
+
+/**
+ * How long the app waits for a proxied call; long-running calls are meant to be
+ * cancelled rather than timed out
+ */
+export type TimeoutClass = 'fast' | 'normal' | 'long_running' | 'streaming';
+
+export interface RequestOptions {
+  /** Lets cancelBackendRequest abort the call */
+  requestId?: string;
+  timeoutClass?: TimeoutClass;
+}
+
+/**
+ * Fresh id for a request that may be cancelled with cancelBackendRequest
+ */
+export function newRequestId(): string {
+  return crypto.randomUUID();
+}
+
+/**
+ * Abort a proxied request, upload or download started with `requestId`;
+ * it then rejects with an ApiException whose `cancelled` is true
+ */
+export async function cancelBackendRequest(requestId: string): Promise<void> {
+  await invoke('cancel_backend_request', { requestId });
+}
+
+/** Size and hit ratio of the app's response cache */
+export interface CacheStats {
+  entries: number;
+  bytes: number;
+  max_entries: number;
+  max_bytes: number;
+  hits: number;
+  misses: number;
+  hit_ratio: number;
+  evictions: number;
+}
+
+/**
+ * Drop the app's cached responses for `prefix` (e.g. '/api/v1/invoices')
+ * and the paths below it; '/' drops everything. Resolves to how many were dropped
+ */
+export async function invalidateCache(prefix: string): Promise<number> {
+  return invoke<number>('invalidate_cache', { prefix });
+}
+
+export async function getCacheStats(): Promise<CacheStats> {
+  return invoke<CacheStats>('get_cache_stats');
+}
+
+/**
+ * Caches purgeCache can delete: the backend's page renders, its temp dir, the
+ * app's response cache and the backend's own files of expired jobs
+ */
+export type CacheKind = 'thumbnails' | 'temp' | 'http_cache' | 'backend_cache';
+
+/** What purgeCache removed of one kind */
+export interface PurgedCache {
+  kind: CacheKind;
+  /** null when it can't be measured, e.g. for an external backend */
+  freed_bytes: number | null;
+  removed: number;
+  /** Files left because they may be in use */
+  skipped: number;
+  /** Why this kind was not purged, or not completely */
+  error: string | null;
+}
+
+/** Payload of purgeCache and `cache-purged` */
+export interface CachePurge {
+  caches: PurgedCache[];
+  freed_bytes: number;
+}
+
+/**
+ * Delete the caches of `kinds` (Tauri only), leaving files that may be in use.
+ * Open views are told through onCachePurged
+ */
+export async function purgeCache(kinds: CacheKind[]): Promise<CachePurge> {
+  return invoke<CachePurge>('purge_cache', { kinds });
+}
+
+/** Be told when caches were purged, to refresh. The returned function stops listening */
+export async function onCachePurged(onPurged: (purge: CachePurge) => void): Promise<() => void> {
+  return listen<CachePurge>('cache-purged', (event) => onPurged(event.payload));
+}
+
+/** Delete the page renders and temp files whenever the app exits (Tauri only) */
+export async function setClearCachesOnExit(enabled: boolean): Promise<void> {
+  await invoke('set_clear_caches_on_exit', { enabled });
+}
+
+/** A batch of backend output, about every 100ms while forwarding is on */
+/** One line of backend output; JSON lines have their fields parsed */
+export interface BackendLogRecord {
+  /** 'note' marks the app's own entries, e.g. backend start and exit */
+  stream: 'stdout' | 'stderr' | 'note';
+  /** The line as printed */
+  line: string;
+  ts: number;
+  level: LogLevel | null;
+  message: string;
+  module: string | null;
+  structured: boolean;
+  /** Other fields of a JSON line */
+  fields?: Record<string, unknown>;
+}
+
+export interface BackendLogBatch {
+  lines: BackendLogRecord[];
+  /** Lines left out to keep the rate down; the log file still has them */
+  dropped: number;
+}
+
+/**
+ * Follow the backend's output live (Tauri only), e.g. while a console panel
+ * is open, from `minLevel` up if given (lines without a level count as info).
+ * The returned function stops listening and turns forwarding off
+ */
+export async function watchBackendLog(
+  onBatch: (batch: BackendLogBatch) => void,
+  minLevel?: LogLevel
+): Promise<() => void> {
+  const unlisten = await listen<BackendLogBatch>('backend-log', (event) => {
+    onBatch(event.payload);
+  });
+  await invoke('set_backend_log_forwarding', { enabled: true, minLevel: minLevel ?? null });
+  return () => {
+    unlisten();
+    invoke('set_backend_log_forwarding', { enabled: false });
+  };
+}
+
+/**
+ * Open the backend console window (Tauri only), or focus it if it is open.
+ * While it is, `backend-log` events go to it alone
+ */
+export async function openBackendConsole(): Promise<void> {
+  return invoke('open_backend_console');
+}
+
+/**
+ * The last ~2000 records of backend output kept in memory, oldest first,
+ * across backend restarts (Tauri only). Shown before live batches arrive
+ */
+export async function getLogBacklog(): Promise<BackendLogRecord[]> {
+  return invoke<BackendLogRecord[]>('get_log_backlog');
+}
+
+/** Payload of the `shell-error` event: a panic in the app itself */
+export interface ShellError {
+  message: string;
+  thread: string;
+  location: string | null;
+}
+
+/**
+ * Be told when the app hits an internal error (Tauri only), to show "an
+ * internal error occurred, see logs". Details are in shell-panics.log in the
+ * logs folder. The returned function stops listening
+ */
+export async function onShellError(onError: (error: ShellError) => void): Promise<() => void> {
+  return listen<ShellError>('shell-error', (event) => onError(event.payload));
+}
+
+/** Tail of the backend log, oldest record first */
+export interface BackendLogTail {
+  path: string;
+  records: BackendLogRecord[];
+}
+
+/**
+ * Last `lines` records of the backend's output (Tauri only), from one stream
+ * and from `minLevel` up if given, plus the log file's path for opening it
+ */
+export async function getBackendLogs(
+  lines: number,
+  stream?: 'stdout' | 'stderr',
+  minLevel?: LogLevel
+): Promise<BackendLogTail> {
+  return invoke<BackendLogTail>('get_backend_logs', {
+    lines,
+    stream: stream ?? null,
+    minLevel: minLevel ?? null,
+  });
+}
+
+/** How much backend log is kept on disk */
+export interface LogRetention {
+  max_size_mb: number;
+  /** Files kept, the live one included */
+  max_files: number;
+  /** Older rotated files are deleted; null keeps them by count only */
+  max_age_days: number | null;
+}
+
+/** Change log retention (Tauri only); applies from the next rotation */
+export async function setLogRetention(retention: LogRetention): Promise<void> {
+  await invoke('set_log_retention', { retention });
+}
+
+/** Why a folder could not be shown; `path` is there to display instead when known */
+export type OpenFolderError =
+  | { kind: 'unresolved'; message: string }
+  | { kind: 'create_failed' | 'no_opener' | 'open_failed'; path: string; message: string };
+
+/** Show the app's log folder in the file manager (Tauri only); resolves to its path */
+export async function openLogsFolder(): Promise<string> {
+  return invoke<string>('open_logs_folder');
+}
+
+/** Show the app's data folder in the file manager (Tauri only); resolves to its path */
+export async function openDataFolder(): Promise<string> {
+  return invoke<string>('open_data_folder');
+}
+
+/** Where the backend keeps invoice data */
+export interface DataDir {
+  path: string;
+  /** The app data dir, not a folder the user chose */
+  is_default: boolean;
+}
+
+/** Payload of `data-dir-migration-progress` while setDataDir copies */
+export interface DataDirMigrationProgress {
+  copied_bytes: number;
+  total_bytes: number;
+  files_done: number;
+  files_total: number;
+}
+
+export async function getDataDir(): Promise<DataDir> {
+  return invoke<DataDir>('get_data_dir');
+}
+
+/**
+ * Move the invoice data to `newPath`, an empty folder with room for it (Tauri
+ * only). The backend is stopped meanwhile; on failure the data stays where it
+ * was. `onProgress` follows the copy
+ */
+export async function setDataDir(
+  newPath: string,
+  onProgress?: (progress: DataDirMigrationProgress) => void
+): Promise<DataDir> {
+  const unlisten = onProgress
+    ? await listen<DataDirMigrationProgress>('data-dir-migration-progress', (event) =>
+        onProgress(event.payload)
+      )
+    : null;
+  try {
+    return await invoke<DataDir>('set_data_dir', { newPath });
+  } finally {
+    unlisten?.();
+  }
+}
+
+/** Payload of `data-dir-locked`: the app instance already using the data */
+export interface DataDirLocked {
+  data_dir: string;
+  /** null while the other instance is still taking the lock */
+  pid: number | null;
+  /** Its start time, in seconds since the epoch */
+  started_at: number | null;
+  host: string | null;
+  exe: string | null;
+}
+
+/**
+ * Be told when the backend wasn't started because another instance of the app
+ * uses the same data (Tauri only). The returned function stops listening
+ */
+export async function onDataDirLocked(
+  onLocked: (locked: DataDirLocked) => void
+): Promise<() => void> {
+  return listen<DataDirLocked>('data-dir-locked', (event) => onLocked(event.payload));
+}
+
+/** Payload of `legacy-data-found`: data of the pre-app standalone backend */
+export interface LegacyDataFound {
+  path: string;
+  files: number;
+  bytes: number;
+}
+
+/** What migrateLegacyData imported */
+export interface LegacyImport {
+  files: number;
+  bytes: number;
+  /** Counted by the backend after the import; null if it couldn't be asked */
+  invoices: number | null;
+  other_documents: number | null;
+  /** Every file matched its original and the backend started on them */
+  verified: boolean;
+}
+
+/**
+ * Be told at launch that the old standalone backend's data can be imported
+ * (Tauri only). It may be sent before the listener exists: check
+ * getLegacyData too
+ */
+export async function onLegacyDataFound(
+  onFound: (found: LegacyDataFound) => void
+): Promise<() => void> {
+  return listen<LegacyDataFound>('legacy-data-found', (event) => onFound(event.payload));
+}
+
+/** The legacy data still on offer, if any (Tauri only) */
+export async function getLegacyData(): Promise<LegacyDataFound | null> {
+  return invoke<LegacyDataFound | null>('get_legacy_data');
+}
+
+/**
+ * Import the legacy data with `confirm`, or decline it for good (Tauri only);
+ * null when declined. The legacy folder is never changed. `onProgress`
+ * follows the copy
+ */
+export async function migrateLegacyData(
+  confirm: boolean,
+  onProgress?: (progress: DataDirMigrationProgress) => void
+): Promise<LegacyImport | null> {
+  const unlisten = onProgress
+    ? await listen<DataDirMigrationProgress>('legacy-migration-progress', (event) =>
+        onProgress(event.payload)
+      )
+    : null;
+  try {
+    return await invoke<LegacyImport | null>('migrate_legacy_data', { confirm });
+  } finally {
+    unlisten?.();
+  }
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/** Log levels in effect; `backend` is null when the backend can't tell */
+export interface LogLevels {
+  shell: LogLevel;
+  backend: LogLevel | null;
+}
+
+/** Current log levels of the app and its backend (Tauri only) */
+export async function getLogLevel(): Promise<LogLevels> {
+  return invoke<LogLevels>('get_log_level');
+}
+
+/**
+ * Change the log level of the app and its backend (Tauri only). Levels above
+ * info fall back to info after a day
+ */
+export async function setLogLevel(level: LogLevel): Promise<LogLevels> {
+  return invoke<LogLevels>('set_log_level', { level });
+}
+
+/** A diagnostics probe's value, or why it could not be taken */
+export type Probe<T> = T | { unavailable: string };
+
+/** Environment report from collectDiagnostics */
+export interface Diagnostics {
+  app_version: string;
+  tauri_version: string;
+  webview_version: Probe<string>;
+  os: string;
+  os_version: Probe<string>;
+  arch: string;
+  resource_dir: Probe<string>;
+  app_data_dir: Probe<string>;
+  app_log_dir: Probe<string>;
+  shell_log: Probe<string>;
+  backend_exe: Probe<{
+    path: string;
+    exists: boolean;
+    bytes: number | null;
+    modified_ms: number | null;
+  }>;
+  backend_url: string;
+  backend_host: Probe<string>;
+  backend_port: Probe<number>;
+  status: { status: string };
+  restart_count: number;
+  last_health_latency_ms: Probe<number>;
+  free_disk_bytes: Probe<number>;
+  last_startup: Probe<StartupRun>;
+}
+
+/** Versions, paths, backend state and disk space for a Diagnostics panel (Tauri only) */
+export async function collectDiagnostics(): Promise<Diagnostics> {
+  return invoke<Diagnostics>('collect_diagnostics');
+}
+
+/**
+ * One backend launch. Milestones are milliseconds after `started_at_ms`,
+ * null if the launch never got there
+ */
+export interface StartupRun {
+  started_at_ms: number;
+  external: boolean;
+  outcome: 'ready' | 'spawn_failed' | 'not_ready' | 'incompatible' | 'cancelled' | null;
+  process_launched_ms: number | null;
+  port_open_ms: number | null;
+  first_healthy_ms: number | null;
+  ready_ms: number | null;
+  ready_emitted_ms: number | null;
+}
+
+/** Timings of the last 20 backend launches, oldest first (Tauri only) */
+export async function getStartupMetrics(): Promise<{ runs: StartupRun[] }> {
+  return invoke<{ runs: StartupRun[] }>('get_startup_metrics');
+}
+
+/** Backend plus its workers, from getBackendResourceUsage */
+export type BackendResources =
+  | { status: 'not_running' }
+  | {
+      status: 'running';
+      pid: number;
+      rss_bytes: number;
+      /** Share of the whole machine, 0-100 */
+      cpu_percent: number;
+      open_files: number | null;
+      child_processes: number;
+    };
+
+/** Memory and CPU of the backend for a status bar readout (Tauri only) */
+export async function getBackendResourceUsage(): Promise<BackendResources> {
+  return invoke<BackendResources>('get_backend_resource_usage');
+}
+
+/** When the app warns about the backend's memory and CPU */
+export interface ResourceThresholds {
+  rss_mb: number;
+  /** 1 to 100, held for cpu_sustain_secs */
+  cpu_percent: number;
+  cpu_sustain_secs: number;
+  /** Least time between two warnings about the same metric */
+  cooldown_secs: number;
+  /** Restart a backend over rss_mb that has no jobs running */
+  restart_on_memory: boolean;
+}
+
+/** Payload of `backend-resource-warning` */
+export interface ResourceWarning {
+  metric: 'rss_mb' | 'cpu_percent';
+  value: number;
+  threshold: number;
+  pid: number;
+  restarting: boolean;
+}
+
+export async function getResourceThresholds(): Promise<ResourceThresholds> {
+  return invoke<ResourceThresholds>('get_resource_thresholds');
+}
+
+/** Takes effect from the next sample, about every 10s (Tauri only) */
+export async function setResourceThresholds(thresholds: ResourceThresholds): Promise<void> {
+  return invoke('set_resource_thresholds', { thresholds });
+}
+
+/**
+ * Be told when the backend goes over its memory or CPU threshold (Tauri
+ * only). The returned function stops listening
+ */
+export async function onResourceWarning(
+  onWarning: (warning: ResourceWarning) => void
+): Promise<() => void> {
+  return listen<ResourceWarning>('backend-resource-warning', (event) => onWarning(event.payload));
+}
+
+/** When uploads are refused and `low-disk-space` is emitted */
+export interface DiskSpaceThresholds {
+  /** Uploads are refused below this; 0 never refuses */
+  floor_mb: number;
+  warn_mb: number;
+}
+
+/** Free space where the backend keeps its data; also the `low-disk-space` payload */
+export interface DiskSpace {
+  path: string;
+  /** null when the volume can't be measured, e.g. an unreachable network share */
+  free_bytes: number | null;
+  floor_bytes: number;
+  warn_bytes: number;
+}
+
+export async function getDiskSpace(): Promise<DiskSpace> {
+  return invoke<DiskSpace>('get_disk_space');
+}
+
+export async function getDiskSpaceThresholds(): Promise<DiskSpaceThresholds> {
+  return invoke<DiskSpaceThresholds>('get_disk_space_thresholds');
+}
+
+/** warn_mb must not be below floor_mb (Tauri only) */
+export async function setDiskSpaceThresholds(thresholds: DiskSpaceThresholds): Promise<void> {
+  return invoke('set_disk_space_thresholds', { thresholds });
+}
+
+/**
+ * Be told when free space for the data drops under the warning threshold,
+ * checked every minute (Tauri only). The returned function stops listening
+ */
+export async function onLowDiskSpace(onLow: (space: DiskSpace) => void): Promise<() => void> {
+  return listen<DiskSpace>('low-disk-space', (event) => onLow(event.payload));
+}
+
+/** Disk space taken by the app's data, by category, in bytes */
+export interface StorageUsage {
+  data_dir: string;
+  /** The SQLite database and its journals */
+  database_bytes: number;
+  /** Uploaded invoices and the documents kept for them */
+  originals_bytes: number;
+  /** Page renders and downloaded OCR models */
+  cache_bytes: number;
+  log_bytes: number;
+  temp_bytes: number;
+  other_bytes: number;
+  total_bytes: number;
+  /** null when the volume couldn't be measured in time */
+  free_bytes: number | null;
+  /** When the sizes were measured, in ms since the epoch */
+  measured_ms: number;
+}
+
+/**
+ * What the app's data takes up on disk (Tauri only). Sizes come from a scan
+ * of the last minute unless `refresh`
+ */
+export async function getStorageUsage(refresh = false): Promise<StorageUsage> {
+  return invoke<StorageUsage>('get_storage_usage', { refresh });
+}
+
+/**
+ * Copy the diagnostics as a Markdown table for a support chat or GitHub issue
+ * (Tauri only). Home directory and credentials are masked. Resolves to the
+ * copied text, for a preview
+ */
+export async function copyDiagnosticsToClipboard(): Promise<string> {
+  return invoke<string>('copy_diagnostics_to_clipboard');
+}
+
+/** One queued telemetry event; never contains invoice data */
+export type TelemetryEvent = { at_ms: number } & (
+  | { kind: 'app_start'; app_version: string; os: string; arch: string }
+  | { kind: 'backend_ready'; startup_ms: number; attempt: number }
+  | {
+      kind: 'backend_crash';
+      code: number | null;
+      signal: number | null;
+      memory_limit_hit: boolean;
+      restart_count: number;
+    }
+);
+
+/** What getTelemetryPreview reports */
+export interface TelemetryPreview {
+  enabled: boolean;
+  endpoint: string | null;
+  queued: number;
+  /** Exactly what the next send would post */
+  next_batch: { install_id: string; events: TelemetryEvent[] } | null;
+}
+
+/** Opt in to or out of anonymous telemetry; only on an explicit user choice (Tauri only) */
+export async function setTelemetryEnabled(enabled: boolean): Promise<void> {
+  return invoke<void>('set_telemetry_enabled', { enabled });
+}
+
+/** Show the user what telemetry would send (Tauri only) */
+export async function getTelemetryPreview(): Promise<TelemetryPreview> {
+  return invoke<TelemetryPreview>('get_telemetry_preview');
+}
+
+/** Zip written by createSupportBundle */
+export interface SupportBundle {
+  path: string;
+  bytes: number;
+}
+
+/**
+ * Zip the backend log tail, diagnostics and redacted settings for a support
+ * request (Tauri only). `destination` comes from a save dialog; a folder gets
+ * a timestamped file inside it. No invoice content is included
+ */
+export async function createSupportBundle(destination: string): Promise<SupportBundle> {
+  return invoke<SupportBundle>('create_support_bundle', { destination });
+}
+
+/** Zip written by exportLogs, and the files it holds */
+export interface LogExport {
+  path: string;
+  bytes: number;
+  files: { name: string; size: number }[];
+  /** Files that could not be read, each with the reason */
+  skipped: string[];
+}
+
+/**
+ * Zip every file in the logs folder (Tauri only) to `destination`, or into it
+ * if it is a folder. Without `destination` the app shows a save dialog;
+ * null means the user cancelled it
+ */
+export async function exportLogs(destination?: string): Promise<LogExport | null> {
+  return invoke<LogExport | null>('export_logs', { destination: destination ?? null });
+}
+
+/** Zip written by backupData */
+export interface DataBackup {
+  path: string;
+  /** Files in the zip besides its manifest */
+  files: number;
+  /** Their size before compression */
+  data_bytes: number;
+  /** Size of the zip */
+  bytes: number;
+}
+
+/** Payload of `backup-progress` */
+export interface BackupProgress {
+  written_bytes: number;
+  total_bytes: number;
+  files_done: number;
+  files_total: number;
+}
+
+/**
+ * Zip the invoice data, with a consistent copy of the database (Tauri only),
+ * to `destination` or into it if it is a folder. Without `destination` the
+ * app shows a save dialog; null means the user cancelled it. `onProgress`
+ * follows the zip being written
+ */
+export async function backupData(
+  destination?: string,
+  onProgress?: (progress: BackupProgress) => void
+): Promise<DataBackup | null> {
+  const unlisten = onProgress
+    ? await listen<BackupProgress>('backup-progress', (event) => onProgress(event.payload))
+    : null;
+  try {
+    return await invoke<DataBackup | null>('backup_data', { destination: destination ?? null });
+  } finally {
+    unlisten?.();
+  }
+}
+
+/** SQLite file written by exportDatabase */
+export interface DatabaseExport {
+  path: string;
+  bytes: number;
+  /** Rows in the main tables the database has */
+  row_counts: Record<string, number>;
+}
+
+/** Why exportDatabase failed; no file is left at the destination */
+export type DatabaseExportError =
+  | { kind: 'unavailable'; message: string }
+  /** The database itself fails SQLite's integrity check */
+  | { kind: 'source_corrupt'; message: string; problems: string[] }
+  /** Writing the copy failed, or the copy came out damaged */
+  | { kind: 'copy_failed'; message: string };
+
+/**
+ * Copy the invoice database, as a consistent snapshot (Tauri only), to
+ * `destination` or into it if it is a folder. The copy is kept only if it
+ * passes an integrity check. Without `destination` the app shows a save
+ * dialog; null means the user cancelled it. Rejects with a DatabaseExportError
+ */
+export async function exportDatabase(destination?: string): Promise<DatabaseExport | null> {
+  return invoke<DatabaseExport | null>('export_database', { destination: destination ?? null });
+}
+
+/** What restoreData did; errors mean nothing was changed */
+export type RestoreOutcome =
+  | {
+      outcome: 'restored';
+      files: number;
+      bytes: number;
+      /** App version that wrote the backup */
+      backup_version: string;
+      /** The replaced data, kept until the next restore */
+      previous_data: string;
+    }
+  | {
+      /** The backend didn't start on the backup; the previous data is back */
+      outcome: 'rolled_back';
+      reason: string;
+      backend_ready: boolean;
+    };
+
+/** Payload of `restore-progress` */
+export interface RestoreProgress {
+  written_bytes: number;
+  total_bytes: number;
+  files_done: number;
+  files_total: number;
+}
+
+/**
+ * Replace the invoice data with a backupData zip (Tauri only). The backend is
+ * stopped meanwhile and the current data kept in a `.bak` folder beside it.
+ * `onProgress` follows the extraction
+ */
+export async function restoreData(
+  archivePath: string,
+  onProgress?: (progress: RestoreProgress) => void
+): Promise<RestoreOutcome> {
+  const unlisten = onProgress
+    ? await listen<RestoreProgress>('restore-progress', (event) => onProgress(event.payload))
+    : null;
+  try {
+    return await invoke<RestoreOutcome>('restore_data', { archivePath });
+  } finally {
+    unlisten?.();
+  }
+}
+
+/** One prepareForUpdate attempt, as recorded */
+export interface PreUpdateBackup {
+  /** The zip, in the backups folder of the data dir; restorable with restoreData */
+  path: string;
+  bytes: number;
+  /** False if it failed, or its zip has since been removed */
+  success: boolean;
+  error: string | null;
+  /** The version about to be installed */
+  target_version: string;
+  /** The version that made the backup */
+  app_version: string;
+  created_ms: number;
+}
+
+/**
+ * Back the database and settings up before installing `version` (Tauri only).
+ * Only the newest few are kept; see setPreUpdateBackupsKept
+ */
+export async function prepareForUpdate(version: string): Promise<PreUpdateBackup> {
+  return invoke<PreUpdateBackup>('prepare_for_update', { version });
+}
+
+/** The recorded pre-update backups, newest first (Tauri only) */
+export async function listPreUpdateBackups(): Promise<PreUpdateBackup[]> {
+  return invoke<PreUpdateBackup[]>('list_pre_update_backups');
+}
+
+/** Be told of each pre-update backup attempt. The returned function stops listening */
+export async function onPreUpdateBackup(
+  onBackup: (backup: PreUpdateBackup) => void
+): Promise<() => void> {
+  return listen<PreUpdateBackup>('pre-update-backup', (event) => onBackup(event.payload));
+}
+
+/** How many pre-update backups to keep; 0 keeps them all (Tauri only) */
+export async function setPreUpdateBackupsKept(kept: number): Promise<void> {
+  await invoke('set_pre_update_backups_kept', { kept });
+}
+
+/** A confirmation issued by requestFactoryReset */
+export interface FactoryResetRequest {
+  /** Pass to factoryReset; works once */
+  token: string;
+  expires_in_secs: number;
+  /** The folder whose contents would be deleted */
+  data_dir: string;
+}
+
+export interface FactoryResetOptions {
+  /** Also delete the downloaded OCR models and temp files */
+  clear_caches?: boolean;
+  /** Also delete the app's and the backend's logs */
+  clear_logs?: boolean;
+  /** Start a fresh backend (the default), or relaunch the app */
+  after?: 'respawn' | 'relaunch';
+}
+
+/** What factoryReset did */
+export interface FactoryReset {
+  removed: number;
+  /** What could not be deleted, and why */
+  failed: string[];
+  /** Where each step was logged; left in place by the reset */
+  audit_log: string;
+  /** Whether the fresh backend became ready; null when relaunching */
+  backend_ready: boolean | null;
+}
+
+/** Get the token factoryReset needs, for the user to confirm with (Tauri only) */
+export async function requestFactoryReset(): Promise<FactoryResetRequest> {
+  return invoke<FactoryResetRequest>('request_factory_reset');
+}
+
+/**
+ * Delete all invoice data and settings (Tauri only). `confirmToken` comes from
+ * requestFactoryReset. With `after: 'relaunch'` the app restarts shortly after answering
+ */
+export async function factoryReset(
+  confirmToken: string,
+  options: FactoryResetOptions = {}
+): Promise<FactoryReset> {
+  return invoke<FactoryReset>('factory_reset', { confirmToken, options });
+}
+
+/**
+ * Call the backend through the app's backend_request command,
+ * which adds the auth token and knows the current port
+ */
+async function proxyFetch<T>(
+  method: string,
+  endpoint: string,
+  body?: unknown,
+  options: RequestOptions = {},
+  responseType: 'auto' | 'base64' = 'auto'
+): Promise<T> {
+  const [path, search = ''] = endpoint.split('?', 2);
+  const query = Object.fromEntries(new URLSearchParams(search));
+
+  log(`${method} ${path} (proxied)`);
+
+  let response: ProxyResponse;
+  try {
+    response = await invoke<ProxyResponse>('backend_request', {
+      request: {
+        method,
+        path: `${API_PREFIX}${path.startsWith('/') ? path : `/${path}`}`,
+        query,
+        json_body: body,
+        request_id: options.requestId,
+        timeout_class: options.timeoutClass,
+        response_type: responseType,
+      },
+    });
+  } catch (error) {
+    const proxyError = error as ProxyError | null;
+    if (proxyError?.cancelled) {
+      const cancelled = new ApiException(0, 'Request cancelled');
+      cancelled.cancelled = true;
+      throw cancelled;
+    }
+    logError('Proxy error', error);
+    const retryable = proxyError?.retryable ?? false;
+    throw new ApiException(
+      0,
+      'Connection error. Please check that the backend is running.',
+      retryable
+    );
+  }
+
+  if (response.status < 200 || response.status >= 300) {
+    // A base64 error body is not worth showing
+    const data = (responseType === 'auto' ? response.body : null) as
+      | { detail?: string; message?: string }
+      | string
+      | null;
+    let detail = `Error ${response.status}`;
+    if (typeof data === 'string' && data) {
+      detail = data;
+    } else if (data && typeof data === 'object') {
+      detail = data.detail || data.message || detail;
+    }
+    const error = friendlyError(response.status, detail);
+    error.retryable = response.retryable;
+    logError(`Request failed: ${endpoint}`, error);
+    throw error;
+  }
+
+  log(`Response from ${endpoint}:`, response.body);
+  return (response.status === 204 ? undefined : response.body) as T;
+}
+
+/**
+ * JSON request, proxied through the app in Tauri production, fetched otherwise
+ */
+async function apiRequest<T>(
+  method: string,
+  endpoint: string,
+  body?: unknown,
+  options: RequestOptions = {}
+): Promise<T> {
+  if (IS_TAURI_PRODUCTION && !import.meta.env.VITE_API_BASE_URL) {
+    return proxyFetch<T>(method, endpoint, body, options);
+  }
+  if (body === undefined) {
+    return apiFetch<T>(endpoint, { method });
+  }
+  return apiFetch<T>(endpoint, {
+    method,
+    headers: {
+      'Content-Type': 'application/json',
+    },
+    body: JSON.stringify(body),
+  });
 }
 
 /**
@@ -120,6 +1079,7 @@ export async function apiFetch<T>(
     const response = await fetch(url, {
       ...options,
       headers: {
+        ...(await authHeaders()),
         ...options.headers,
       },
     });
@@ -152,21 +1112,47 @@ export async function apiFetch<T>(
 /**
  * GET request
  */
-export async function apiGet<T>(endpoint: string): Promise<T> {
-  return apiFetch<T>(endpoint, { method: 'GET' });
+export async function apiGet<T>(endpoint: string, options: RequestOptions = {}): Promise<T> {
+  return apiRequest<T>('GET', endpoint, undefined, options);
+}
+
+/**
+ * GET a binary resource (e.g. a PDF preview) as bytes
+ */
+export async function apiGetBytes(
+  endpoint: string,
+  options: RequestOptions = {}
+): Promise<Uint8Array> {
+  if (IS_TAURI_PRODUCTION && !import.meta.env.VITE_API_BASE_URL) {
+    const encoded = await proxyFetch<string>('GET', endpoint, undefined, options, 'base64');
+    return Uint8Array.from(atob(encoded), (c) => c.charCodeAt(0));
+  }
+  const url = buildUrl(endpoint);
+  log(`GET ${url}`);
+  let response: Response;
+  try {
+    response = await fetch(url, { headers: await authHeaders() });
+  } catch (error) {
+    logError('Network error', error);
+    throw new ApiException(0, 'Connection error. Please check that the backend is running.');
+  }
+  if (!response.ok) {
+    const error = await parseError(response);
+    logError(`Request failed: ${endpoint}`, error);
+    throw error;
+  }
+  return new Uint8Array(await response.arrayBuffer());
 }
 
 /**
  * POST request with JSON body
  */
-export async function apiPost<T>(endpoint: string, body?: unknown): Promise<T> {
-  return apiFetch<T>(endpoint, {
-    method: 'POST',
-    headers: {
-      'Content-Type': 'application/json',
-    },
-    body: body ? JSON.stringify(body) : undefined,
-  });
+export async function apiPost<T>(
+  endpoint: string,
+  body?: unknown,
+  options: RequestOptions = {}
+): Promise<T> {
+  return apiRequest<T>('POST', endpoint, body || undefined, options);
 }
 
 /**
@@ -180,6 +1166,7 @@ export async function apiPostForm<T>(endpoint: string, formData: FormData): Prom
   try {
     const response = await fetch(url, {
       method: 'POST',
+      headers: await authHeaders(),
       body: formData,
       // Don't set Content-Type header - browser will set it with boundary
     });
@@ -207,18 +1194,12 @@ export async function apiPostForm<T>(endpoint: string, formData: FormData): Prom
  * PUT request with JSON body
  */
 export async function apiPut<T>(endpoint: string, body?: unknown): Promise<T> {
-  return apiFetch<T>(endpoint, {
-    method: 'PUT',
-    headers: {
-      'Content-Type': 'application/json',
-    },
-    body: body ? JSON.stringify(body) : undefined,
-  });
+  return apiRequest<T>('PUT', endpoint, body || undefined);
 }
 
 /**
  * DELETE request
  */
 export async function apiDelete<T = void>(endpoint: string): Promise<T> {
-  return apiFetch<T>(endpoint, { method: 'DELETE' });
+  return apiRequest<T>('DELETE', endpoint);
 }
@@ -23,17 +23,24 @@ export * from './types';
 export {
   // Two-step workflow
   analyzeDocument,
+  uploadInvoice,
+  cancelUpload,
   processJob,
   getJobStatus,
+  watchJob,
   processFile,
   getJobImageUrl,
   cleanupJob,
   // Invoice CRUD
   listInvoices,
+  listInvoicePage,
+  onInvoicesUpdated,
+  searchInvoices,
   getInvoice,
   deleteInvoice,
   updateInvoice,
   getInvoiceDocumentUrl,
+  downloadInvoiceDocument,
   // Other documents
   listOtherDocuments,
   getOtherDocument,
@@ -50,3 +57,98 @@ export {
 } from './invoices';
 
 export type { FileProcessingResult } from './invoices';
+
+export {
+  newRequestId,
+  cancelBackendRequest,
+  invalidateCache,
+  getCacheStats,
+  purgeCache,
+  onCachePurged,
+  setClearCachesOnExit,
+  watchBackendLog,
+  openBackendConsole,
+  getLogBacklog,
+  onShellError,
+  getBackendLogs,
+  setLogRetention,
+  openLogsFolder,
+  openDataFolder,
+  getDataDir,
+  setDataDir,
+  onDataDirLocked,
+  onLegacyDataFound,
+  getLegacyData,
+  migrateLegacyData,
+  getLogLevel,
+  setLogLevel,
+  createSupportBundle,
+  exportLogs,
+  backupData,
+  exportDatabase,
+  restoreData,
+  prepareForUpdate,
+  listPreUpdateBackups,
+  onPreUpdateBackup,
+  setPreUpdateBackupsKept,
+  requestFactoryReset,
+  factoryReset,
+  collectDiagnostics,
+  copyDiagnosticsToClipboard,
+  setTelemetryEnabled,
+  getTelemetryPreview,
+  getStartupMetrics,
+  getBackendResourceUsage,
+  getResourceThresholds,
+  setResourceThresholds,
+  onResourceWarning,
+  getDiskSpace,
+  getDiskSpaceThresholds,
+  setDiskSpaceThresholds,
+  onLowDiskSpace,
+  getStorageUsage,
+} from './client';
+export type {
+  RequestOptions,
+  TimeoutClass,
+  CacheStats,
+  CacheKind,
+  PurgedCache,
+  CachePurge,
+  BackendLogBatch,
+  BackendLogRecord,
+  BackendLogTail,
+  LogRetention,
+  OpenFolderError,
+  DataDir,
+  DataDirMigrationProgress,
+  DataDirLocked,
+  LegacyDataFound,
+  LegacyImport,
+  LogLevel,
+  LogLevels,
+  SupportBundle,
+  LogExport,
+  DataBackup,
+  BackupProgress,
+  DatabaseExport,
+  DatabaseExportError,
+  RestoreOutcome,
+  PreUpdateBackup,
+  FactoryResetRequest,
+  FactoryResetOptions,
+  FactoryReset,
+  RestoreProgress,
+  Diagnostics,
+  StartupRun,
+  BackendResources,
+  ResourceThresholds,
+  ResourceWarning,
+  DiskSpace,
+  DiskSpaceThresholds,
+  StorageUsage,
+  TelemetryEvent,
+  TelemetryPreview,
+  ShellError,
+  Probe,
+} from './client';
@@ -17,7 +17,9 @@
  * Two-step workflow: Analyze -> Process
  */
 
-import { apiGet, apiPost, apiPostForm, apiDelete, apiPut, buildUrl } from './client';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { apiGet, apiPost, apiPostForm, apiDelete, apiPut, buildResourceUrl } from './client';
 import type {
   AnalyzeResponse,
   ProcessRequest,
@@ -32,6 +34,10 @@ import type {
   ApiKeyStoreResponse,
   ApiKeysStatusResponse,
   Pipeline,
+  DownloadResult,
+  InvoicePage,
+  InvoiceQuery,
+  InvoiceSearchPage,
   UserPreference,
 } from './types';
 
@@ -49,6 +55,21 @@ export async function analyzeDocument(file: File): Promise<AnalyzeResponse> {
   return apiPostForm<AnalyzeResponse>('/analyze', formData);
 }
 
+/**
+ * Step 1, for a file on disk (Tauri only): the app streams it to the backend
+ * and emits `upload-progress` events. Rejects with an UploadError
+ */
+export async function uploadInvoice(path: string, uploadId?: string): Promise<AnalyzeResponse> {
+  return invoke<AnalyzeResponse>('upload_invoice', { path, uploadId });
+}
+
+/**
+ * Cancel an upload started with uploadInvoice
+ */
+export async function cancelUpload(uploadId: string): Promise<void> {
+  await invoke('cancel_upload', { uploadId });
+}
+
 /**
  * Step 2: Process a job with the chosen pipeline
  * Uses the job_id from analyzeDocument to extract invoice data
@@ -65,7 +86,8 @@ export async function processJob(
     save_to_db: saveToDb,
     user_preference: userPreference,
   };
-  return apiPost<ProcessResponse>('/process', request);
+  // Extraction can take minutes on slow machines
+  return apiPost<ProcessResponse>('/process', request, { timeoutClass: 'long_running' });
 }
 
 /**
@@ -89,6 +111,36 @@ export async function listInvoices(
   return apiGet<InvoiceResponse[]>(`/invoices?skip=${skip}&limit=${limit}`);
 }
 
+/**
+ * One page of invoices (Tauri only), cached by the app. Pages start at 0;
+ * `filter` matches provider, invoice number and file name
+ */
+export async function listInvoicePage(
+  page: number = 0,
+  pageSize: number = 50,
+  filter?: string
+): Promise<InvoicePage> {
+  return invoke<InvoicePage>('list_invoices', { page, pageSize, filter });
+}
+
+/**
+ * Follow refreshed invoice pages the app fetched in the background (Tauri only).
+ * The returned function stops listening
+ */
+export async function onInvoicesUpdated(
+  onPage: (page: InvoicePage) => void
+): Promise<() => void> {
+  return listen<InvoicePage>('invoices-updated', (event) => onPage(event.payload));
+}
+
+/**
+ * Search invoices by vendor, amount and date (Tauri only). The app checks the
+ * query first and rejects with a SearchError
+ */
+export async function searchInvoices(query: InvoiceQuery): Promise<InvoiceSearchPage> {
+  return invoke<InvoiceSearchPage>('search_invoices', { query });
+}
+
 /**
  * Get a specific invoice by ID
  */
@@ -117,14 +169,48 @@ export async function updateInvoice(
  * Get the URL for an invoice's original document
  */
 export function getInvoiceDocumentUrl(id: number): string {
-  return buildUrl(`/invoices/${id}/document`);
+  return buildResourceUrl(`/invoices/${id}/document`);
+}
+
+/**
+ * Save an invoice's original document to `destination` (Tauri only), e.g. a path
+ * picked in a save dialog. The app streams it to disk and emits `download-progress`
+ */
+export async function downloadInvoiceDocument(
+  id: number,
+  destination: string,
+  requestId?: string
+): Promise<DownloadResult> {
+  return invoke<DownloadResult>('download_to_file', {
+    path: `/api/v1/invoices/${id}/document`,
+    destination,
+    requestId,
+  });
+}
+
+/**
+ * Follow a job's status as the app relays it from the backend (Tauri only).
+ * Calls `onStatus` with each update; the returned function stops listening
+ */
+export async function watchJob(
+  jobId: string,
+  onStatus: (status: JobStatusResponse) => void
+): Promise<() => void> {
+  const unlisten = await listen<JobStatusResponse>(`job-progress:${jobId}`, (event) => {
+    onStatus(event.payload);
+  });
+  await invoke('subscribe_job_events', { jobId });
+  return () => {
+    unlisten();
+    invoke('unsubscribe_job_events', { jobId });
+  };
 }
 
 /**
  * Get the URL for a job's page image (used during review)
  */
 export function getJobImageUrl(jobId: string, page: number = 0): string {
-  return buildUrl(`/jobs/${jobId}/image?page=${page}`);
+  return buildResourceUrl(`/jobs/${jobId}/image?page=${page}`);
 }
 
 /**
@@ -228,6 +228,80 @@ export interface ApiKeysStatusResponse {
   console_url: string;
 }
 
+// ============================================================================
+// Streaming Upload Types
+// ============================================================================
+
+export interface UploadProgress {
+  upload_id: string;
+  path: string;
+  sent_bytes: number;
+  total_bytes: number;
+}
+
+export type UploadError =
+  | { kind: 'invalid_file'; message: string }
+  | { kind: 'unreadable'; message: string }
+  | { kind: 'rejected'; status: number; message: string }
+  | { kind: 'connection_lost'; message: string }
+  | { kind: 'cancelled'; message: string }
+  | { kind: 'low_disk_space'; free_bytes: number; floor_bytes: number; message: string };
+
+// ============================================================================
+// Streaming Download Types
+// ============================================================================
+
+export interface DownloadProgress {
+  path: string;
+  destination: string;
+  received_bytes: number;
+  total_bytes: number | null;
+}
+
+export interface DownloadResult {
+  bytes: number;
+  sha256: string;
+}
+
+export interface InvoicePage {
+  page: number;
+  page_size: number;
+  filter: string | null;
+  invoices: InvoiceResponse[];
+  has_more: boolean;
+  /** Whether the app answered from its cache; `invoices-updated` follows a revalidation */
+  debug: {
+    hit: boolean;
+    age_ms: number;
+    revalidating: boolean;
+  };
+}
+
+/** Criteria of searchInvoices; fields left out don't filter. Dates as YYYY-MM-DD */
+export interface InvoiceQuery {
+  vendor?: string;
+  min_amount?: number;
+  max_amount?: number;
+  date_from?: string;
+  date_to?: string;
+  page?: number;
+  page_size?: number;
+}
+
+export interface InvoiceSearchPage {
+  invoices: InvoiceResponse[];
+  total: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+}
+
+/** Why searchInvoices failed; `invalid` names the query field at fault */
+export type SearchError =
+  | { kind: 'invalid'; field: string; message: string }
+  | { kind: 'rejected'; status: number; message: string }
+  | { kind: 'connection_lost'; message: string };
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -240,11 +314,16 @@ export interface ApiError {
 export class ApiException extends Error {
   status: number;
   detail: string;
+  /** Sending the same request again may work, e.g. while the backend restarts */
+  retryable: boolean;
+  /** Aborted with cancelBackendRequest */
+  cancelled: boolean = false;
 
-  constructor(status: number, detail: string) {
+  constructor(status: number, detail: string, retryable: boolean = false) {
     super(detail);
     this.name = 'ApiException';
     this.status = status;
     this.detail = detail;
+    this.retryable = retryable;
   }
 }
@@ -15,5 +15,8 @@
 import { createApp } from "vue";
 import "./assets/main.css";
 import App from "./App.vue";
+import BackendConsole from "./components/BackendConsole.vue";
 
-createApp(App).mount("#app");
+// The backend console window loads the same page with `?view=backend-console`.
+const view = new URLSearchParams(window.location.search).get("view");
+createApp(view === "backend-console" ? BackendConsole : App).mount("#app");